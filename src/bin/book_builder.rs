@@ -0,0 +1,74 @@
+use chess_engine_revive::pgn::{parse_pgn, PgnGame};
+use chess_engine_revive::polyglot::BookBuilder;
+
+const USAGE: &str = "Usage: book_builder [options] <output.bin> <input.pgn>...
+
+Options:
+    --max-ply <plies>      Number of plies from the start of each game to add (default 24)
+    --min-games <games>    Minimum number of games a move must be played in (default 1)
+    --min-score <percent>  Minimum score a move must achieve for the team playing it (default 0)
+    --min-elo <rating>     Minimum rating of a player for their moves to be added (default 0)";
+
+fn main() {
+    let mut builder: BookBuilder = BookBuilder::new();
+    let mut paths: Vec<String> = Vec::new();
+
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        if !argument.starts_with("--") {
+            paths.push(argument);
+            continue;
+        }
+
+        let value: String = arguments.next().unwrap_or_else(|| exit_with_usage());
+        builder = match argument.as_str() {
+            "--max-ply" => builder.with_max_ply(parse_or_exit(&value)),
+            "--min-games" => builder.with_min_games(parse_or_exit(&value)),
+            "--min-score" => builder.with_min_score(parse_or_exit::<f64>(&value) / 100.0),
+            "--min-elo" => builder.with_min_elo(parse_or_exit(&value)),
+            _ => exit_with_usage(),
+        };
+    }
+
+    if paths.len() < 2 {
+        exit_with_usage();
+    }
+    let output_path: String = paths.remove(0);
+
+    let mut games_read: usize = 0;
+    let mut games_used: usize = 0;
+    for path in paths.iter() {
+        let text: String = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) => {
+                eprintln!("Failed to read {}: {}", path, error);
+                std::process::exit(1);
+            }
+        };
+
+        let games: Vec<PgnGame> = parse_pgn(&text);
+        games_read += games.len();
+        games_used += games.iter().filter(|game| builder.add_game(game)).count();
+    }
+
+    if let Err(error) = builder.write(&output_path) {
+        eprintln!("Failed to write {}: {:?}", output_path, error);
+        std::process::exit(1);
+    }
+    println!(
+        "Read {} games, used {} games, wrote {} entries to {}",
+        games_read,
+        games_used,
+        builder.entries().len(),
+        output_path
+    );
+}
+
+fn parse_or_exit<T: std::str::FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
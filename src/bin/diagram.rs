@@ -0,0 +1,123 @@
+use chess_engine_revive::board::{Board, Fen};
+use chess_engine_revive::coordinate::Coordinate;
+use chess_engine_revive::moves::Move;
+use chess_engine_revive::piece::Team;
+use chess_engine_revive::svg::{PieceSet, SvgRenderer, SvgTheme};
+
+const USAGE: &str = "Usage: diagram [options] [output.svg]
+
+Draws a position as an SVG diagram, written to the output file or to the standard output.
+
+Options:
+    --fen <fen>              Position to draw (default the starting position)
+    --moves <uci>...         Moves played from the position before it is drawn, separated by spaces
+    --size <pixels>          Width and height of the diagram (default 360)
+    --theme <name>           Colors of the board: brown, blue, or green (default brown)
+    --pieces <name>          Pieces to draw: glyphs or letters (default glyphs)
+    --arrow <uci>            Arrow between two squares, such as e2e4 (may be repeated)
+    --circle <square>        Circle around a square, such as e4 (may be repeated)
+    --highlight <square>     Square to highlight (may be repeated)
+    --flip                   Draw the board from the side of black
+    --no-coordinates         Leave out the letters and numbers of the squares";
+
+fn main() {
+    let mut board: Board = Board::new();
+    let mut moves: String = String::new();
+    let mut renderer: SvgRenderer = SvgRenderer::new();
+    let mut paths: Vec<String> = Vec::new();
+
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        if !argument.starts_with("--") {
+            paths.push(argument);
+            continue;
+        }
+        match argument.as_str() {
+            "--flip" => {
+                renderer = renderer.with_orientation(Team::Black);
+                continue;
+            }
+            "--no-coordinates" => {
+                renderer = renderer.with_coordinates(false);
+                continue;
+            }
+            _ => {}
+        }
+
+        let value: String = arguments.next().unwrap_or_else(|| exit_with_usage());
+        renderer = match argument.as_str() {
+            "--fen" => {
+                board = Board::try_new_with_fen(Fen { state: value }).unwrap_or_else(|error| {
+                    eprintln!("Invalid FEN: {:?}", error);
+                    std::process::exit(1);
+                });
+                renderer
+            }
+            "--moves" => {
+                moves = value;
+                renderer
+            }
+            "--size" => renderer.with_size(parse_or_exit(&value)),
+            "--theme" => renderer.with_theme(match value.as_str() {
+                "brown" => SvgTheme::brown(),
+                "blue" => SvgTheme::blue(),
+                "green" => SvgTheme::green(),
+                _ => exit_with_usage(),
+            }),
+            "--pieces" => renderer.with_piece_set(match value.as_str() {
+                "glyphs" => PieceSet::Glyphs,
+                "letters" => PieceSet::Letters,
+                _ => exit_with_usage(),
+            }),
+            "--arrow" => {
+                let (from, to) = parse_squares_or_exit(&value);
+                renderer.with_arrow(from, to)
+            }
+            "--circle" => renderer.with_circle(parse_square_or_exit(&value)),
+            "--highlight" => renderer.with_highlight(parse_square_or_exit(&value)),
+            _ => exit_with_usage(),
+        };
+    }
+    if paths.len() > 1 {
+        exit_with_usage();
+    }
+
+    for uci in moves.split_whitespace() {
+        let result = board.parse_uci(uci).and_then(|chess_move: Move| board.make_move(&chess_move));
+        if let Err(error) = result {
+            eprintln!("Invalid move {}: {:?}", uci, error);
+            std::process::exit(1);
+        }
+    }
+
+    let svg: String = renderer.render(&board);
+    match paths.first() {
+        Some(path) => std::fs::write(path, svg).unwrap_or_else(|error| {
+            eprintln!("Failed to write {}: {}", path, error);
+            std::process::exit(1);
+        }),
+        None => print!("{}", svg),
+    }
+}
+
+/// Parses a square written as in UCI, such as `e4`.
+fn parse_square_or_exit(value: &str) -> Coordinate {
+    Coordinate::try_from(value.to_uppercase().as_str()).unwrap_or_else(|_| exit_with_usage())
+}
+
+/// Parses two squares written one after the other, such as `e2e4`.
+fn parse_squares_or_exit(value: &str) -> (Coordinate, Coordinate) {
+    if value.len() != 4 || !value.is_ascii() {
+        exit_with_usage();
+    }
+    (parse_square_or_exit(&value[..2]), parse_square_or_exit(&value[2..]))
+}
+
+fn parse_or_exit<T: std::str::FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
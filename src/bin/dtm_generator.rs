@@ -0,0 +1,43 @@
+use chess_engine_revive::dtm::DtmTablebase;
+
+const USAGE: &str = "Usage: dtm_generator <output-directory> <material>...
+
+Generates distance to mate tables for endgames of up to four pieces, such as KQvK or KBNvK, along with the tables of
+the endgames they turn into. Tables already in the output directory are reused.";
+
+fn main() {
+    let mut arguments: Vec<String> = std::env::args().skip(1).collect();
+    if arguments.len() < 2 {
+        exit_with_usage();
+    }
+    let output_directory: String = arguments.remove(0);
+
+    let mut tablebase: DtmTablebase = match std::fs::create_dir_all(&output_directory) {
+        Ok(()) => DtmTablebase::open(&output_directory).unwrap_or_else(|error| {
+            eprintln!("Failed to read the tables in {}: {:?}", output_directory, error);
+            std::process::exit(1);
+        }),
+        Err(error) => {
+            eprintln!("Failed to create {}: {}", output_directory, error);
+            std::process::exit(1);
+        }
+    };
+
+    for material in arguments.iter() {
+        if let Err(error) = tablebase.generate(material) {
+            eprintln!("Failed to generate {}: {:?}", material, error);
+            std::process::exit(1);
+        }
+        println!("Generated {}", material);
+    }
+
+    if let Err(error) = tablebase.write(&output_directory) {
+        eprintln!("Failed to write the tables to {}: {:?}", output_directory, error);
+        std::process::exit(1);
+    }
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,127 @@
+use chess_engine_revive::board::Board;
+use chess_engine_revive::epd::{parse_epd, Epd};
+use chess_engine_revive::moves::Move;
+use chess_engine_revive::search::{search, Score, SearchLimits, SearchResult};
+use std::time::Duration;
+
+const USAGE: &str = "Usage: epd_runner [options] <suite.epd>
+
+Searches every position of an EPD test suite, such as WAC or STS, and reports how many of them were solved. A position
+is solved when the move found is one of its best moves (bm) and none of its moves to avoid (am), or when a mate is found
+in at most the number of moves given by its direct mate (dm).
+
+Options:
+    --time <milliseconds>  Time to search each position for (default 1000)
+    --depth <plies>        Maximum depth to search each position to";
+
+fn main() {
+    let mut limits: SearchLimits = SearchLimits::new();
+    let mut time: u64 = 1000;
+    let mut paths: Vec<String> = Vec::new();
+
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        if !argument.starts_with("--") {
+            paths.push(argument);
+            continue;
+        }
+
+        let value: String = arguments.next().unwrap_or_else(|| exit_with_usage());
+        match argument.as_str() {
+            "--time" => time = parse_or_exit(&value),
+            "--depth" => limits = limits.with_depth(parse_or_exit(&value)),
+            _ => exit_with_usage(),
+        }
+    }
+    if paths.len() != 1 {
+        exit_with_usage();
+    }
+    let limits: SearchLimits = limits.with_time(Duration::from_millis(time));
+
+    let text: String = std::fs::read_to_string(&paths[0]).unwrap_or_else(|error| {
+        eprintln!("Failed to read {}: {}", paths[0], error);
+        std::process::exit(1);
+    });
+    let suite: Vec<Epd> = parse_epd(&text).unwrap_or_else(|error| {
+        eprintln!("Failed to parse {}: {:?}", paths[0], error);
+        std::process::exit(1);
+    });
+
+    let mut solved: usize = 0;
+    let mut tested: usize = 0;
+    for (index, epd) in suite.iter().enumerate() {
+        let id: String = epd.id().map_or_else(|| format!("#{}", index + 1), |id| id.to_string());
+        let board: Board = match epd.board() {
+            Ok(board) => board,
+            Err(error) => {
+                println!("{}: skipped, invalid position ({:?})", id, error);
+                continue;
+            }
+        };
+        let (best_moves, avoid_moves) = match (epd.best_moves(), epd.avoid_moves()) {
+            (Ok(best_moves), Ok(avoid_moves)) => (best_moves, avoid_moves),
+            (Err(error), _) | (_, Err(error)) => {
+                println!("{}: skipped, invalid move ({:?})", id, error);
+                continue;
+            }
+        };
+        let direct_mate: Option<u32> = epd.direct_mate();
+        if best_moves.is_empty() && avoid_moves.is_empty() && direct_mate.is_none() {
+            println!("{}: skipped, no solution given", id);
+            continue;
+        }
+
+        let result: SearchResult = search(&board, &limits);
+        let is_solved: bool = match (result.best_move, direct_mate) {
+            (_, Some(moves)) if best_moves.is_empty() && avoid_moves.is_empty() => {
+                matches!(result.score, Score::Mate(found) if found > 0 && found as u32 <= moves)
+            }
+            (Some(found), _) => {
+                (best_moves.is_empty() || best_moves.contains(&found)) && !avoid_moves.contains(&found)
+            }
+            (None, _) => false,
+        };
+
+        tested += 1;
+        if is_solved {
+            solved += 1;
+        }
+        println!(
+            "{}: {} {} ({}, depth {}, {} nodes){}",
+            id,
+            if is_solved { "solved" } else { "failed" },
+            result.best_move.map_or("-".to_string(), |found| board.san(&found)),
+            result.score,
+            result.depth,
+            result.nodes,
+            expected(&board, &best_moves, &avoid_moves, direct_mate),
+        );
+    }
+
+    println!("Solved {} of {} positions", solved, tested);
+}
+
+/// Describes the solution of a position for the report.
+fn expected(board: &Board, best_moves: &[Move], avoid_moves: &[Move], direct_mate: Option<u32>) -> String {
+    let sans = |moves: &[Move]| moves.iter().map(|chess_move| board.san(chess_move)).collect::<Vec<_>>().join(" ");
+    let mut parts: Vec<String> = Vec::new();
+    if !best_moves.is_empty() {
+        parts.push(format!("bm {}", sans(best_moves)));
+    }
+    if !avoid_moves.is_empty() {
+        parts.push(format!("am {}", sans(avoid_moves)));
+    }
+    if let Some(moves) = direct_mate {
+        parts.push(format!("dm {}", moves));
+    }
+    format!(" [{}]", parts.join("; "))
+}
+
+fn parse_or_exit<T: std::str::FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
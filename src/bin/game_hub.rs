@@ -0,0 +1,150 @@
+use chess_engine_revive::hub::{ClientMessage, ConnectionId, Hub, Outgoing, ServerMessage};
+use std::collections::HashMap;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tungstenite::{Message, WebSocket};
+
+const USAGE: &str = "Usage: game_hub [options]
+
+Pairs players in game rooms over WebSockets. Clients send and receive JSON messages, such as
+{\"type\": \"join\", \"room\": \"lobby\", \"minutes\": 5} and {\"type\": \"move\", \"move\": \"e4\"}.
+
+Options:
+    --host <address>  Address to listen on (default 127.0.0.1)
+    --port <port>     Port to listen on (default 9000)";
+
+/// How often the clocks are sent to the rooms.
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a connection waits for a message from its client before sending the messages waiting for it.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The hub and the channels that deliver its messages to the threads of the connections.
+#[derive(Default)]
+struct Shared {
+    hub: Hub,
+    senders: HashMap<ConnectionId, Sender<ServerMessage>>,
+}
+
+impl Shared {
+    /// Locks the shared state, even when the thread of another connection panicked while holding the lock, so that one
+    /// connection can not take down the clocks and every other connection with it.
+    fn lock(shared: &Mutex<Self>) -> MutexGuard<'_, Self> {
+        shared.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn dispatch(&self, outgoing: Vec<Outgoing>) {
+        for (connection, message) in outgoing {
+            if let Some(sender) = self.senders.get(&connection) {
+                sender.send(message).ok();
+            }
+        }
+    }
+}
+
+fn main() {
+    let mut host: String = "127.0.0.1".to_string();
+    let mut port: u16 = 9000;
+
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        let value: String = arguments.next().unwrap_or_else(|| exit_with_usage());
+        match argument.as_str() {
+            "--host" => host = value,
+            "--port" => port = parse_or_exit(&value),
+            _ => exit_with_usage(),
+        }
+    }
+
+    let address: String = format!("{}:{}", host, port);
+    let listener: TcpListener = TcpListener::bind(&address).unwrap_or_else(|error| {
+        eprintln!("Failed to listen on {}: {}", address, error);
+        std::process::exit(1);
+    });
+    println!("Hosting games on ws://{}", address);
+
+    let shared: Arc<Mutex<Shared>> = Arc::new(Mutex::new(Shared::default()));
+    let ticking: Arc<Mutex<Shared>> = shared.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TICK_INTERVAL);
+        let mut shared = Shared::lock(&ticking);
+        let outgoing: Vec<Outgoing> = shared.hub.tick();
+        shared.dispatch(outgoing);
+    });
+
+    for (connection, stream) in (0..).zip(listener.incoming()) {
+        let stream: TcpStream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        let shared: Arc<Mutex<Shared>> = shared.clone();
+        std::thread::spawn(move || serve(connection, stream, shared));
+    }
+}
+
+/// Relays the messages between a client and the hub until the client disconnects.
+fn serve(connection: ConnectionId, stream: TcpStream, shared: Arc<Mutex<Shared>>) {
+    let mut socket: WebSocket<TcpStream> = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(_) => return,
+    };
+    if socket.get_ref().set_read_timeout(Some(POLL_INTERVAL)).is_err() {
+        return;
+    }
+    let (sender, receiver): (Sender<ServerMessage>, Receiver<ServerMessage>) = channel();
+    Shared::lock(&shared).senders.insert(connection, sender);
+
+    loop {
+        let text: Option<String> = match socket.read() {
+            Ok(Message::Text(text)) => Some(text),
+            Ok(Message::Close(_)) => break,
+            Ok(_) => None,
+            Err(tungstenite::Error::Io(error))
+                if matches!(error.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+            {
+                None
+            }
+            Err(_) => break,
+        };
+
+        if let Some(text) = text {
+            match serde_json::from_str::<ClientMessage>(&text) {
+                Ok(message) => {
+                    let mut shared = Shared::lock(&shared);
+                    let outgoing: Vec<Outgoing> = shared.hub.handle(connection, message);
+                    shared.dispatch(outgoing);
+                }
+                Err(error) => {
+                    let message: String = format!("The message is not valid: {}", error);
+                    Shared::lock(&shared).dispatch(vec![(connection, ServerMessage::Error { message })]);
+                }
+            }
+        }
+
+        let mut connected: bool = true;
+        for message in receiver.try_iter() {
+            let text: String = serde_json::to_string(&message).expect("messages can always be written as JSON");
+            connected &= socket.write(Message::Text(text)).is_ok();
+        }
+        // Flushing also sends the replies to pings from the client.
+        if !connected || socket.flush().is_err() {
+            break;
+        }
+    }
+
+    let mut shared = Shared::lock(&shared);
+    shared.senders.remove(&connection);
+    let outgoing: Vec<Outgoing> = shared.hub.disconnect(connection);
+    shared.dispatch(outgoing);
+}
+
+fn parse_or_exit<T: std::str::FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,386 @@
+use chess_engine_revive::board::{Board, BoardError, Fen};
+use chess_engine_revive::moves::Move;
+use chess_engine_revive::pgn::PgnGame;
+use chess_engine_revive::piece::Team;
+use chess_engine_revive::variant::variant_by_name;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const USAGE: &str = "Usage: game_server [options]
+
+Serves games over a local HTTP JSON API. Games are kept in memory and are lost when the server stops.
+
+Options:
+    --host <address>  Address to listen on (default 127.0.0.1)
+    --port <port>     Port to listen on (default 8080)
+
+Endpoints:
+    POST   /games             Create a game, from {\"fen\", \"variant\", \"chess960\"} which are all optional
+    GET    /games             List the ids of the games
+    GET    /games/<id>        Get the state of a game
+    DELETE /games/<id>        Delete a game
+    POST   /games/<id>/moves  Make a move, from {\"move\"} in SAN or UCI
+    POST   /games/<id>/undo   Take back the last move
+    GET    /games/<id>/pgn    Export the game as PGN";
+
+fn main() {
+    let mut host: String = "127.0.0.1".to_string();
+    let mut port: u16 = 8080;
+
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        let value: String = arguments.next().unwrap_or_else(|| exit_with_usage());
+        match argument.as_str() {
+            "--host" => host = value,
+            "--port" => port = parse_or_exit(&value),
+            _ => exit_with_usage(),
+        }
+    }
+
+    let address: String = format!("{}:{}", host, port);
+    let server: Server = Server::http(&address).unwrap_or_else(|error| {
+        eprintln!("Failed to listen on {}: {}", address, error);
+        std::process::exit(1);
+    });
+    println!("Serving games on http://{}", address);
+
+    let mut store: GameStore = GameStore::default();
+    for mut request in server.incoming_requests() {
+        let mut body: String = String::new();
+        let reply: Reply = match request.as_reader().read_to_string(&mut body) {
+            // A request which makes the server panic is answered with an error instead of stopping the server.
+            Ok(_) => panic::catch_unwind(AssertUnwindSafe(|| store.handle(request.method(), request.url(), &body)))
+                .unwrap_or_else(|_| Reply::error(500, "The server failed to handle the request.")),
+            Err(_) => Reply::error(400, "The body of the request is not valid UTF-8."),
+        };
+        respond(request, reply);
+    }
+}
+
+/// The games being played, by their id.
+#[derive(Default)]
+struct GameStore {
+    games: BTreeMap<u64, Board>,
+    next_id: u64,
+}
+
+impl GameStore {
+    fn handle(&mut self, method: &Method, url: &str, body: &str) -> Reply {
+        let path: &str = url.split('?').next().unwrap_or_default();
+        let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+
+        match (method, segments.as_slice()) {
+            (Method::Options, _) => Reply { status: 204, body: String::new(), content_type: "text/plain" },
+            (Method::Get, ["games"]) => Reply::json(200, &self.games.keys().collect::<Vec<_>>()),
+            (Method::Post, ["games"]) => self.create(body),
+            (method, ["games", id, rest @ ..]) => {
+                let id: u64 = match id.parse() {
+                    Ok(id) if self.games.contains_key(&id) => id,
+                    _ => return Reply::error(404, "There is no game with that id."),
+                };
+                match (method, rest) {
+                    (Method::Get, []) => Reply::json(200, &GameState::new(id, &self.games[&id])),
+                    (Method::Delete, []) => {
+                        self.games.remove(&id);
+                        Reply { status: 204, body: String::new(), content_type: "text/plain" }
+                    }
+                    (Method::Post, ["moves"]) => self.make_move(id, body),
+                    (Method::Post, ["undo"]) => self.undo(id),
+                    (Method::Get, ["pgn"]) => self.pgn(id),
+                    _ => Reply::error(404, "There is no such endpoint."),
+                }
+            }
+            _ => Reply::error(404, "There is no such endpoint."),
+        }
+    }
+
+    fn create(&mut self, body: &str) -> Reply {
+        let request: CreateGame = match body.trim() {
+            "" => CreateGame::default(),
+            body => match serde_json::from_str(body) {
+                Ok(request) => request,
+                Err(error) => return Reply::error(400, &format!("The body is not a valid game: {}", error)),
+            },
+        };
+
+        let board: Result<Board, BoardError> = match (request.fen, request.chess960) {
+            (Some(_), Some(_)) => return Reply::error(400, "A game can start from either a FEN or a Chess960 index."),
+            (Some(fen), None) => Board::try_new_with_fen(Fen { state: fen }),
+            (None, Some(index)) => Board::new_chess960(index),
+            // Variants which start from their own position, such as Horde, start there unless a FEN is given.
+            (None, None) => match request.variant.as_deref().and_then(variant_by_name) {
+                Some(variant) => Board::try_new_with_fen(variant.starting_fen()),
+                None => Ok(Board::new()),
+            },
+        };
+        let mut board: Board = match board {
+            Ok(board) => board,
+            Err(error) => return Reply::board_error(&error),
+        };
+        if let Some(variant) = request.variant {
+            if let Err(error) = board.set_variant_by_name(&variant) {
+                return Reply::board_error(&error);
+            }
+        }
+
+        let id: u64 = self.next_id;
+        self.next_id += 1;
+        let reply: Reply = Reply::json(201, &GameState::new(id, &board));
+        self.games.insert(id, board);
+        reply
+    }
+
+    fn make_move(&mut self, id: u64, body: &str) -> Reply {
+        let request: MoveRequest = match serde_json::from_str(body) {
+            Ok(request) => request,
+            Err(error) => return Reply::error(400, &format!("The body is not a valid move: {}", error)),
+        };
+
+        let board: &mut Board = self.games.get_mut(&id).unwrap();
+        if board.winner().is_some() || board.is_draw() {
+            return Reply::error(409, "The game is over.");
+        }
+        let chess_move: Result<Move, BoardError> = board.parse_san(&request.chess_move).or_else(|error| {
+            board.parse_uci(&request.chess_move).map_err(|_| error)
+        });
+        match chess_move.and_then(|chess_move| board.make_move(&chess_move)) {
+            Ok(()) => Reply::json(200, &GameState::new(id, board)),
+            Err(error) => Reply::board_error(&error),
+        }
+    }
+
+    fn undo(&mut self, id: u64) -> Reply {
+        let board: &mut Board = self.games.get_mut(&id).unwrap();
+        match board.undo_move() {
+            Some(_) => Reply::json(200, &GameState::new(id, board)),
+            None => Reply::error(409, "There are no moves to take back."),
+        }
+    }
+
+    fn pgn(&self, id: u64) -> Reply {
+        let board: &Board = &self.games[&id];
+        let mut game: PgnGame = PgnGame::from_board(board, result(board));
+        game.set_tag("Event", &format!("Game {}", id));
+        Reply { status: 200, body: game.to_string(), content_type: "application/x-chess-pgn" }
+    }
+}
+
+/// The body of a request to create a game.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CreateGame {
+    fen: Option<String>,
+
+    /// The name of the variant, as given by `Variant::name`.
+    variant: Option<String>,
+
+    /// The index of the Chess960 starting position, from 0 to 959.
+    chess960: Option<u16>,
+}
+
+/// The body of a request to make a move.
+#[derive(Deserialize)]
+struct MoveRequest {
+    #[serde(rename = "move")]
+    chess_move: String,
+}
+
+/// The state of a game as it is sent to clients.
+#[derive(Serialize)]
+struct GameState {
+    id: u64,
+    fen: String,
+    variant: &'static str,
+    chess960: bool,
+    turn: Team,
+
+    /// Either `playing`, `checkmate`, `stalemate`, `draw`, or `won` when a team won by the rules of the variant.
+    status: &'static str,
+    winner: Option<Team>,
+    check: bool,
+
+    /// The moves made so far in standard algebraic notation, the last of which is repeated in `last_move`.
+    moves: Vec<String>,
+    last_move: Option<String>,
+    legal_moves: Vec<LegalMove>,
+
+    /// The result of the game in PGN notation.
+    result: &'static str,
+}
+
+#[derive(Serialize)]
+struct LegalMove {
+    uci: String,
+    san: String,
+}
+
+impl GameState {
+    fn new(id: u64, board: &Board) -> Self {
+        let winner: Option<Team> = board.winner();
+        let status: &'static str = match winner {
+            Some(_) if board.is_checkmate() => "checkmate",
+            Some(_) => "won",
+            None if board.is_draw() && board.is_stalemate() => "stalemate",
+            None if board.is_draw() => "draw",
+            None => "playing",
+        };
+        let legal_moves: Vec<LegalMove> = match status {
+            "playing" => board
+                .legal_moves()
+                .iter()
+                .map(|chess_move| LegalMove { uci: board.uci(chess_move), san: board.san(chess_move) })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let moves: Vec<String> = PgnGame::from_board(board, "*").moves;
+
+        Self {
+            id,
+            fen: board.fen().state,
+            variant: board.variant().name(),
+            chess960: board.is_chess960(),
+            turn: board.turn_to_play(),
+            status,
+            winner,
+            check: board.is_in_check(board.turn_to_play()),
+            last_move: moves.last().cloned(),
+            moves,
+            legal_moves,
+            result: result(board),
+        }
+    }
+}
+
+/// The result of the game in PGN notation.
+fn result(board: &Board) -> &'static str {
+    match board.winner() {
+        Some(Team::White) => "1-0",
+        Some(Team::Black) => "0-1",
+        None if board.is_draw() => "1/2-1/2",
+        None => "*",
+    }
+}
+
+/// A response to a request, before the headers shared by every response are added.
+struct Reply {
+    status: u16,
+    body: String,
+    content_type: &'static str,
+}
+
+impl Reply {
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(value).expect("responses can always be written as JSON"),
+            content_type: "application/json",
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &BTreeMap::from([("error", message)]))
+    }
+
+    fn board_error(error: &BoardError) -> Self {
+        Self::error(400, &error.to_string())
+    }
+}
+
+/// Sends the reply, allowing pages served from any origin to call the API so that frontends can be developed against
+/// it from their own development servers.
+fn respond(request: Request, reply: Reply) {
+    let headers: [(&str, &str); 4] = [
+        ("Content-Type", reply.content_type),
+        ("Access-Control-Allow-Origin", "*"),
+        ("Access-Control-Allow-Methods", "GET, POST, DELETE, OPTIONS"),
+        ("Access-Control-Allow-Headers", "Content-Type"),
+    ];
+    let mut response = Response::from_string(reply.body).with_status_code(reply.status);
+    for (name, value) in headers {
+        response.add_header(Header::from_bytes(name, value).unwrap());
+    }
+    if let Err(error) = request.respond(response) {
+        eprintln!("Failed to send a response: {}", error);
+    }
+}
+
+fn parse_or_exit<T: std::str::FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chess_engine_revive::variant::{Horde, Variant};
+
+    fn body(reply: &Reply) -> serde_json::Value {
+        serde_json::from_str(&reply.body).unwrap()
+    }
+
+    /// Creates a game from the body of the request and returns its id.
+    fn create(store: &mut GameStore, request: &str) -> u64 {
+        let reply: Reply = store.handle(&Method::Post, "/games", request);
+        assert_eq!(reply.status, 201, "{}", reply.body);
+        body(&reply)["id"].as_u64().unwrap()
+    }
+
+    fn play(store: &mut GameStore, id: u64, chess_move: &str) -> Reply {
+        let request: String = serde_json::json!({ "move": chess_move }).to_string();
+        store.handle(&Method::Post, &format!("/games/{}/moves", id), &request)
+    }
+
+    #[test]
+    fn games_are_created_played_and_undone() {
+        let mut store: GameStore = GameStore::default();
+        let id: u64 = create(&mut store, "");
+
+        let reply: Reply = play(&mut store, id, "e4");
+        assert_eq!(reply.status, 200);
+        assert_eq!(body(&reply)["moves"], serde_json::json!(["e4"]));
+        assert_eq!(play(&mut store, id, "e7e5").status, 200);
+        assert_eq!(play(&mut store, id, "e5").status, 400);
+
+        let reply: Reply = store.handle(&Method::Post, &format!("/games/{}/undo", id), "");
+        assert_eq!(body(&reply)["last_move"], "e4");
+        assert!(store.handle(&Method::Get, &format!("/games/{}/pgn", id), "").body.contains("1. e4 *"));
+        assert_eq!(store.handle(&Method::Delete, &format!("/games/{}", id), "").status, 204);
+        assert_eq!(store.handle(&Method::Get, &format!("/games/{}", id), "").status, 404);
+    }
+
+    #[test]
+    fn variants_start_from_their_own_position() {
+        let mut store: GameStore = GameStore::default();
+        let id: u64 = create(&mut store, r#"{"variant": "Horde"}"#);
+        let reply: Reply = store.handle(&Method::Get, &format!("/games/{}", id), "");
+        assert_eq!(body(&reply)["fen"], Horde.starting_fen().state);
+
+        let id: u64 = create(&mut store, r#"{"chess960": 518}"#);
+        let reply: Reply = store.handle(&Method::Get, &format!("/games/{}", id), "");
+        assert_eq!(body(&reply)["chess960"], true);
+    }
+
+    #[test]
+    fn malformed_requests_are_rejected() {
+        let mut store: GameStore = GameStore::default();
+        let id: u64 = create(&mut store, r#"{"variant": "Crazyhouse"}"#);
+        for chess_move in ["é", "N@é", "é@e4", "e2é", "ée2e4", "", "@", "Ke9"] {
+            assert_eq!(play(&mut store, id, chess_move).status, 400, "{:?}", chess_move);
+        }
+        assert_eq!(store.handle(&Method::Post, &format!("/games/{}/moves", id), "{").status, 400);
+        assert_eq!(store.handle(&Method::Post, "/games/999/moves", r#"{"move": "e4"}"#).status, 404);
+
+        let queens: String = "Q".repeat(256);
+        let fen: String = format!(r#"{{"fen": "4k3/8/8/8/8/8/8/4K3[{}] w - - 0 1"}}"#, queens);
+        for request in [fen.as_str(), r#"{"fen": "é"}"#, r#"{"variant": "Nonsense"}"#, r#"{"chess960": 960}"#, "["] {
+            assert_eq!(store.handle(&Method::Post, "/games", request).status, 400, "{}", request);
+        }
+        assert_eq!(store.handle(&Method::Get, "/games", "").body, format!("[{}]", id));
+    }
+}
@@ -0,0 +1,179 @@
+use chess_engine_revive::board::{Board, Fen};
+use chess_engine_revive::hub::{ClientMessage, JoinRequest, RoomState, ServerMessage};
+use std::io::BufRead;
+use std::net::TcpStream;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::time::Duration;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+const USAGE: &str = "Usage: hub_client [options]
+
+Connects to a game_hub from the terminal, which is enough to play a game end to end with two of them.
+
+Options:
+    --url <url>  Address of the hub (default ws://127.0.0.1:9000)
+
+Commands:
+    join <room> [minutes] [increment]  Join a room, creating it with a clock when it does not exist yet
+    spectate <room>                    Watch the game in a room
+    rejoin <room> <token>              Take back a seat after reconnecting
+    <move>                             Make a move in SAN or UCI
+    resign, draw, accept-draw, decline-draw, takeback, accept-takeback, decline-takeback
+    {...}                              Send a message written in JSON";
+
+/// How long reading from the hub waits before checking for commands.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+fn main() {
+    let mut url: String = "ws://127.0.0.1:9000".to_string();
+
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        let value: String = arguments.next().unwrap_or_else(|| exit_with_usage());
+        match argument.as_str() {
+            "--url" => url = value,
+            _ => exit_with_usage(),
+        }
+    }
+
+    let (mut socket, _) = tungstenite::connect(url.as_str()).unwrap_or_else(|error| {
+        eprintln!("Failed to connect to {}: {}", url, error);
+        std::process::exit(1);
+    });
+    if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+        stream.set_read_timeout(Some(POLL_INTERVAL)).ok();
+    }
+
+    let (sender, commands): (_, Receiver<String>) = channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => match serde_json::from_str::<ServerMessage>(&text) {
+                Ok(message) => print_message(&message),
+                Err(_) => println!("{}", text),
+            },
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(error))
+                if matches!(error.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+            Err(error) => {
+                eprintln!("Lost the connection: {}", error);
+                break;
+            }
+        }
+
+        let line: String = match commands.try_recv() {
+            Ok(line) => line,
+            Err(TryRecvError::Empty) => continue,
+            Err(TryRecvError::Disconnected) => break,
+        };
+        let message: Option<ClientMessage> = parse_command(line.trim());
+        match message.map(|message| serde_json::to_string(&message).unwrap()) {
+            Some(text) => {
+                if let Err(error) = socket.send(Message::Text(text)) {
+                    eprintln!("Failed to send: {}", error);
+                }
+            }
+            None if line.trim().starts_with('{') => {
+                socket.send(Message::Text(line.trim().to_string())).ok();
+            }
+            None if line.trim().is_empty() => {}
+            None => println!("{}", USAGE),
+        }
+    }
+    close(&mut socket);
+}
+
+fn parse_command(line: &str) -> Option<ClientMessage> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let message: ClientMessage = match words.as_slice() {
+        ["join", room, rest @ ..] => ClientMessage::Join(JoinRequest {
+            room: room.to_string(),
+            minutes: rest.first().and_then(|minutes| minutes.parse().ok()),
+            increment: rest.get(1).and_then(|increment| increment.parse().ok()),
+            ..JoinRequest::default()
+        }),
+        ["spectate", room] => ClientMessage::Join(JoinRequest {
+            room: room.to_string(),
+            spectate: true,
+            ..JoinRequest::default()
+        }),
+        ["rejoin", room, token] => ClientMessage::Join(JoinRequest {
+            room: room.to_string(),
+            token: Some(token.to_string()),
+            ..JoinRequest::default()
+        }),
+        ["resign"] => ClientMessage::Resign,
+        ["draw"] => ClientMessage::OfferDraw,
+        ["accept-draw"] => ClientMessage::AcceptDraw,
+        ["decline-draw"] => ClientMessage::DeclineDraw,
+        ["takeback"] => ClientMessage::RequestTakeback,
+        ["accept-takeback"] => ClientMessage::AcceptTakeback,
+        ["decline-takeback"] => ClientMessage::DeclineTakeback,
+        [chess_move] if !chess_move.starts_with('{') => ClientMessage::Move { chess_move: chess_move.to_string() },
+        _ => return None,
+    };
+    Some(message)
+}
+
+fn print_message(message: &ServerMessage) {
+    match message {
+        ServerMessage::Joined { seat, token, state } => {
+            println!("Joined {} as {:?}{}", state.room, seat, token.as_ref().map_or(String::new(), |token| {
+                format!(", rejoin with the token {}", token)
+            }));
+            print_state(state);
+        }
+        ServerMessage::State { state } => print_state(state),
+        ServerMessage::Move { san, state, .. } => {
+            println!("{:?} played {}", state.turn.opponent(), san);
+            print_state(state);
+        }
+        ServerMessage::Clock { clock } => {
+            println!("Clock: white {:.1}s, black {:.1}s", clock.white as f64 / 1000.0, clock.black as f64 / 1000.0)
+        }
+        ServerMessage::DrawOffered { by } => println!("{:?} offers a draw", by),
+        ServerMessage::DrawDeclined { by } => println!("{:?} declines the draw", by),
+        ServerMessage::TakebackRequested { by } => println!("{:?} asks to take back their move", by),
+        ServerMessage::TakebackDeclined { by } => println!("{:?} declines the takeback", by),
+        ServerMessage::TakenBack { state } => {
+            println!("The move was taken back");
+            print_state(state);
+        }
+        ServerMessage::GameOver { result, reason, state } => {
+            print_state(state);
+            println!("Game over: {} by {}", result, reason);
+        }
+        ServerMessage::Error { message } => println!("Error: {}", message),
+    }
+}
+
+fn print_state(state: &RoomState) {
+    let board: Board = Board::new_with_fen(Fen { state: state.fen.clone() });
+    let name = |player: &Option<_>| match player {
+        Some(chess_engine_revive::hub::PlayerState { name, connected: true }) => name.clone(),
+        Some(chess_engine_revive::hub::PlayerState { name, connected: false }) => format!("{} (disconnected)", name),
+        None => "(waiting)".to_string(),
+    };
+    println!("{}\n", board);
+    println!("White: {}, Black: {}, spectators: {}", name(&state.white), name(&state.black), state.spectators);
+    println!("Moves: {}", state.moves.join(" "));
+}
+
+fn close(socket: &mut WebSocket<MaybeTlsStream<TcpStream>>) {
+    socket.close(None).ok();
+    socket.flush().ok();
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
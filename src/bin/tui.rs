@@ -0,0 +1,565 @@
+use chess_engine_revive::board::{Board, Fen};
+use chess_engine_revive::clock::{Clock, Outcome, TimeControl};
+use chess_engine_revive::coordinate::Coordinate;
+use chess_engine_revive::moves::Move;
+use chess_engine_revive::pgn::PgnGame;
+use chess_engine_revive::piece::{Piece, PieceClass, Team};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const USAGE: &str = "Usage: tui [options]
+
+Plays a game of chess between two players in a full screen terminal interface. The game is printed as PGN on exit.
+
+Options:
+    --fen <fen>              Position to start the game from
+    --clock <minutes>        Time on the clock of each player, without a clock when left out
+    --increment <seconds>    Time added to the clock of a player after each of their moves (default 0)
+
+Keys:
+    arrows, h j k l          Move the cursor
+    enter, space             Select a piece, then the square to move it to
+    escape, x                Cancel the selection
+    u                        Take back the last move
+    f                        Turn the board around
+    q, ctrl-c                Quit";
+
+const LIGHT_SQUARE: u8 = 180;
+const DARK_SQUARE: u8 = 137;
+const LIGHT_LAST_MOVE: u8 = 186;
+const DARK_LAST_MOVE: u8 = 143;
+const SELECTED_SQUARE: u8 = 74;
+const CAPTURE_TARGET: u8 = 167;
+const CHECKED_KING: u8 = 160;
+const WHITE_PIECE: u8 = 231;
+const BLACK_PIECE: u8 = 16;
+const TARGET_MARKER: u8 = 22;
+
+/// How long reading a key waits before the screen is drawn again, so that the clocks keep counting down.
+const REFRESH_TENTHS_OF_SECOND: &str = "1";
+
+/// The number of lines that the board takes on the screen, with the labels of the columns above and below it.
+const BOARD_LINES: usize = 10;
+
+/// The width of the column with the players beside the board, before the move list.
+const PLAYER_COLUMN_WIDTH: usize = 24;
+
+fn main() {
+    let mut board: Board = Board::new();
+    let mut clock_minutes: Option<f64> = None;
+    let mut increment_seconds: f64 = 0.0;
+
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        let value: String = arguments.next().unwrap_or_else(|| exit_with_usage());
+        match argument.as_str() {
+            "--fen" => {
+                board = Board::try_new_with_fen(Fen { state: value }).unwrap_or_else(|error| {
+                    eprintln!("Invalid FEN: {:?}", error);
+                    std::process::exit(1);
+                })
+            }
+            "--clock" => clock_minutes = Some(parse_or_exit(&value)),
+            "--increment" => increment_seconds = parse_or_exit(&value),
+            _ => exit_with_usage(),
+        }
+    }
+    let clock: Option<Clock> = clock_minutes.map(|minutes| {
+        let time: Duration = Duration::from_secs_f64(minutes * 60.0);
+        let increment: Duration = Duration::from_secs_f64(increment_seconds);
+        let mut clock: Clock = Clock::with_system_time(TimeControl::fischer(time, increment));
+        clock.start(board.turn_to_play()).expect("a new clock has time left");
+        clock
+    });
+
+    let terminal: RawTerminal = RawTerminal::enter().unwrap_or_else(|| {
+        eprintln!("The terminal could not be switched to raw mode, tui needs to be run in an interactive terminal.");
+        std::process::exit(1);
+    });
+    let mut game: Game = Game::new(board, clock);
+    game.run();
+    drop(terminal);
+
+    println!("{}", PgnGame::from_board(&game.board, game.result()));
+}
+
+/// Puts the terminal in raw mode on an alternate screen, and restores it when dropped.
+struct RawTerminal {
+    saved_settings: String,
+}
+
+impl RawTerminal {
+    fn enter() -> Option<Self> {
+        let saved_settings: String = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "0", "time", REFRESH_TENTHS_OF_SECOND])?;
+        print!("\x1b[?1049h\x1b[?25l\x1b[2J");
+        std::io::stdout().flush().ok();
+        Some(Self { saved_settings: saved_settings.trim().to_string() })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        print!("\x1b[0m\x1b[?25h\x1b[?1049l");
+        std::io::stdout().flush().ok();
+        stty(&[&self.saved_settings]);
+    }
+}
+
+/// Runs `stty` on the terminal of the standard input, returning what it printed.
+fn stty(arguments: &[&str]) -> Option<String> {
+    let output = Command::new("stty").args(arguments).stdin(Stdio::inherit()).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// A key read from the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Cancel,
+    Character(char),
+}
+
+/// Reads the keys pressed since the last read. Arrow keys arrive as escape sequences, while a lone escape byte is the
+/// escape key itself.
+fn read_keys() -> Vec<Key> {
+    let mut buffer: [u8; 64] = [0; 64];
+    let length: usize = std::io::stdin().read(&mut buffer).unwrap_or(0);
+    let bytes: &[u8] = &buffer[..length];
+
+    let mut keys: Vec<Key> = Vec::new();
+    let mut index: usize = 0;
+    while index < bytes.len() {
+        let key: Key = match &bytes[index..] {
+            [0x1b, b'[' | b'O', direction, ..] if matches!(direction, b'A'..=b'D') => {
+                index += 2;
+                match direction {
+                    b'A' => Key::Up,
+                    b'B' => Key::Down,
+                    b'C' => Key::Right,
+                    _ => Key::Left,
+                }
+            }
+            [0x1b, ..] => Key::Cancel,
+            [b'\r' | b'\n' | b' ', ..] => Key::Select,
+            [0x03, ..] => Key::Character('q'),
+            [byte, ..] => Key::Character(*byte as char),
+            [] => break,
+        };
+        keys.push(key);
+        index += 1;
+    }
+    keys
+}
+
+/// The state of the game and of the interface around it.
+struct Game {
+    board: Board,
+    clock: Option<Clock>,
+
+    /// The moves of the game in standard algebraic notation, kept as they are made since they can not be written
+    /// without the position that they were played from.
+    sans: Vec<String>,
+
+    flipped: bool,
+
+    /// The row and column of the cursor on the screen, counted from the top left corner of the board as drawn.
+    cursor: (usize, usize),
+
+    /// The piece selected to move, and the coordinates it can move to mapped to the coordinate of the piece it
+    /// would capture.
+    selected: Option<(Coordinate, HashMap<Coordinate, Option<Coordinate>>)>,
+
+    /// The moves which wait for the player to choose the piece that their pawn promotes to.
+    pending_promotions: Vec<Move>,
+
+    message: String,
+    outcome: Option<Outcome>,
+    quit: bool,
+}
+
+impl Game {
+    fn new(board: Board, clock: Option<Clock>) -> Self {
+        let flipped: bool = board.turn_to_play() == Team::Black;
+        Self {
+            board,
+            clock,
+            sans: Vec::new(),
+            flipped,
+            cursor: (6, 4),
+            selected: None,
+            pending_promotions: Vec::new(),
+            message: String::new(),
+            outcome: None,
+            quit: false,
+        }
+    }
+
+    fn run(&mut self) {
+        while !self.quit {
+            self.update_outcome();
+            self.draw();
+            for key in read_keys() {
+                self.handle_key(key);
+            }
+        }
+    }
+
+    /// The result of the game in PGN notation.
+    fn result(&self) -> &'static str {
+        match self.outcome {
+            Some(Outcome::Win(Team::White) | Outcome::WinOnTime(Team::White)) => "1-0",
+            Some(Outcome::Win(Team::Black) | Outcome::WinOnTime(Team::Black)) => "0-1",
+            Some(Outcome::Draw | Outcome::DrawOnTime) => "1/2-1/2",
+            None => "*",
+        }
+    }
+
+    fn update_outcome(&mut self) {
+        if self.outcome.is_some() {
+            return;
+        }
+        self.outcome = match self.clock.as_mut() {
+            Some(clock) => clock.outcome(&self.board),
+            None => match self.board.winner() {
+                Some(team) => Some(Outcome::Win(team)),
+                None => self.board.is_draw().then_some(Outcome::Draw),
+            },
+        };
+        if let (Some(_), Some(clock)) = (self.outcome, self.clock.as_mut()) {
+            clock.pause();
+        }
+    }
+
+    /// The coordinate under the screen position, which depends on which way the board is turned.
+    fn coordinate_at(&self, row: usize, column: usize) -> Coordinate {
+        let (row, column) = if self.flipped { (7 - row, 7 - column) } else { (row, column) };
+        Coordinate::try_from((row as u8, column as u8)).unwrap()
+    }
+
+    fn handle_key(&mut self, key: Key) {
+        if !self.pending_promotions.is_empty() {
+            self.choose_promotion(key);
+            return;
+        }
+
+        let (row, column) = self.cursor;
+        match key {
+            Key::Up | Key::Character('k') => self.cursor.0 = row.saturating_sub(1),
+            Key::Down | Key::Character('j') => self.cursor.0 = (row + 1).min(7),
+            Key::Left | Key::Character('h') => self.cursor.1 = column.saturating_sub(1),
+            Key::Right | Key::Character('l') => self.cursor.1 = (column + 1).min(7),
+            Key::Cancel | Key::Character('x') => self.selected = None,
+            Key::Character('f') => {
+                self.flipped = !self.flipped;
+                self.cursor = (7 - row, 7 - column);
+            }
+            Key::Character('u') => self.undo(),
+            Key::Character('q') => self.quit = true,
+            Key::Select => self.select(),
+            Key::Character(_) => {}
+        }
+    }
+
+    /// Selects the piece under the cursor, or moves the selected piece to the coordinate under the cursor.
+    fn select(&mut self) {
+        if self.outcome.is_some() {
+            return;
+        }
+        let coordinate: Coordinate = self.coordinate_at(self.cursor.0, self.cursor.1);
+        self.message.clear();
+
+        if let Some((from, targets)) = self.selected.take() {
+            if targets.contains_key(&coordinate) {
+                let moves: Vec<Move> = self
+                    .board
+                    .legal_moves()
+                    .into_iter()
+                    .filter(|chess_move| chess_move.from == from && chess_move.to == coordinate)
+                    .collect();
+                match moves.len() {
+                    1 => self.make_move(moves[0]),
+                    _ => {
+                        self.message = "Promote to: (q)ueen, (r)ook, (b)ishop, k(n)ight".to_string();
+                        self.pending_promotions = moves;
+                    }
+                }
+                return;
+            }
+            if from == coordinate {
+                return;
+            }
+        }
+
+        match self.board.get_piece(&coordinate) {
+            Some(piece) if piece.team() != self.board.turn_to_play() => {
+                self.message = "That piece belongs to the other team.".to_string();
+            }
+            Some(_) => match self.board.piece_legal_moves(&coordinate) {
+                Ok(targets) if !targets.is_empty() => self.selected = Some((coordinate, targets)),
+                _ => self.message = "That piece has no legal moves.".to_string(),
+            },
+            None => {}
+        }
+    }
+
+    fn choose_promotion(&mut self, key: Key) {
+        let class: PieceClass = match key {
+            Key::Character('q') | Key::Select => PieceClass::Queen,
+            Key::Character('r') => PieceClass::Rook,
+            Key::Character('b') => PieceClass::Bishop,
+            Key::Character('n') => PieceClass::Knight,
+            Key::Character('k') => PieceClass::King,
+            Key::Cancel | Key::Character('x') => {
+                self.pending_promotions.clear();
+                self.message.clear();
+                return;
+            }
+            _ => return,
+        };
+        let chosen: Option<Move> = self
+            .pending_promotions
+            .iter()
+            .copied()
+            .find(|chess_move| chess_move.promotion == Some(class));
+        if let Some(chess_move) = chosen {
+            self.pending_promotions.clear();
+            self.message.clear();
+            self.make_move(chess_move);
+        }
+    }
+
+    fn make_move(&mut self, chess_move: Move) {
+        let san: String = self.board.san(&chess_move);
+        if let Err(error) = self.board.make_move(&chess_move) {
+            self.message = format!("The move could not be made: {:?}", error);
+            return;
+        }
+        self.sans.push(san);
+        if let Some(clock) = self.clock.as_mut() {
+            if clock.press().is_err() {
+                self.message = "Time ran out before the move was made.".to_string();
+            }
+        }
+    }
+
+    /// Takes back the last move. The clocks keep the time that they have, but count for the team to play again.
+    fn undo(&mut self) {
+        self.selected = None;
+        if self.board.undo_move().is_none() {
+            self.message = "There are no moves to take back.".to_string();
+            return;
+        }
+        self.sans.pop();
+        self.message.clear();
+        // A fallen flag can not be taken back, so a game lost on time stays lost.
+        self.outcome = None;
+        if let Some(clock) = self.clock.as_mut() {
+            clock.start(self.board.turn_to_play()).ok();
+        }
+    }
+
+    fn draw(&self) {
+        let mut screen: Vec<String> = Vec::new();
+        let panel: Vec<String> = self.panel();
+
+        let files: Vec<char> = if self.flipped { ('a'..='h').rev().collect() } else { ('a'..='h').collect() };
+        let file_labels: String = files.iter().map(|file| format!(" {} ", file)).collect();
+        screen.push(format!("   {}", file_labels));
+        for row in 0..8 {
+            let rank: usize = if self.flipped { row + 1 } else { 8 - row };
+            let mut line: String = format!(" {} ", rank);
+            for column in 0..8 {
+                line.push_str(&self.square(row, column));
+            }
+            line.push_str(&format!("\x1b[0m {}", rank));
+            screen.push(line);
+        }
+        screen.push(format!("   {}", file_labels));
+
+        let mut output: String = String::from("\x1b[H");
+        for (index, line) in screen.iter().enumerate() {
+            output.push_str(line);
+            output.push_str("   ");
+            output.push_str(panel.get(index).map_or("", |text| text.as_str()));
+            output.push_str("\x1b[K\r\n");
+        }
+        output.push_str("\x1b[K\r\n");
+        output.push_str(&format!(" {}\x1b[K\r\n", self.status()));
+        output.push_str(&format!(" {}\x1b[K\r\n", self.message));
+        output.push_str(" arrows move, enter selects, x cancels, u undoes, f flips, q quits\x1b[K\x1b[J");
+        print!("{}", output);
+        std::io::stdout().flush().ok();
+    }
+
+    /// Draws a single square with its piece, coloring it for the last move, the selected piece and its targets, and a
+    /// king in check. The cursor is drawn as brackets around the square.
+    fn square(&self, row: usize, column: usize) -> String {
+        let coordinate: Coordinate = self.coordinate_at(row, column);
+        let is_light: bool = (coordinate.row() + coordinate.column()).is_multiple_of(2);
+        let piece: Option<Piece> = self.board.get_piece(&coordinate);
+
+        let last_move: bool = self
+            .board
+            .history()
+            .last()
+            .is_some_and(|node| node.from == coordinate || node.to == coordinate);
+        let in_check: bool = piece.is_some_and(|piece| {
+            piece.class() == PieceClass::King
+                && piece.team() == self.board.turn_to_play()
+                && self.board.is_in_check(piece.team())
+        });
+        let (selected, target) = match &self.selected {
+            Some((from, targets)) => (*from == coordinate, targets.contains_key(&coordinate)),
+            None => (false, false),
+        };
+
+        let background: u8 = match () {
+            _ if in_check => CHECKED_KING,
+            _ if selected => SELECTED_SQUARE,
+            _ if target && piece.is_some() => CAPTURE_TARGET,
+            _ if last_move && is_light => LIGHT_LAST_MOVE,
+            _ if last_move => DARK_LAST_MOVE,
+            _ if is_light => LIGHT_SQUARE,
+            _ => DARK_SQUARE,
+        };
+        let (foreground, glyph) = match piece {
+            Some(piece) => (
+                if piece.team() == Team::White { WHITE_PIECE } else { BLACK_PIECE },
+                glyph(piece.class()),
+            ),
+            None if target => (TARGET_MARKER, "•".to_string()),
+            None => (BLACK_PIECE, " ".to_string()),
+        };
+        let (left, right) = if self.cursor == (row, column) { ('[', ']') } else { (' ', ' ') };
+        format!("\x1b[48;5;{}m\x1b[38;5;{};1m{}{}{}", background, foreground, left, glyph, right)
+    }
+
+    /// The clocks and the material captured by each team next to their side of the board, followed by the latest
+    /// moves of the game.
+    fn panel(&self) -> Vec<String> {
+        let (top, bottom) = if self.flipped { (Team::White, Team::Black) } else { (Team::Black, Team::White) };
+        let mut players: Vec<String> = vec![String::new(); BOARD_LINES];
+        players[1] = self.player_line(top);
+        players[2] = format!("  {}", self.captured(top));
+        players[BOARD_LINES - 3] = format!("  {}", self.captured(bottom));
+        players[BOARD_LINES - 2] = self.player_line(bottom);
+
+        let move_lines: Vec<String> = self.move_list();
+        let skipped: usize = move_lines.len().saturating_sub(BOARD_LINES);
+        players
+            .iter()
+            .enumerate()
+            .map(|(index, player)| {
+                let padding: usize = PLAYER_COLUMN_WIDTH.saturating_sub(player.chars().count());
+                let moves: &str = move_lines.get(skipped + index).map_or("", |line| line.as_str());
+                format!("{}{}{}", player, " ".repeat(padding), moves)
+            })
+            .collect()
+    }
+
+    fn player_line(&self, team: Team) -> String {
+        let name: &str = if team == Team::White { "White" } else { "Black" };
+        let marker: &str = if self.outcome.is_none() && self.board.turn_to_play() == team { "▶" } else { " " };
+        match &self.clock {
+            Some(clock) => format!("{} {}  {}", marker, name, format_time(clock.remaining(team))),
+            None => format!("{} {}", marker, name),
+        }
+    }
+
+    /// The pieces of the opponent captured by the team, followed by the material advantage of the team in pawns.
+    fn captured(&self, team: Team) -> String {
+        let mut pieces: Vec<Piece> = self
+            .board
+            .graveyard()
+            .into_iter()
+            .filter(|piece| piece.team() != team)
+            .collect();
+        pieces.sort_by_key(|piece| -piece.class().value());
+        let mut bar: String = pieces.iter().map(|piece| glyph(piece.class())).collect();
+
+        let advantage: i32 = material(&self.board, team) - material(&self.board, team.opponent());
+        if advantage > 0 {
+            bar.push_str(&format!(" +{}", advantage));
+        }
+        bar
+    }
+
+    /// The moves of the game numbered in pairs, one full move on each line.
+    fn move_list(&self) -> Vec<String> {
+        let starting_board: Board = Board::new_with_fen(self.board.starting_fen());
+        let mut number: u16 = starting_board.fullmove_number();
+        let mut sans: Vec<&str> = self.sans.iter().map(|san| san.as_str()).collect();
+        if starting_board.turn_to_play() == Team::Black {
+            sans.insert(0, "...");
+        }
+
+        sans.chunks(2)
+            .map(|pair| {
+                let line: String = format!("{:>3}. {:<8} {}", number, pair[0], pair.get(1).unwrap_or(&""));
+                number += 1;
+                line
+            })
+            .collect()
+    }
+
+    fn status(&self) -> String {
+        match self.outcome {
+            Some(Outcome::Win(team)) => format!("Checkmate, {} wins.", team_name(team)),
+            Some(Outcome::WinOnTime(team)) => format!("{} wins on time.", team_name(team)),
+            Some(Outcome::Draw) => "The game is drawn.".to_string(),
+            Some(Outcome::DrawOnTime) => "Time ran out, but the game is drawn for lack of mating material.".to_string(),
+            None if self.board.is_in_check(self.board.turn_to_play()) => {
+                format!("{} to play, in check.", team_name(self.board.turn_to_play()))
+            }
+            None => format!("{} to play.", team_name(self.board.turn_to_play())),
+        }
+    }
+}
+
+/// The filled glyph of the piece class, which is colored for the team that the piece belongs to.
+fn glyph(class: PieceClass) -> String {
+    Piece::new(class, Team::Black).to_string()
+}
+
+/// The material of the team on the board in pawns, without the king.
+fn material(board: &Board, team: Team) -> i32 {
+    board
+        .map()
+        .iter()
+        .flatten()
+        .flatten()
+        .filter(|piece| piece.team() == team && piece.class() != PieceClass::King)
+        .map(|piece| piece.class().value() / 100)
+        .sum()
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds: u64 = time.as_secs();
+    match seconds {
+        0..=9 => format!("{}:{:02}.{}", seconds / 60, seconds % 60, time.subsec_millis() / 100),
+        _ => format!("{}:{:02}", seconds / 60, seconds % 60),
+    }
+}
+
+fn team_name(team: Team) -> &'static str {
+    match team {
+        Team::White => "White",
+        Team::Black => "Black",
+    }
+}
+
+fn parse_or_exit<T: std::str::FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
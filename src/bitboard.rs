@@ -0,0 +1,153 @@
+use crate::coordinate::Coordinate;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Not};
+
+/// A set of coordinates on the chess board stored as a 64 bit mask. The bit at index `row * 8 + column` is set when the
+/// coordinate at that row and column is a member of the set.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub struct Bitboard(u64);
+
+impl Bitboard {
+    pub const EMPTY: Self = Self(0);
+    pub const FULL: Self = Self(u64::MAX);
+
+    pub fn new(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        self.0 & Self::from(*coordinate).0 != 0
+    }
+
+    pub fn insert(&mut self, coordinate: &Coordinate) {
+        self.0 |= Self::from(*coordinate).0
+    }
+
+    pub fn remove(&mut self, coordinate: &Coordinate) {
+        self.0 &= !Self::from(*coordinate).0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The number of coordinates in the set.
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Returns the coordinates in the set ordered by their row and then by their column.
+    pub fn coordinates(&self) -> Vec<Coordinate> {
+        self.into_iter().collect()
+    }
+}
+
+impl From<Coordinate> for Bitboard {
+    fn from(coordinate: Coordinate) -> Self {
+        Self(1 << coordinate.index())
+    }
+}
+
+impl FromIterator<Coordinate> for Bitboard {
+    fn from_iter<T: IntoIterator<Item = Coordinate>>(iter: T) -> Self {
+        let mut bitboard: Self = Self::EMPTY;
+        for coordinate in iter {
+            bitboard.insert(&coordinate);
+        }
+        bitboard
+    }
+}
+
+impl IntoIterator for Bitboard {
+    type Item = Coordinate;
+    type IntoIter = BitboardIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BitboardIter { remaining: self.0 }
+    }
+}
+
+/// An iterator over the coordinates of a [`Bitboard`].
+pub struct BitboardIter {
+    remaining: u64,
+}
+
+impl Iterator for BitboardIter {
+    type Item = Coordinate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let index: u32 = self.remaining.trailing_zeros();
+        self.remaining &= self.remaining - 1;
+        Some(Coordinate::try_from((index / 8, index % 8)).unwrap())
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self::Output {
+        Self(self.0 & other.0)
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self::Output {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self::Output {
+        Self(self.0 ^ other.0)
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self(!self.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, other: Self) {
+        self.0 &= other.0
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0
+    }
+}
+
+impl std::fmt::Display for Bitboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for row in 0u8..8u8 {
+            write!(f, "{} ┃ ", 8 - row)?;
+            for column in 0u8..8u8 {
+                let coordinate: Coordinate = Coordinate::try_from((row, column)).unwrap();
+                write!(f, "{} ", if self.contains(&coordinate) { "x" } else { "." })?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "  ┗━━━━━━━━━━━━━━━━")?;
+        write!(f, "    ")?;
+        for letter in 'A'..'I' {
+            write!(f, "{} ", letter)?;
+        }
+        std::fmt::Result::Ok(())
+    }
+}
@@ -1,11 +1,33 @@
-use crate::coordinate::{Coordinate, CoordinatePath};
+use crate::castling::CastlingRights;
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
 use crate::piece::{Piece, PieceClass, Team};
+use crate::pocket::Pockets;
+use crate::render::BoardRenderer;
+use crate::variant::{variant_by_name, Standard, Variant};
 use std::collections::HashMap;
+use std::sync::Arc;
 use itertools::Itertools;
 use regex::Regex;
 
+mod atomic;
+mod attacks;
+mod chess960;
+mod drops;
+mod encoding;
+mod events;
+mod movegen;
+mod pins;
+mod san;
+mod see;
+mod uci;
+
+pub use chess960::STANDARD_CHESS960_INDEX;
+pub use events::{BoardEvent, BoardObserver, MoveEvent};
+pub use pins::{DiscoveredAttack, Pin};
+
 /// Represents the current chess board with all of its pieces
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Board {
     /// A two dimensional array of the actual board.
     map: [[Option<Piece>; 8]; 8],
@@ -20,7 +42,44 @@ pub struct Board {
     history: Vec<HistoryNode>,
 
     /// Represents the team which has the turn to play.
-    turn_to_play: Team
+    turn_to_play: Team,
+
+    /// The castling rights which are still available to each of the teams.
+    castling_rights: CastlingRights,
+
+    /// The coordinate that a pawn can move to in order to capture en passant. This is only set right after a pawn has
+    /// moved two squares forward.
+    en_passant: Option<Coordinate>,
+
+    /// The number of half moves made since the last capture or pawn move, used for the fifty move rule.
+    halfmove_clock: u16,
+
+    /// The number of the current full move. Starts at 1 and is incremented after each move of the black team.
+    fullmove_number: u16,
+
+    /// Whether the game follows the Chess960 castling rules, where castling moves are written as the king capturing its
+    /// own rook rather than as the king moving two squares.
+    chess960: bool,
+
+    /// The rules of the variant of chess played on the board.
+    variant: Arc<dyn Variant>,
+
+    /// The pieces that each team holds in hand, in variants which allow dropping pieces onto the board.
+    pockets: Pockets,
+
+    /// The pieces added to the pockets from outside of the board after the first move, as in Bughouse, along with the
+    /// number of moves which had been made when they were added.
+    received: Vec<(usize, Team, PieceClass)>,
+
+    /// The number of times each team has given check, in variants which are won by giving a number of checks.
+    checks_given: HashMap<Team, u8>,
+
+    /// The FEN of the position before the first move in the history was made, which is only known once a move has been
+    /// made since the position can be set up in many ways before that.
+    starting_fen: Option<Fen>,
+
+    /// The observers which are notified of the moves made on the board.
+    observers: events::Observers,
 }
 
 impl Board {
@@ -34,8 +93,8 @@ impl Board {
                 PieceClass::Rook,
                 PieceClass::Knight,
                 PieceClass::Bishop,
-                PieceClass::King,
                 PieceClass::Queen,
+                PieceClass::King,
                 PieceClass::Bishop,
                 PieceClass::Knight,
                 PieceClass::Rook,
@@ -45,7 +104,7 @@ impl Board {
             {
                 board.set_piece(
                     &Coordinate::try_from((row_index, i)).unwrap(),
-                    Some(Piece::new(item_class.clone(), team)),
+                    Some(Piece::new(*item_class, team)),
                 );
             }
         }
@@ -59,40 +118,124 @@ impl Board {
             }
         }
 
+        board.castling_rights = CastlingRights::standard();
+
         board
     }
 
+    /// Creates a new board in the starting position of the variant, which is played by the rules of the variant.
+    pub fn new_with_variant<V: Variant + 'static>(variant: V) -> Self {
+        let mut board: Self = Self::new_with_fen(variant.starting_fen());
+        board.variant = Arc::new(variant);
+        board
+    }
+
+    /// Creates a new board from a FEN, panicking if the FEN is not valid.
     pub fn new_with_fen(fen: Fen) -> Self {
+        Self::try_new_with_fen(fen).unwrap()
+    }
+
+    /// Creates a new board from a FEN. Only the piece placement field is required, the remaining fields default to white
+    /// to play, no castling rights, no en passant coordinate, and the start of the game.
+    pub fn try_new_with_fen(fen: Fen) -> Result<Self, BoardError> {
         let mut board: Self = Default::default();
+        let fields: Vec<&str> = fen.state.split_whitespace().collect();
 
+        // Variants with pockets write them after the piece placement, either inside brackets or as a ninth row.
         let board_pieces_state: String = fen.board_pieces_state();
-        for (row_index, row_data) in board_pieces_state.split('/').enumerate() {
+        let (placement, pockets) = match board_pieces_state.split_once('[') {
+            Some((placement, pockets)) => {
+                (placement, Some(pockets.strip_suffix(']').ok_or(BoardError::InvalidFen)?))
+            }
+            None => (board_pieces_state.as_str(), None),
+        };
+        let mut rows: Vec<&str> = placement.split('/').collect();
+        let pockets: Option<&str> = if rows.len() == 9 { rows.pop() } else { pockets };
+        if rows.len() != 8 {
+            return Err(BoardError::InvalidFen);
+        }
+        if let Some(pockets) = pockets {
+            board.parse_pockets(pockets)?;
+        }
+        for (row_index, row_data) in rows.into_iter().enumerate() {
             let mut column_index: usize = 0;
             for char in row_data.chars() {
-                if char.is_alphabetic() {
-                    let piece: Piece = Piece::try_from(char).unwrap();
-                    board.set_piece(&Coordinate::try_from((row_index, column_index)).unwrap(), Some(piece));
+                if char == '~' && column_index > 0 {
+                    // A tilde marks the piece before it as promoted from a pawn.
+                    let coordinate: Coordinate = Coordinate::try_from((row_index, column_index - 1)).unwrap();
+                    let piece: Piece = board.get_piece(&coordinate).ok_or(BoardError::InvalidFen)?;
+                    board.set_piece(&coordinate, Some(Piece::new_promoted(piece.class(), piece.team())));
+                } else if char.is_alphabetic() {
+                    let piece: Piece = Piece::try_from(char).map_err(|_| BoardError::InvalidFen)?;
+                    let coordinate: Coordinate =
+                        Coordinate::try_from((row_index, column_index)).map_err(|_| BoardError::InvalidFen)?;
+                    board.set_piece(&coordinate, Some(piece));
                     column_index += 1;
-                } else if char.is_numeric() {
-                    let amount: u32 = char.to_digit(10).unwrap();
-                    for _ in 0..amount {
-                        board.set_piece(&Coordinate::try_from((row_index, column_index)).unwrap(), None);
-                        column_index += 1;
-                    }
+                } else if let Some(amount) = char.to_digit(10) {
+                    column_index += amount as usize;
                 } else {
-                    panic!("Got a symbol in the FEN");
+                    return Err(BoardError::InvalidFen);
                 }
             }
+            if column_index != 8 {
+                return Err(BoardError::InvalidFen);
+            }
+        }
+
+        board.turn_to_play = fen.turn_to_play().ok_or(BoardError::InvalidFen)?;
+
+        if let Some(castling_field) = fields.get(2) {
+            board.parse_castling_field(castling_field)?;
+        }
+
+        board.en_passant = match fields.get(3) {
+            None | Some(&"-") => None,
+            Some(en_passant_field) => {
+                Some(Coordinate::try_from(*en_passant_field).map_err(|_| BoardError::InvalidFen)?)
+            }
+        };
+
+        // Variants which are won by giving checks write the checks either as the remaining checks of both teams before
+        // the clocks (e.g. `3+3`) or as the checks given by both teams after them (e.g. `+0+0`).
+        let mut clock_fields: Vec<&str> = fields.iter().skip(4).copied().collect();
+        if clock_fields.last().is_some_and(|field| field.starts_with('+')) {
+            let checks_field: &str = clock_fields.pop().unwrap();
+            board.parse_checks_field(&checks_field[1..], false)?;
+        } else if clock_fields.first().is_some_and(|field| field.contains('+')) {
+            let checks_field: &str = clock_fields.remove(0);
+            board.parse_checks_field(checks_field, true)?;
+        }
+
+        if let Some(halfmove_clock_field) = clock_fields.first() {
+            board.halfmove_clock = halfmove_clock_field.parse().map_err(|_| BoardError::InvalidFen)?;
+        }
+        if let Some(fullmove_number_field) = clock_fields.get(1) {
+            board.fullmove_number = fullmove_number_field.parse().map_err(|_| BoardError::InvalidFen)?;
         }
 
-        return board
+        Ok(board)
+    }
+
+    /// Creates a board with only the given pieces on it, with no castling rights and no en passant coordinate.
+    pub(crate) fn with_pieces(pieces: &[(Coordinate, Piece)], turn_to_play: Team) -> Self {
+        let mut board: Self = Default::default();
+        for (coordinate, piece) in pieces.iter() {
+            board.set_piece(coordinate, Some(*piece));
+        }
+        board.turn_to_play = turn_to_play;
+        board
     }
 
     pub fn try_new_with_history(history: Vec<HistoryNode>) -> Result<Self, BoardError> {
         let mut board: Self = Self::new();
 
         for node in history.iter() {
-            board.move_piece(&node.from, &node.to)?;
+            board.make_move(&Move {
+                from: node.from,
+                to: node.to,
+                promotion: node.promotion,
+                drop: node.drop,
+            })?;
         }
 
         Ok(board)
@@ -131,74 +274,314 @@ impl Board {
         self.team_moves.clone()
     }
 
+    pub fn history(&self) -> &[HistoryNode] {
+        &self.history
+    }
+
+    /// Gets the FEN of the position that the moves in the history were made from, which is the current position when no
+    /// moves have been made.
+    pub fn starting_fen(&self) -> Fen {
+        match self.history.is_empty() {
+            true => self.fen(),
+            false => self.starting_fen.clone().unwrap_or_else(|| self.fen()),
+        }
+    }
+
+    /// Rebuilds the position that the moves in the history were made from, following the same rules as the board.
+    fn starting_board(&self) -> Result<Self, BoardError> {
+        let mut board: Self = Self::try_new_with_fen(self.starting_fen())?;
+        board.variant = self.variant.clone();
+        board.chess960 = self.chess960;
+        Ok(board)
+    }
+
+    /// Takes back the last move in the history by restoring the state that the board had before it, and returns the
+    /// history node of the move taken back. Pieces added to the pockets since the move, as in Bughouse, stay in the
+    /// pockets. The observers of the board are notified of the undo.
+    pub fn undo_move(&mut self) -> Option<HistoryNode> {
+        let last: HistoryNode = self.history.pop()?;
+        let team: Team = last.piece.team();
+        let undo: &UndoState = &last.undo;
+
+        for (coordinate, piece) in undo.changed.iter() {
+            self.set_piece(coordinate, *piece);
+        }
+        self.graveyard.truncate(undo.graveyard_length);
+        self.castling_rights = undo.castling_rights;
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
+
+        if let Some(class) = last.drop {
+            self.pockets.get_mut(team).add(class);
+        }
+        if let Some(class) = undo.pocketed {
+            self.pockets.get_mut(team).remove(class);
+        }
+        if undo.gave_check {
+            *self.checks_given.get_mut(&team).unwrap() -= 1;
+        }
+        *self.team_moves.get_mut(&team).unwrap() -= 1;
+        self.turn_to_play = team;
+
+        // Pieces received after the move was made now arrived before the moves left in the history, and once there are
+        // none left they are part of the position that the next move starts from.
+        let ply: usize = self.history.len();
+        match ply {
+            0 => self.received.clear(),
+            _ => self.received.iter_mut().for_each(|(received_ply, _, _)| *received_ply = (*received_ply).min(ply)),
+        }
+
+        self.notify(&BoardEvent::Undo(last.clone()));
+        Some(last)
+    }
+
+    /// Gets the rules of the variant of chess played on the board.
+    pub fn variant(&self) -> &dyn Variant {
+        self.variant.as_ref()
+    }
+
+    /// Changes the rules that the board is played by without changing the position, which is how positions of variants
+    /// are set up from a FEN.
+    pub fn set_variant<V: Variant + 'static>(&mut self, variant: V) {
+        self.variant = Arc::new(variant);
+    }
+
+    /// Changes the rules that the board is played by to the variant with the name, as given by [`Variant::name`].
+    pub fn set_variant_by_name(&mut self, name: &str) -> Result<(), BoardError> {
+        self.variant = variant_by_name(name).ok_or(BoardError::UnknownVariant)?;
+        Ok(())
+    }
+
     pub fn turn_to_play(&self) -> Team {
         self.turn_to_play
     }
 
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
+    }
+
+    pub fn en_passant(&self) -> Option<Coordinate> {
+        self.en_passant
+    }
+
+    /// Gets the number of times the team has given check, which is only counted in variants won by giving checks.
+    pub fn checks_given(&self, team: Team) -> u8 {
+        self.checks_given[&team]
+    }
+
+    pub fn halfmove_clock(&self) -> u16 {
+        self.halfmove_clock
+    }
+
+    pub fn fullmove_number(&self) -> u16 {
+        self.fullmove_number
+    }
+
     fn toggle_turn_to_play(&mut self) {
-        self.turn_to_play = match self.turn_to_play {
-            Team::White => Team::Black,
-            Team::Black => Team::White
-        }
+        self.turn_to_play = self.turn_to_play.opponent()
     }
 
     /// Moves a piece from one coordinate to another coordinate. Checks that the move is legal before performing the 
-    /// move.
+    /// move. Pawns reaching the last row are promoted to a queen, use [`Board::make_move`] to promote to other pieces.
     pub fn move_piece(
         &mut self,
         from: &Coordinate,
         to: &Coordinate
     ) -> Result<(), BoardError> {
-        // Getting the piece at the specified coordinate.
-        let mut piece: Piece = {
-            match self.get_piece(from) {
-                Some(piece) => Ok(piece),
-                None => Err(BoardError::EmptyCoordinate),
+        let promotion: Option<PieceClass> = match self.get_piece(from) {
+            Some(piece) if matches!(piece.class(), PieceClass::Pawn) && (to.row() == 0 || to.row() == 7) => {
+                Some(PieceClass::Queen)
             }
-        }?;
+            _ => None,
+        };
+
+        self.make_move(&Move {
+            from: *from,
+            to: *to,
+            promotion,
+            drop: None,
+        })
+    }
+
+    /// Performs a move on the board. Checks that the move is legal before performing it.
+    pub fn make_move(&mut self, chess_move: &Move) -> Result<(), BoardError> {
+        // Drops take their piece from the pocket of the team to play, so there is no piece on the board to check.
+        if !chess_move.is_drop() {
+            // Getting the piece at the specified coordinate.
+            let piece: Piece = {
+                match self.get_piece(&chess_move.from) {
+                    Some(piece) => Ok(piece),
+                    None => Err(BoardError::EmptyCoordinate),
+                }
+            }?;
+
+            // Check if this piece is of the team that is currently allowed to play, if not then return an error
+            if piece.team() != self.turn_to_play {
+                return Err(BoardError::NotYourTurn)
+            }
+        }
 
-        // Check if this piece is of the team that is currently allowed to play, if not then return an error
-        if piece.team() != self.turn_to_play {
-            return Err(BoardError::NotYourTurn)
+        if !self.legal_moves().contains(chess_move) {
+            return Err(BoardError::IllegalMove)
         }
 
-        // Getting all of the legal moves for this piece
-        let legal_moves: HashMap<Coordinate, Option<Coordinate>> = self.piece_legal_moves(from).unwrap();
+        let event: Option<MoveEvent> = self.move_event(chess_move);
+        self.apply_move(chess_move);
+        if let Some(event) = event {
+            self.notify_move(event);
+        }
+        Ok(())
+    }
 
-        // If true, then this is a legal move and we can go ahead with the removal of the old item.
-        if legal_moves.contains_key(to) {
-            // If there is an item to destroy, go ahead and destroy it.
-            match legal_moves.get(from) {
-                Some(to_destroy_coordinate) => {
-                    self.remove_piece(&to_destroy_coordinate.unwrap())?;
-                },
-                None => {}
+    /// Performs a move on the board without checking that it is legal. Used by searches which only ever play moves
+    /// taken from the legal moves of the board, where checking them again would be wasted work.
+    pub(crate) fn apply_move(&mut self, chess_move: &Move) {
+        let from: &Coordinate = &chess_move.from;
+        let to: &Coordinate = &chess_move.to;
+        if self.history.is_empty() {
+            self.starting_fen = Some(self.fen());
+        }
+        let map_before: [[Option<Piece>; 8]; 8] = self.map;
+        let mut undo: UndoState = UndoState {
+            changed: Vec::new(),
+            graveyard_length: self.graveyard.len(),
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            pocketed: None,
+            gave_check: false,
+        };
+
+        if let Some(class) = chess_move.drop {
+            let piece: Piece = self.drop_piece(class, to);
+            self.en_passant = None;
+            self.record_move(piece, chess_move, false, undo, &map_before);
+            return;
+        }
+        let mut piece: Piece = self.get_piece(from).unwrap();
+
+        // If there is an item to destroy, go ahead and destroy it. Variants with pockets keep it for dropping later.
+        let captured_coordinate: Option<Coordinate> = self.captured_coordinate(chess_move);
+        if let Some(captured_coordinate) = captured_coordinate {
+            let captured_piece: Piece = self.get_piece(&captured_coordinate).unwrap();
+            self.remove_piece(&captured_coordinate).unwrap();
+            if self.variant.pockets_captures() {
+                undo.pocketed = self.pocket_piece(piece.team(), &captured_piece);
             }
+        }
 
-            // Perform the move operation
-            piece.add_move();
-            self.set_piece(to, Some(piece));
-            self.set_piece(from, None);
+        // Castling moves the rook to the other side of the king. Both of them are lifted before either is placed, since
+        // in Chess960 the king or the rook can land on the coordinate that the other one starts on.
+        piece.add_move();
+        let destination: Coordinate = match (self.castling_side(chess_move), self.castling_rook_move(chess_move)) {
+            (Some(side), Some((rook_from, rook_to))) => {
+                let mut rook: Piece = self.get_piece(&rook_from).unwrap();
+                rook.add_move();
+                self.set_piece(&rook_from, None);
+                self.set_piece(from, None);
+                self.set_piece(&rook_to, Some(rook));
+                Coordinate::try_from((from.row(), side.king_destination_column())).unwrap()
+            }
+            _ => {
+                self.set_piece(from, None);
+                *to
+            }
+        };
+        self.set_piece(&destination, Some(piece));
 
-            // Adding this move to the total number of moves made
-            *self.team_moves.get_mut(&piece.team()).unwrap() += 1;
+        // If the pawn has reached the last row then it gets switched with the piece it's promoted to.
+        if let Some(promotion) = chess_move.promotion {
+            self.set_piece(to, Some(Piece::new_promoted(promotion, piece.team())));
+        }
 
-            // Adding the move to the history of the match
-            self.history.push(HistoryNode { piece: piece, from: from.clone(), to: to.clone() });
+        if captured_coordinate.is_some() && self.variant.explodes_captures() {
+            self.explode(to);
+        }
 
-            // Toggle the teams
-            self.toggle_turn_to_play();
+        // Moving the king or the rooks, or capturing a rook, gives up the castling rights which rely on them.
+        if matches!(piece.class(), PieceClass::King) {
+            self.castling_rights.remove_team(piece.team());
+        }
+        for coordinate in [from, to] {
+            self.castling_rights.remove_rook(coordinate);
+        }
 
-            // If an upgrade can happen, then switch the pawn with a queen.
-            // TODO: Add a way to select what gets promoted.
-            if (to.row() == 0 || to.row() == 7) && matches!(piece.class(), PieceClass::Pawn) {
-                self.set_piece(to, Some(Piece::new(PieceClass::Queen, piece.team())));
-            }
+        // A pawn which moves two squares forward from the row that the pawns start the game on can be captured en
+        // passant on the next move. Variants which allow double steps from other rows, such as Horde, do not allow
+        // capturing those en passant.
+        let double_step_row: usize = match piece.team() {
+            Team::White => 6,
+            Team::Black => 1,
+        };
+        self.en_passant = if matches!(piece.class(), PieceClass::Pawn)
+            && from.row() == double_step_row
+            && from.row().abs_diff(to.row()) == 2
+        {
+            Some(Coordinate::try_from(((from.row() + to.row()) / 2, from.column())).unwrap())
+        } else {
+            None
+        };
+
+        self.record_move(
+            piece,
+            chess_move,
+            matches!(piece.class(), PieceClass::Pawn) || captured_coordinate.is_some(),
+            undo,
+            &map_before,
+        );
+    }
 
-            Ok(())
+    /// Updates the clocks and the history after the piece has made the move, and passes the turn to the other team.
+    /// The halfmove clock starts over when the move is a capture or a pawn move. The history node keeps the state from
+    /// before the move along with the pieces that stood on the coordinates which the move changed.
+    fn record_move(
+        &mut self,
+        piece: Piece,
+        chess_move: &Move,
+        resets_halfmove_clock: bool,
+        mut undo: UndoState,
+        map_before: &[[Option<Piece>; 8]; 8],
+    ) {
+        if resets_halfmove_clock {
+            self.halfmove_clock = 0;
         } else {
-            Err(BoardError::IllegalMove)
+            self.halfmove_clock += 1;
         }
+        if matches!(piece.team(), Team::Black) {
+            self.fullmove_number += 1;
+        }
+
+        // Adding this move to the total number of moves made
+        *self.team_moves.get_mut(&piece.team()).unwrap() += 1;
+
+        if self.variant.winning_checks().is_some() && self.is_in_check(piece.team().opponent()) {
+            *self.checks_given.get_mut(&piece.team()).unwrap() += 1;
+            undo.gave_check = true;
+        }
+
+        for (row_index, (row_before, row)) in map_before.iter().zip(self.map.iter()).enumerate() {
+            for (column_index, (piece_before, piece)) in row_before.iter().zip(row.iter()).enumerate() {
+                if piece_before != piece {
+                    let coordinate: Coordinate = Coordinate::try_from((row_index, column_index)).unwrap();
+                    undo.changed.push((coordinate, *piece_before));
+                }
+            }
+        }
+
+        // Adding the move to the history of the match
+        self.history.push(HistoryNode {
+            piece,
+            from: chess_move.from,
+            to: chess_move.to,
+            promotion: chess_move.promotion,
+            drop: chess_move.drop,
+            undo,
+        });
+
+        // Toggle the teams
+        self.toggle_turn_to_play();
     }
 
     /// This method gets all of the legal moves that a specific piece from a specific coordinate is allowed to make and
@@ -222,321 +605,67 @@ impl Board {
             }
         }?;
 
-        // This is the HashMap that will be returned. The following match statement will only modify this HashMap, it
-        // wont create a new one.
-        let mut legal_moves: HashMap<Coordinate, Option<Coordinate>> = HashMap::new();
-
-        // The logic depends on the type of the piece that we're checking for.
-        match piece.class() {
-            PieceClass::Knight => {
-                // The night only has a set of coordinates that they can move to, nothing else. Here we calculate the
-                // possible coordinate offsets that they can move to.
-                let coordinates: Vec<Coordinate> = vec![1, 2, -1, -2]
-                    .iter()
-                    .cloned()
-                    .permutations(2)
-                    .filter(|perm| perm.iter().map(|n| i8::abs(*n)).sum::<i8>() == 3)
-                    .map(|offsets| {
-                        let row_offset: i8 = offsets[0];
-                        let column_offset: i8 = offsets[1];
-
-                        coordinate.checked_add_individual(row_offset, column_offset)
-                    })
-                    .filter(|maybe_coordinate| maybe_coordinate.is_ok())
-                    .map(|x| x.unwrap())
-                    .collect();
-
-                // Go over the coordinates and ensure that the knight can only move to coordinates where no friendlies
-                // are
-                for single_coordinate in coordinates.into_iter() {
-                    match self.get_piece(&single_coordinate) {
-                        Some(other_piece) => {
-                            if other_piece.team() != piece.team() {
-                                legal_moves.insert(
-                                    single_coordinate.clone(),
-                                    Some(single_coordinate.clone()),
-                                );
-                            }
-                        }
-                        None => {
-                            legal_moves.insert(single_coordinate, None);
-                        }
-                    }
-                }
-            }
-            // These pieces will all follow the same logic of CoordinatePaths but will be using different paths. So, it
-            // is better to group their logic into one single place to be able to use it quickly.
-            PieceClass::Bishop | PieceClass::Rook | PieceClass::Queen | PieceClass::King => {
-                let paths: Vec<CoordinatePath> = {
-                    match piece.class() {
-                        PieceClass::Bishop => vec![-1, -1, 1, 1]
-                            .iter()
-                            .cloned()
-                            .permutations(2)
-                            .unique()
-                            .map(|multipliers| {
-                                let row_multiplier: i8 = multipliers[0];
-                                let column_multiplier: i8 = multipliers[1];
-
-                                (1..8)
-                                    .map(|n| {
-                                        (n.clone() * row_multiplier, n.clone() * column_multiplier)
-                                    })
-                                    .collect::<Vec<(i8, i8)>>()
-                            })
-                            .map(|offsets_vec| {
-                                offsets_vec
-                                    .iter()
-                                    .map(|(row_offset, column_offset)| {
-                                        coordinate
-                                            .checked_add_individual(*row_offset, *column_offset)
-                                    })
-                                    .filter(|maybe_coordinate| maybe_coordinate.is_ok())
-                                    .map(|x| x.unwrap())
-                                    .collect()
-                            })
-                            .collect::<Vec<CoordinatePath>>(),
-                        PieceClass::Rook => vec![-1, 1]
-                            .iter()
-                            .cloned()
-                            .flat_map(|multiplier| {
-                                vec![
-                                    (1..8)
-                                        .map(|n| (0, n.clone() * multiplier))
-                                        .collect::<Vec<(i8, i8)>>(),
-                                    (1..8)
-                                        .map(|n| (n.clone() * multiplier, 0))
-                                        .collect::<Vec<(i8, i8)>>(),
-                                ]
-                            })
-                            .map(|offsets_vec| {
-                                offsets_vec
-                                    .iter()
-                                    .map(|(row_offset, column_offset)| {
-                                        coordinate
-                                            .checked_add_individual(*row_offset, *column_offset)
-                                    })
-                                    .filter(|maybe_coordinate| maybe_coordinate.is_ok())
-                                    .map(|x| x.unwrap())
-                                    .collect()
-                            })
-                            .collect::<Vec<CoordinatePath>>(),
-                        PieceClass::Queen | PieceClass::King => {
-                            let end: i8 = if matches!(piece.class(), PieceClass::Queen) {
-                                8
-                            } else {
-                                2
-                            };
-                            vec![-1, 1]
-                                .iter()
-                                .cloned()
-                                .flat_map(|multiplier| {
-                                    vec![
-                                        (1..end)
-                                            .map(|n| (0, n.clone() * multiplier))
-                                            .collect::<Vec<(i8, i8)>>(),
-                                        (1..end)
-                                            .map(|n| (n.clone() * multiplier, 0))
-                                            .collect::<Vec<(i8, i8)>>(),
-                                    ]
-                                })
-                                .map(|offsets_vec| {
-                                    offsets_vec
-                                        .iter()
-                                        .map(|(row_offset, column_offset)| {
-                                            coordinate
-                                                .checked_add_individual(*row_offset, *column_offset)
-                                        })
-                                        .filter(|maybe_coordinate| maybe_coordinate.is_ok())
-                                        .map(|x| x.unwrap())
-                                        .collect()
-                                })
-                                .chain(
-                                    vec![-1, -1, 1, 1]
-                                        .iter()
-                                        .cloned()
-                                        .permutations(2)
-                                        .unique()
-                                        .map(|multipliers| {
-                                            let row_multiplier: i8 = multipliers[0];
-                                            let column_multiplier: i8 = multipliers[1];
-
-                                            (1..end)
-                                                .map(|n| {
-                                                    (
-                                                        n.clone() * row_multiplier,
-                                                        n.clone() * column_multiplier,
-                                                    )
-                                                })
-                                                .collect::<Vec<(i8, i8)>>()
-                                        })
-                                        .map(|offsets_vec| {
-                                            offsets_vec
-                                                .iter()
-                                                .map(|(row_offset, column_offset)| {
-                                                    coordinate.checked_add_individual(
-                                                        *row_offset,
-                                                        *column_offset,
-                                                    )
-                                                })
-                                                .filter(|maybe_coordinate| maybe_coordinate.is_ok())
-                                                .map(|x| x.unwrap())
-                                                .collect()
-                                        }),
-                                )
-                                .collect::<Vec<CoordinatePath>>()
-                        }
-                        _ => {
-                            panic!("Impossible case occurred.")
-                        }
-                    }
-                };
-
-                for path in paths.into_iter() {
-                    for single_coordinate in path.into_iter() {
-                        match self.get_piece(&single_coordinate) {
-                            Some(other_piece) => {
-                                if other_piece.team() != piece.team() {
-                                    legal_moves.insert(
-                                        single_coordinate.clone(),
-                                        Some(single_coordinate.clone()),
-                                    );
-                                }
-                                break;
-                            }
-                            None => {
-                                legal_moves.insert(single_coordinate, None);
-                            }
-                        }
-                    }
-                }
-            }
-            PieceClass::Pawn => {
-                // Determine the direction of allowed movements depending on the team
-                let single_pawn_move: i8 = match piece.team() {
-                    Team::Black => 1,
-                    Team::White => -1,
-                };
-
-                // Single pawn move
-                let mut is_single_move_legal: bool = false;
-                match coordinate.checked_add_individual(single_pawn_move, 0) {
-                    Ok(single_coordinate) => {
-                        match self.get_piece(&single_coordinate) {
-                            Some(_) => { }
-                            None => {
-                                legal_moves.insert(single_coordinate, None);
-                                is_single_move_legal = true;
-                            }
-                        }
-                    },
-                    Err(_) => {}
-                }
-
-                // Two pawn move
-                if piece.is_first_move() && is_single_move_legal {
-                    match coordinate.checked_add_individual(single_pawn_move * 2, 0) {
-                        Ok(single_coordinate) => {
-                            match self.get_piece(&single_coordinate) {
-                                Some(_) => { }
-                                None => {
-                                    legal_moves.insert(single_coordinate, None);
-                                }
-                            }
-                        },
-                        Err(_) => {}
-                    }
-                }
+        Ok(self
+            .team_legal_moves(piece.team())
+            .into_iter()
+            .filter(|chess_move| chess_move.from == *coordinate)
+            .map(|chess_move| (chess_move.to, self.captured_coordinate(&chess_move)))
+            .collect())
+    }
 
-                // Pawn's attack move
-                for column_offset in [-1, 1] {
-                    match coordinate.checked_add_individual(single_pawn_move, column_offset) {
-                        Ok(single_coordinate) => {
-                            match self.get_piece(&single_coordinate) {
-                                Some(other_piece) => { 
-                                    if other_piece.team() != piece.team() {
-                                        legal_moves.insert(
-                                            single_coordinate.clone(),
-                                            Some(single_coordinate.clone()),
-                                        );
-                                    }
-                                }
-                                None => {
-                                    legal_moves.insert(single_coordinate, None);
-                                }
-                            }
-                        },
-                        Err(_) => {}
-                    }
-                }
+    /// Checks if a winner can be declared according to the rules of the variant, which in standard chess happens when
+    /// the team to play has been checkmated, and returns the team which won.
+    pub fn winner(&self) -> Option<Team> {
+        self.variant.winner(self)
+    }
 
-                // En Passant rule
-                for column_offset in [-1, 1] {
-                    match coordinate.checked_add_individual(0, column_offset) {
-                        Ok(single_coordinate) => {
-                            match self.get_piece(&single_coordinate) {
-                                Some(other_piece) => { 
-                                    if other_piece.number_of_moves() == 1 && matches!(other_piece.class(), PieceClass::Pawn) {
-                                        if other_piece.team() != piece.team() {
-                                            legal_moves.insert(
-                                                single_coordinate.checked_add_individual(single_pawn_move, 0).unwrap(),
-                                                Some(single_coordinate.clone()),
-                                            );
-                                        }
-                                    }
-                                }
-                                None => {
-                                    legal_moves.insert(single_coordinate, None);
-                                }
-                            }
-                        },
-                        Err(_) => {}
-                    }
-                }
-            } 
-        }
+    /// Checks if the game is drawn according to the rules of the variant.
+    pub fn is_draw(&self) -> bool {
+        self.variant.is_draw(self)
+    }
 
-        return Ok(legal_moves);
+    /// Checks if the team to play is in check and has no legal moves.
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check(self.turn_to_play) && self.legal_moves().is_empty()
     }
 
-    /// Checks if a winner is ready to be declared, declares them the winner, and returns the team which won.
-    pub fn winner(&self) -> Option<Team> {
-        let kings: Vec<Piece> = self.map
-            .iter()
-            .flatten()
-            .cloned()
-            .filter(|x| x.is_some())
-            .map(|x| x.unwrap())
-            .filter(|x| matches!(x.class(), PieceClass::King))
-            .collect::<Vec<Piece>>();
-
-        // Do we have two kings? If so then no winner can be declared
-        if kings.len() == 2 {
-            None
-        } else if kings.len() == 1 {
-            let king: Piece = *kings.get(0).unwrap();
-            Some(king.team())
-        } else {
-            panic!("Impossible case")
-        }
+    /// Checks if the team to play is not in check but has no legal moves.
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_in_check(self.turn_to_play) && self.legal_moves().is_empty()
     }
 
+    /// Gets the FEN of the board. The castling field is written as in X-FEN, which is the same as standard FEN except
+    /// in Chess960 positions where a right can not be told apart by the side of the king alone.
     pub fn fen(&self) -> Fen {
+        self.fen_with_castling_field(self.castling_field(false))
+    }
+
+    /// Gets the FEN of the board with the castling field written as in Shredder-FEN, which names the column of the
+    /// rook of every castling right.
+    pub fn shredder_fen(&self) -> Fen {
+        self.fen_with_castling_field(self.castling_field(true))
+    }
+
+    fn fen_with_castling_field(&self, castling_field: String) -> Fen {
         let mut fen_string: String = String::new();
 
-        // Adding the row states
+        // Adding the row states. Variants with pockets mark the pieces which were promoted from pawns with a tilde.
+        let allows_drops: bool = self.variant.allows_drops();
         for row in self.map() {
             for item in row.iter() {
                 match item {
                     Some(piece) => {
-                        fen_string.push(piece.clone().into())
+                        fen_string.push((*piece).into());
+                        if allows_drops && piece.is_promoted() {
+                            fen_string.push('~');
+                        }
                     },
                     None => { fen_string.push('1') }
                 }
             }
             fen_string.push('/');
         }
-        fen_string = fen_string.trim_end_matches(&['/']).to_string();
+        fen_string = fen_string.trim_end_matches(['/']).to_string();
 
         // Find all of the repeating ones and replace them with their total
         let re: Regex = Regex::new(r"(1+)").unwrap();
@@ -552,6 +681,10 @@ impl Board {
             fen_string = fen_string.replace(key, value.to_string().as_str()).to_string();
         }
         
+        if allows_drops {
+            fen_string.push_str(format!("[{}]", self.pockets).as_str());
+        }
+
         // Adding the final additional information
         fen_string.push(' ');
         match self.turn_to_play {
@@ -559,10 +692,37 @@ impl Board {
             Team::Black => fen_string.push('b'),
         }
 
-        fen_string.push_str(format!(" - - 0 {}", self.history.len() + 1).as_str());
+        let en_passant: String = match self.en_passant {
+            Some(coordinate) => coordinate.to_string().to_lowercase(),
+            None => "-".to_string(),
+        };
+        fen_string.push_str(format!(" {} {}", castling_field, en_passant).as_str());
+        if let Some(winning_checks) = self.variant.winning_checks() {
+            fen_string.push_str(
+                format!(
+                    " {}+{}",
+                    winning_checks.saturating_sub(self.checks_given(Team::White)),
+                    winning_checks.saturating_sub(self.checks_given(Team::Black))
+                )
+                .as_str(),
+            );
+        }
+        fen_string.push_str(format!(" {} {}", self.halfmove_clock, self.fullmove_number).as_str());
 
         Fen { state: fen_string }
     }
+
+    /// Parses the checks of both teams written as two numbers separated by a `+`, white first. The numbers are either
+    /// the checks given so far, or the checks that each team still has to give, which count down from three as in
+    /// Three-check.
+    fn parse_checks_field(&mut self, field: &str, remaining: bool) -> Result<(), BoardError> {
+        let (white, black) = field.split_once('+').ok_or(BoardError::InvalidFen)?;
+        for (team, checks) in [(Team::White, white), (Team::Black, black)] {
+            let checks: u8 = checks.parse().map_err(|_| BoardError::InvalidFen)?;
+            self.checks_given.insert(team, if remaining { 3u8.saturating_sub(checks) } else { checks });
+        }
+        Ok(())
+    }
 }
 
 impl Default for Board {
@@ -577,7 +737,18 @@ impl Default for Board {
             graveyard: Vec::new(),
             team_moves: default_hashmap,
             history: Vec::new(),
-            turn_to_play: Team::White
+            turn_to_play: Team::White,
+            castling_rights: CastlingRights::default(),
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            chess960: false,
+            variant: Arc::new(Standard),
+            pockets: Pockets::default(),
+            received: Vec::new(),
+            checks_given: HashMap::from([(Team::Black, 0), (Team::White, 0)]),
+            starting_fen: None,
+            observers: events::Observers::default(),
         }
     }
 }
@@ -586,44 +757,77 @@ impl Default for Board {
 pub enum BoardError {
     EmptyCoordinate,
     IllegalMove,
-    NotYourTurn
+    NotYourTurn,
+    InvalidFen,
+    InvalidSan,
+    InvalidUci,
+    AmbiguousMove,
+    InvalidChess960Index,
+    UnknownVariant,
+    InvalidEncoding,
+}
+
+impl std::fmt::Display for BoardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let message: &str = match self {
+            BoardError::EmptyCoordinate => "There is no piece on that coordinate.",
+            BoardError::IllegalMove => "That move is not legal.",
+            BoardError::NotYourTurn => "That piece belongs to the other team.",
+            BoardError::InvalidFen => "The FEN is not valid.",
+            BoardError::InvalidSan => "The move is not valid SAN.",
+            BoardError::InvalidUci => "The move is not valid UCI.",
+            BoardError::AmbiguousMove => "More than one piece can make that move.",
+            BoardError::InvalidChess960Index => "Chess960 starting positions are numbered from 0 to 959.",
+            BoardError::UnknownVariant => "There is no variant with that name.",
+            BoardError::InvalidEncoding => "The game could not be decoded.",
+        };
+        write!(f, "{}", message)
+    }
 }
 
 impl std::fmt::Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        for (row_number, row) in self.map().iter().enumerate() {
-            write!(f, "{} ┃ ", 8 - row_number)?;
-            for item in row.iter() {
-                write!(
-                    f,
-                    "{} ",
-                    match item {
-                        Some(item) => format!("{}", item),
-                        None => format!("."),
-                    }
-                )?
-            }
-            write!(f, "\n")?;
-        }
-        write!(f, "  ┗━━━━━━━━━━━━━━━━\n")?;
-        write!(f, "    ")?;
-        for letter in 'A'..'I' {
-            write!(f, "{} ", letter)?;
-        }
-        std::fmt::Result::Ok(())
+        write!(f, "{}", BoardRenderer::new().render(self))
     }
 }
 
 /// Represents a point in the history of the game with information on which pieces moved to which locations
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HistoryNode {
     pub piece: Piece,
     pub from: Coordinate,
     pub to: Coordinate,
+    pub promotion: Option<PieceClass>,
+    pub drop: Option<PieceClass>,
+
+    /// The state of the board before the move, which is not written out since boards are rebuilt by replaying moves.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    undo: UndoState,
+}
+
+/// The state of the board before a move, which is what [`Board::undo_move`] needs to take the move back.
+#[derive(Debug, Clone, Default)]
+struct UndoState {
+    /// The coordinates that the move changed, with the pieces which stood on them before the move.
+    changed: Vec<(Coordinate, Option<Piece>)>,
+
+    graveyard_length: usize,
+    castling_rights: CastlingRights,
+    en_passant: Option<Coordinate>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+
+    /// The class of the captured piece that the move added to the pocket of the moving team.
+    pocketed: Option<PieceClass>,
+
+    /// Whether the move counted as a check given, in variants won by giving checks.
+    gave_check: bool,
 }
 
 /// A Fen representation of the state of a chess board
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct Fen {
     pub state: String
 }
@@ -632,4 +836,79 @@ impl Fen {
     pub fn board_pieces_state(&self) -> String {
         self.state.split(' ').nth(0).unwrap().to_string()
     }
-}
\ No newline at end of file
+
+    /// Gets the team which has the turn to play from the active color field, which is white when the field is missing.
+    /// Returns `None` when the field is neither `w` nor `b`.
+    pub fn turn_to_play(&self) -> Option<Team> {
+        match self.state.split_whitespace().nth(1) {
+            None | Some("w") => Some(Team::White),
+            Some("b") => Some(Team::Black),
+            Some(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::variant::{Atomic, Bughouse, Crazyhouse, ThreeCheck};
+
+    /// Plays the moves on the board, then takes all of them back and checks that every position is restored.
+    fn play_and_undo(board: &mut Board, moves: &[&str]) {
+        let mut fens: Vec<String> = Vec::new();
+        for uci in moves {
+            fens.push(board.fen().state);
+            board.make_move(&board.parse_uci(uci).unwrap()).unwrap();
+        }
+        for fen in fens.into_iter().rev() {
+            assert!(board.undo_move().is_some());
+            assert_eq!(board.fen().state, fen);
+        }
+    }
+
+    #[test]
+    fn undoing_restores_castling_en_passant_and_promotions() {
+        let fen: Fen = Fen { state: "r3k2r/8/8/3pP3/8/8/1p6/R3K2R w KQkq d6 0 1".to_string() };
+        let mut board: Board = Board::new_with_fen(fen);
+
+        play_and_undo(&mut board, &["e5d6", "e8c8", "e1g1", "b2a1q", "d6d7"]);
+        assert!(board.history().is_empty());
+        assert!(board.undo_move().is_none());
+        assert!(board.graveyard().is_empty());
+        assert!(board.legal_moves().contains(&board.parse_uci("e5d6").unwrap()));
+    }
+
+    #[test]
+    fn undoing_restores_the_pockets() {
+        let mut board: Board = Board::new_with_variant(Crazyhouse);
+
+        play_and_undo(&mut board, &["e2e4", "d7d5", "e4d5", "d8d5", "P@e6"]);
+        assert!(board.pocket(Team::White).is_empty());
+        assert!(board.pocket(Team::Black).is_empty());
+    }
+
+    #[test]
+    fn undoing_keeps_pieces_passed_from_the_other_board() {
+        let mut board: Board = Board::new_with_variant(Bughouse);
+        board.make_move(&board.parse_uci("e2e4").unwrap()).unwrap();
+        board.add_to_pocket(Team::White, PieceClass::Knight);
+
+        board.undo_move();
+        assert_eq!(board.pocket(Team::White).count(PieceClass::Knight), 1);
+    }
+
+    #[test]
+    fn undoing_restores_exploded_pieces_and_checks_given() {
+        let mut board: Board = Board::new_with_fen(Fen { state: "4k3/8/2n5/3p4/4P3/8/8/4K3 w - - 0 1".to_string() });
+        board.set_variant(Atomic);
+        play_and_undo(&mut board, &["e4d5"]);
+
+        let mut board: Board = Board::new_with_fen(Fen { state: "4k3/8/8/8/8/8/8/4KQ2 w - - 0 1".to_string() });
+        board.set_variant(ThreeCheck);
+        board.make_move(&board.parse_uci("f1f7").unwrap()).unwrap();
+        assert_eq!(board.checks_given(Team::White), 1);
+        board.undo_move();
+        assert_eq!(board.checks_given(Team::White), 0);
+        assert_eq!(board.team_moves()[&Team::White], 0);
+    }
+}
@@ -0,0 +1,28 @@
+use super::attacks::attacks_with_occupancy;
+use super::Board;
+use crate::bitboard::Bitboard;
+use crate::coordinate::Coordinate;
+use crate::piece::{Piece, PieceClass, Team};
+
+impl Board {
+    /// Explodes the capture made on the coordinate, removing the capturing piece and every piece other than a pawn on
+    /// the coordinates around it. Exploded kings and rooks take the castling rights which rely on them with them.
+    pub(super) fn explode(&mut self, center: &Coordinate) {
+        let neighbours: Bitboard =
+            attacks_with_occupancy(&Piece::new(PieceClass::King, Team::White), center, Bitboard::EMPTY);
+
+        self.remove_piece(center).unwrap();
+        self.castling_rights.remove_rook(center);
+        for coordinate in neighbours {
+            let piece: Piece = match self.get_piece(&coordinate) {
+                Some(piece) if !matches!(piece.class(), PieceClass::Pawn) => piece,
+                _ => continue,
+            };
+            self.remove_piece(&coordinate).unwrap();
+            self.castling_rights.remove_rook(&coordinate);
+            if matches!(piece.class(), PieceClass::King) {
+                self.castling_rights.remove_team(piece.team());
+            }
+        }
+    }
+}
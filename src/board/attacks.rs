@@ -0,0 +1,356 @@
+use super::Board;
+use crate::bitboard::Bitboard;
+use crate::coordinate::Coordinate;
+use crate::piece::{Piece, PieceClass, Team};
+
+impl Board {
+    /// Returns the set of all of the occupied coordinates on the board.
+    pub fn occupancy(&self) -> Bitboard {
+        self.coordinates_where(|_| true)
+    }
+
+    /// Returns the set of all of the coordinates occupied by the pieces of a team.
+    pub fn team_occupancy(&self, team: Team) -> Bitboard {
+        self.coordinates_where(|piece| piece.team() == team)
+    }
+
+    /// Returns the set of all of the coordinates which hold a piece matching the predicate.
+    fn coordinates_where<F: Fn(&Piece) -> bool>(&self, predicate: F) -> Bitboard {
+        let mut bitboard: Bitboard = Bitboard::EMPTY;
+        for (row_index, row) in self.map.iter().enumerate() {
+            for (column_index, item) in row.iter().enumerate() {
+                if matches!(item, Some(piece) if predicate(piece)) {
+                    bitboard.insert(&Coordinate::try_from((row_index, column_index)).unwrap());
+                }
+            }
+        }
+        bitboard
+    }
+
+    /// Gets the coordinate of the king of a team, if the team has a king on the board.
+    pub fn king_coordinate(&self, team: Team) -> Option<Coordinate> {
+        self.coordinates_where(|piece| piece.team() == team && matches!(piece.class(), PieceClass::King))
+            .into_iter()
+            .next()
+    }
+
+    /// Gets the set of coordinates attacked by the piece at the specified coordinate. Unlike the legal moves of the
+    /// piece, this includes coordinates defended by the piece which are occupied by its own team, and only includes the
+    /// diagonal capture coordinates of pawns.
+    pub fn piece_attacks(&self, coordinate: &Coordinate) -> Bitboard {
+        match self.get_piece(coordinate) {
+            Some(piece) => attacks_with_occupancy(&piece, coordinate, self.occupancy()),
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// Gets the set of all of the coordinates attacked by at least one piece of the team.
+    pub fn attacks(&self, team: Team) -> Bitboard {
+        let occupied: Bitboard = self.occupancy();
+        self.team_occupancy(team)
+            .into_iter()
+            .fold(Bitboard::EMPTY, |attacked, coordinate| {
+                attacked | attacks_with_occupancy(&self.get_piece(&coordinate).unwrap(), &coordinate, occupied)
+            })
+    }
+
+    /// Gets the coordinates of all of the pieces of a team which attack the specified coordinate.
+    pub fn attackers_to(&self, coordinate: &Coordinate, team: Team) -> Bitboard {
+        self.attackers_with_occupancy(coordinate, team, self.occupancy())
+    }
+
+    /// Checks if the specified coordinate is attacked by any of the pieces of the given team.
+    pub fn is_attacked(&self, coordinate: &Coordinate, by: Team) -> bool {
+        !self.attackers_to(coordinate, by).is_empty()
+    }
+
+    /// Gets the coordinates of all of the pieces which are currently giving check to the king of the team.
+    pub fn checkers(&self, team: Team) -> Bitboard {
+        match self.king_coordinate(team) {
+            Some(king_coordinate) => self.attackers_to(&king_coordinate, team.opponent()),
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// Checks if the king of the team is currently in check.
+    pub fn is_in_check(&self, team: Team) -> bool {
+        !self.checkers(team).is_empty()
+    }
+
+    /// Gets the coordinates of all of the pieces of the team which are absolutely pinned, meaning that they stand
+    /// between their own king and an attacking sliding piece of the opponent.
+    pub fn pinned(&self, team: Team) -> Bitboard {
+        let king_coordinate: Coordinate = match self.king_coordinate(team) {
+            Some(king_coordinate) => king_coordinate,
+            None => return Bitboard::EMPTY,
+        };
+
+        let mut pinned: Bitboard = Bitboard::EMPTY;
+        for (row_offset, column_offset) in KING_OFFSETS {
+            let mut blocker: Option<Coordinate> = None;
+            for distance in 1..8 {
+                let other_coordinate: Coordinate = match king_coordinate
+                    .checked_add_individual(row_offset * distance, column_offset * distance)
+                {
+                    Ok(other_coordinate) => other_coordinate,
+                    Err(_) => break,
+                };
+                let other_piece: Piece = match self.get_piece(&other_coordinate) {
+                    Some(other_piece) => other_piece,
+                    None => continue,
+                };
+
+                match blocker {
+                    None if other_piece.team() == team => blocker = Some(other_coordinate),
+                    Some(blocker) if other_piece.team() != team => {
+                        if slides_along(other_piece.class(), row_offset, column_offset) {
+                            pinned.insert(&blocker);
+                        }
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        pinned
+    }
+
+    /// Gets the coordinates of all of the pieces of a team which attack the specified coordinate, considering only the
+    /// pieces whose coordinates are in the occupancy. Sliding pieces are blocked by the occupancy rather than by the
+    /// pieces on the board, which allows for pieces to be removed from the board speculatively.
+    pub(crate) fn attackers_with_occupancy(
+        &self,
+        coordinate: &Coordinate,
+        team: Team,
+        occupied: Bitboard,
+    ) -> Bitboard {
+        attackers_in(&self.map, coordinate, team, occupied)
+    }
+}
+
+/// Gets the coordinates of all of the pieces of a team on the map which attack the specified coordinate, considering
+/// only the pieces whose coordinates are in the occupancy.
+pub(crate) fn attackers_in(
+    map: &[[Option<Piece>; 8]; 8],
+    coordinate: &Coordinate,
+    team: Team,
+    occupied: Bitboard,
+) -> Bitboard {
+    let mut attackers: Bitboard = Bitboard::EMPTY;
+    let mut add_if_attacker = |other_coordinate: Coordinate, classes: &[PieceClass]| {
+        if !occupied.contains(&other_coordinate) {
+            return;
+        }
+        if let Some(other_piece) = map[other_coordinate.row()][other_coordinate.column()] {
+            if other_piece.team() == team && classes.contains(&other_piece.class()) {
+                attackers.insert(&other_coordinate);
+            }
+        }
+    };
+
+    // Pawns attack diagonally forward, so they are found diagonally behind the coordinate from their perspective.
+    for column_offset in [-1, 1] {
+        if let Ok(other_coordinate) =
+            coordinate.checked_add_individual(-pawn_direction(team), column_offset)
+        {
+            add_if_attacker(other_coordinate, &[PieceClass::Pawn]);
+        }
+    }
+
+    for (row_offset, column_offset) in KNIGHT_OFFSETS {
+        if let Ok(other_coordinate) = coordinate.checked_add_individual(row_offset, column_offset) {
+            add_if_attacker(other_coordinate, &[PieceClass::Knight]);
+        }
+    }
+
+    for (row_offset, column_offset) in KING_OFFSETS {
+        if let Ok(other_coordinate) = coordinate.checked_add_individual(row_offset, column_offset) {
+            add_if_attacker(other_coordinate, &[PieceClass::King]);
+        }
+    }
+
+    // Sliding pieces are found by walking along each ray until the first occupied coordinate.
+    for (row_offset, column_offset) in KING_OFFSETS {
+        let classes: &[PieceClass] = if row_offset == 0 || column_offset == 0 {
+            &[PieceClass::Rook, PieceClass::Queen]
+        } else {
+            &[PieceClass::Bishop, PieceClass::Queen]
+        };
+        if let Some(other_coordinate) = first_occupied_on_ray(coordinate, row_offset, column_offset, occupied) {
+            add_if_attacker(other_coordinate, classes);
+        }
+    }
+
+    attackers
+}
+
+/// Gets the set of coordinates attacked by a piece standing at the specified coordinate where the sliding pieces are
+/// blocked by the occupancy.
+pub(crate) fn attacks_with_occupancy(piece: &Piece, coordinate: &Coordinate, occupied: Bitboard) -> Bitboard {
+    let offsets_to_bitboard = |offsets: &[(i8, i8)]| -> Bitboard {
+        offsets
+            .iter()
+            .flat_map(|(row_offset, column_offset)| coordinate.checked_add_individual(*row_offset, *column_offset))
+            .collect()
+    };
+
+    match piece.class() {
+        PieceClass::Pawn => {
+            offsets_to_bitboard(&[(pawn_direction(piece.team()), -1), (pawn_direction(piece.team()), 1)])
+        }
+        PieceClass::Knight => offsets_to_bitboard(&KNIGHT_OFFSETS),
+        PieceClass::King => offsets_to_bitboard(&KING_OFFSETS),
+        PieceClass::Bishop | PieceClass::Rook | PieceClass::Queen => KING_OFFSETS
+            .iter()
+            .filter(|(row_offset, column_offset)| slides_along(piece.class(), *row_offset, *column_offset))
+            .flat_map(|(row_offset, column_offset)| ray(coordinate, *row_offset, *column_offset, occupied))
+            .collect(),
+    }
+}
+
+/// Gets the coordinates along a ray starting next to the coordinate and going in the direction of the offsets. The ray
+/// stops at, and includes, the first coordinate found in the occupancy.
+pub(crate) fn ray(coordinate: &Coordinate, row_offset: i8, column_offset: i8, occupied: Bitboard) -> Bitboard {
+    let mut ray: Bitboard = Bitboard::EMPTY;
+    for distance in 1..8 {
+        match coordinate.checked_add_individual(row_offset * distance, column_offset * distance) {
+            Ok(other_coordinate) => {
+                ray.insert(&other_coordinate);
+                if occupied.contains(&other_coordinate) {
+                    break;
+                }
+            }
+            Err(_) => break,
+        }
+    }
+    ray
+}
+
+/// Gets the first coordinate in the occupancy along the ray starting next to the coordinate.
+fn first_occupied_on_ray(
+    coordinate: &Coordinate,
+    row_offset: i8,
+    column_offset: i8,
+    occupied: Bitboard,
+) -> Option<Coordinate> {
+    (1..8)
+        .map_while(|distance| coordinate.checked_add_individual(row_offset * distance, column_offset * distance).ok())
+        .find(|other_coordinate| occupied.contains(other_coordinate))
+}
+
+/// Checks if a piece of the given class slides along the direction of the offsets.
+pub(crate) fn slides_along(class: PieceClass, row_offset: i8, column_offset: i8) -> bool {
+    let is_orthogonal: bool = row_offset == 0 || column_offset == 0;
+    match class {
+        PieceClass::Queen => true,
+        PieceClass::Rook => is_orthogonal,
+        PieceClass::Bishop => !is_orthogonal,
+        _ => false,
+    }
+}
+
+/// The row offset of a single step forward for a pawn of the team.
+pub(crate) fn pawn_direction(team: Team) -> i8 {
+    match team {
+        Team::Black => 1,
+        Team::White => -1,
+    }
+}
+
+/// The offsets from a coordinate to all of the coordinates that a knight can jump to.
+pub(crate) const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+/// The offsets from a coordinate to all of its neighbouring coordinates, which are also the directions of the rays that
+/// sliding pieces move along.
+pub(crate) const KING_OFFSETS: [(i8, i8); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+#[cfg(test)]
+pub(in crate::board) mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    pub(in crate::board) fn board_from_fen(fen: &str) -> Board {
+        Board::new_with_fen(Fen { state: fen.to_string() })
+    }
+
+    pub(in crate::board) fn coordinate(name: &str) -> Coordinate {
+        Coordinate::try_from(name).unwrap()
+    }
+
+    pub(in crate::board) fn coordinates(names: &[&str]) -> Bitboard {
+        names.iter().map(|name| coordinate(name)).collect()
+    }
+
+    #[test]
+    fn attackers_of_every_class_are_found() {
+        let board: Board = board_from_fen("3r3k/B7/8/4p3/7Q/2PPKN2/8/3R4 w - - 0 1");
+        let target: Coordinate = coordinate("d4");
+
+        // The pawn on d3 only pushes to d4, and the rook on d1 is blocked by it.
+        assert_eq!(board.attackers_to(&target, Team::White), coordinates(&["a7", "c3", "e3", "f3", "h4"]));
+        assert_eq!(board.attackers_to(&target, Team::Black), coordinates(&["d8", "e5"]));
+        assert!(board.is_attacked(&target, Team::Black));
+        assert!(board.is_attacked(&coordinate("d2"), Team::White));
+        assert!(!board.is_attacked(&coordinate("d2"), Team::Black));
+    }
+
+    #[test]
+    fn pawns_attack_diagonally_but_not_forward() {
+        let board: Board = board_from_fen("4k3/8/8/4p3/8/3P4/8/P3K3 w - - 0 1");
+
+        assert_eq!(board.piece_attacks(&coordinate("d3")), coordinates(&["c4", "e4"]));
+        assert_eq!(board.piece_attacks(&coordinate("e5")), coordinates(&["d4", "f4"]));
+        assert_eq!(board.piece_attacks(&coordinate("a1")), coordinates(&["b2"]));
+        assert!(!board.is_attacked(&coordinate("d4"), Team::White));
+        assert!(!board.is_attacked(&coordinate("e4"), Team::Black));
+    }
+
+    #[test]
+    fn sliding_attacks_stop_at_the_first_piece() {
+        let board: Board = board_from_fen("4k3/8/8/8/1p1R3P/8/8/4K3 w - - 0 1");
+
+        let row: Bitboard = coordinates(&["b4", "c4", "e4", "f4", "g4", "h4"]);
+        let column: Bitboard = coordinates(&["d1", "d2", "d3", "d5", "d6", "d7", "d8"]);
+        assert_eq!(board.piece_attacks(&coordinate("d4")), row | column);
+        assert!(board.attacks(Team::White).contains(&coordinate("h4")));
+        assert!(!board.attacks(Team::White).contains(&coordinate("a4")));
+    }
+
+    #[test]
+    fn every_piece_giving_check_is_a_checker() {
+        let board: Board = board_from_fen("4k3/8/8/1B6/8/8/8/4R1K1 b - - 0 1");
+
+        assert_eq!(board.checkers(Team::Black), coordinates(&["b5", "e1"]));
+        assert!(board.is_in_check(Team::Black));
+        assert!(board.checkers(Team::White).is_empty());
+        assert!(!board.is_in_check(Team::White));
+    }
+
+    #[test]
+    fn teams_without_a_king_are_never_in_check() {
+        let board: Board = board_from_fen("8/8/8/8/8/8/8/R3K3 w - - 0 1");
+
+        assert_eq!(board.king_coordinate(Team::Black), None);
+        assert!(board.checkers(Team::Black).is_empty());
+        assert!(board.pinned(Team::Black).is_empty());
+    }
+}
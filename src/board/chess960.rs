@@ -0,0 +1,258 @@
+use super::{Board, BoardError};
+use crate::castling::{back_row, CastlingRights, CastlingSide};
+use crate::coordinate::Coordinate;
+use crate::piece::{Piece, PieceClass, Team};
+
+/// The index of the standard starting position in the numbering of the Chess960 starting positions.
+pub const STANDARD_CHESS960_INDEX: u16 = 518;
+
+/// The ways to place the two knights on the five columns left after placing the bishops and the queen, in the order of
+/// the numbering of the Chess960 starting positions.
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+impl Board {
+    /// Creates the Chess960 starting position with the index, from 0 to 959, using the numbering of Reinhard Scharnagl
+    /// in which 518 is the standard starting position. Castling moves on the board are written as the king capturing its
+    /// own rook.
+    pub fn new_chess960(index: u16) -> Result<Self, BoardError> {
+        if index >= 960 {
+            return Err(BoardError::InvalidChess960Index);
+        }
+
+        let mut board: Self = Self::default();
+        let back_row_classes: [PieceClass; 8] = chess960_back_row(index as usize);
+        for team in [Team::White, Team::Black] {
+            let pawn_row: usize = if matches!(team, Team::White) { 6 } else { 1 };
+            for (column, class) in back_row_classes.iter().enumerate() {
+                board.set_piece(
+                    &Coordinate::try_from((back_row(team), column)).unwrap(),
+                    Some(Piece::new(*class, team)),
+                );
+                board.set_piece(
+                    &Coordinate::try_from((pawn_row, column)).unwrap(),
+                    Some(Piece::new(PieceClass::Pawn, team)),
+                );
+            }
+        }
+
+        // The rooks are always on both sides of the king, so every team can castle with both of them.
+        let mut rook_columns = back_row_classes
+            .iter()
+            .enumerate()
+            .filter(|(_, class)| matches!(class, PieceClass::Rook))
+            .map(|(column, _)| column);
+        let (queen_side_rook, king_side_rook) = (rook_columns.next(), rook_columns.next());
+        for team in [Team::White, Team::Black] {
+            board.castling_rights.set(team, CastlingSide::KingSide, king_side_rook);
+            board.castling_rights.set(team, CastlingSide::QueenSide, queen_side_rook);
+        }
+        board.chess960 = true;
+
+        Ok(board)
+    }
+
+    /// Checks if the board follows the Chess960 castling rules.
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
+    }
+
+    /// Sets whether the board follows the Chess960 castling rules. This only changes how castling moves are written, so
+    /// it can be used to play the standard starting position as a Chess960 game.
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.chess960 = chess960;
+    }
+
+    /// Parses the castling field of a FEN, which can be written as in standard FEN, X-FEN, or Shredder-FEN. `K` and `Q`
+    /// name the outermost rook on their side of the king, while the letters of the columns name the rook directly. The
+    /// board switches to the Chess960 castling rules when the field names rooks by their column or when the king or the
+    /// rooks are not on their standard coordinates.
+    pub(super) fn parse_castling_field(&mut self, field: &str) -> Result<(), BoardError> {
+        self.castling_rights = CastlingRights::default();
+        if field == "-" {
+            return Ok(());
+        }
+
+        for character in field.chars() {
+            let team: Team = if character.is_ascii_uppercase() { Team::White } else { Team::Black };
+            let row: usize = back_row(team);
+            let king_column: usize = self
+                .king_coordinate(team)
+                .filter(|king_coordinate| king_coordinate.row() == row)
+                .map_or(4, |king_coordinate| king_coordinate.column());
+
+            let (side, rook_column) = match character.to_ascii_lowercase() {
+                'k' => (
+                    CastlingSide::KingSide,
+                    self.outermost_rook_column(team, (king_column + 1..8).rev()).unwrap_or(7),
+                ),
+                'q' => (
+                    CastlingSide::QueenSide,
+                    self.outermost_rook_column(team, 0..king_column).unwrap_or(0),
+                ),
+                column_letter @ 'a'..='h' => {
+                    let column: usize = (column_letter as u8 - b'a') as usize;
+                    self.chess960 = true;
+                    match column.cmp(&king_column) {
+                        std::cmp::Ordering::Greater => (CastlingSide::KingSide, column),
+                        std::cmp::Ordering::Less => (CastlingSide::QueenSide, column),
+                        std::cmp::Ordering::Equal => return Err(BoardError::InvalidFen),
+                    }
+                }
+                _ => return Err(BoardError::InvalidFen),
+            };
+
+            let standard_column: usize = match side {
+                CastlingSide::KingSide => 7,
+                CastlingSide::QueenSide => 0,
+            };
+            if king_column != 4 || rook_column != standard_column {
+                self.chess960 = true;
+            }
+            self.castling_rights.set(team, side, Some(rook_column));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the castling rights as the castling field of a FEN. Shredder-FEN writes every right as the column of its
+    /// rook, while X-FEN only does so when the rook is not the outermost one on its side of the king, so that it matches
+    /// standard FEN for all of the positions which standard FEN can describe.
+    pub(super) fn castling_field(&self, shredder: bool) -> String {
+        let mut field: String = String::new();
+
+        for team in [Team::White, Team::Black] {
+            for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+                let rook_column: usize = match self.castling_rights.rook_column(team, side) {
+                    Some(rook_column) => rook_column,
+                    None => continue,
+                };
+
+                let outer_columns = match side {
+                    CastlingSide::KingSide => rook_column + 1..8,
+                    CastlingSide::QueenSide => 0..rook_column,
+                };
+                let character: char = if !shredder && self.outermost_rook_column(team, outer_columns).is_none() {
+                    match side {
+                        CastlingSide::KingSide => 'k',
+                        CastlingSide::QueenSide => 'q',
+                    }
+                } else {
+                    (b'a' + rook_column as u8) as char
+                };
+
+                field.push(match team {
+                    Team::White => character.to_ascii_uppercase(),
+                    Team::Black => character,
+                });
+            }
+        }
+
+        if field.is_empty() {
+            field.push('-');
+        }
+        field
+    }
+
+    /// Gets the first column of the columns with a rook of the team on its back row.
+    fn outermost_rook_column<I: Iterator<Item = usize>>(&self, team: Team, mut columns: I) -> Option<usize> {
+        columns.find(|column| {
+            matches!(
+                self.get_piece(&Coordinate::try_from((back_row(team), *column)).unwrap()),
+                Some(piece) if piece.team() == team && matches!(piece.class(), PieceClass::Rook)
+            )
+        })
+    }
+}
+
+/// Gets the pieces of the back row of the Chess960 starting position with the index. The digits of the index in mixed
+/// radix give, in order, the column of the bishop on a light square, the column of the bishop on a dark square, the
+/// empty column that the queen is on, and the placement of the knights. The rooks and the king fill the remaining three
+/// columns with the king in the middle.
+fn chess960_back_row(index: usize) -> [PieceClass; 8] {
+    let mut row: [Option<PieceClass>; 8] = [None; 8];
+    let mut index: usize = index;
+
+    row[2 * (index % 4) + 1] = Some(PieceClass::Bishop);
+    index /= 4;
+    row[2 * (index % 4)] = Some(PieceClass::Bishop);
+    index /= 4;
+
+    let empty_columns = |row: &[Option<PieceClass>; 8]| -> Vec<usize> {
+        (0..8).filter(|column| row[*column].is_none()).collect()
+    };
+
+    row[empty_columns(&row)[index % 6]] = Some(PieceClass::Queen);
+    index /= 6;
+
+    let empty: Vec<usize> = empty_columns(&row);
+    let (first_knight, second_knight) = KNIGHT_PLACEMENTS[index];
+    row[empty[first_knight]] = Some(PieceClass::Knight);
+    row[empty[second_knight]] = Some(PieceClass::Knight);
+
+    for (column, class) in empty_columns(&row)
+        .into_iter()
+        .zip([PieceClass::Rook, PieceClass::King, PieceClass::Rook])
+    {
+        row[column] = Some(class);
+    }
+
+    row.map(Option::unwrap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::movegen::tests::{board_from_fen, perft};
+
+    #[test]
+    fn the_standard_index_is_the_standard_starting_position() {
+        let board: Board = Board::new_chess960(STANDARD_CHESS960_INDEX).unwrap();
+
+        assert_eq!(board.fen().state, Board::new().fen().state);
+        assert_eq!(board.shredder_fen().state, "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1");
+        assert!(matches!(Board::new_chess960(960), Err(BoardError::InvalidChess960Index)));
+    }
+
+    #[test]
+    fn starting_positions_follow_the_scharnagl_numbering() {
+        let placement = |index: u16| Board::new_chess960(index).unwrap().fen().board_pieces_state();
+
+        assert_eq!(placement(0), "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR");
+        assert_eq!(placement(959), "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/RKRNNQBB");
+    }
+
+    #[test]
+    fn perft_of_chess960_positions() {
+        let positions: [(&str, &[usize]); 5] = [
+            ("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", &[21, 528, 12189]),
+            ("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9", &[21, 807]),
+            ("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9", &[20, 479]),
+            ("qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9", &[22, 593]),
+            ("1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9", &[28, 1120]),
+        ];
+        for (fen, counts) in positions {
+            let board: Board = board_from_fen(fen);
+            assert!(board.is_chess960(), "{}", fen);
+            for (depth, count) in counts.iter().enumerate() {
+                assert_eq!(perft(&board, depth + 1), *count, "{} at depth {}", fen, depth + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn castling_fields_round_trip_through_shredder_fen() {
+        let fen: &str = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+        assert_eq!(board_from_fen(fen).shredder_fen().state, fen);
+    }
+}
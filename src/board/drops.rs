@@ -0,0 +1,186 @@
+use super::attacks::attackers_in;
+use super::{Board, BoardError};
+use crate::castling::back_row;
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+use crate::pocket::{Pocket, Pockets, POCKET_CLASSES};
+
+impl Board {
+    /// Gets the pockets of both teams. Pockets are only used by variants which allow drops, and are empty otherwise.
+    pub fn pockets(&self) -> &Pockets {
+        &self.pockets
+    }
+
+    pub fn pocket(&self, team: Team) -> &Pocket {
+        self.pockets.get(team)
+    }
+
+    /// Adds a piece of the class to the pocket of the team, which is how the pieces captured on the other board reach a
+    /// team in Bughouse. Returns `false` if the pocket can not hold any more pieces of the class.
+    pub fn add_to_pocket(&mut self, team: Team, class: PieceClass) -> bool {
+        let added: bool = self.pockets.get_mut(team).add(class);
+        // Pieces added before the first move are part of the starting position.
+        if added && !self.history.is_empty() {
+            self.received.push((self.history.len(), team, class));
+        }
+        added
+    }
+
+    /// Gets the pieces added to the pockets by [`Board::add_to_pocket`] after the first move, along with the number of
+    /// moves which had been made when they were added.
+    pub fn received_pieces(&self) -> &[(usize, Team, PieceClass)] {
+        &self.received
+    }
+
+    /// Adds the drops of the team to the moves. A piece in the pocket can be dropped on any empty coordinate, except for
+    /// pawns which can not be dropped on the first or the last row, as long as the drop does not leave the king of the
+    /// team in check.
+    pub(crate) fn drop_moves(&self, team: Team, moves: &mut Vec<Move>) {
+        let pocket: &Pocket = self.pockets.get(team);
+        if pocket.is_empty() {
+            return;
+        }
+
+        // Dropping a piece can only block attacks on the king, so when the king is not in check every drop is legal.
+        let king_coordinate: Option<Coordinate> = self.king_coordinate(team).filter(|_| self.is_in_check(team));
+        let occupied = self.occupancy();
+
+        for to in !occupied {
+            let blocks_check: bool = match king_coordinate {
+                Some(king_coordinate) => {
+                    let mut map: [[Option<Piece>; 8]; 8] = self.map;
+                    map[to.row()][to.column()] = Some(Piece::new(PieceClass::Pawn, team));
+                    let mut blocked = occupied;
+                    blocked.insert(&to);
+                    attackers_in(&map, &king_coordinate, team.opponent(), blocked).is_empty()
+                }
+                None => true,
+            };
+            if !blocks_check {
+                continue;
+            }
+
+            let allows_pawns: bool = to.row() != back_row(Team::White) && to.row() != back_row(Team::Black);
+            for class in POCKET_CLASSES.into_iter().filter(|class| pocket.count(*class) > 0) {
+                if !matches!(class, PieceClass::Pawn) || allows_pawns {
+                    moves.push(Move::new_drop(class, to));
+                }
+            }
+        }
+    }
+
+    /// Takes a piece of the class out of the pocket of the team to play and places it on the coordinate.
+    pub(super) fn drop_piece(&mut self, class: PieceClass, to: &Coordinate) -> Piece {
+        let team: Team = self.turn_to_play;
+        self.pockets.get_mut(team).remove(class);
+        let piece: Piece = Piece::new(class, team);
+        self.set_piece(to, Some(piece));
+        piece
+    }
+
+    /// Adds a captured piece to the pocket of the team which captured it, and returns the class it was added as, or
+    /// `None` if the pocket is full. Promoted pieces go back to being pawns.
+    pub(super) fn pocket_piece(&mut self, team: Team, captured_piece: &Piece) -> Option<PieceClass> {
+        let class: PieceClass = if captured_piece.is_promoted() {
+            PieceClass::Pawn
+        } else {
+            captured_piece.class()
+        };
+        self.pockets.get_mut(team).add(class).then_some(class)
+    }
+
+    /// Parses the pockets written after the piece placement of a FEN, either inside brackets or as a ninth row.
+    pub(super) fn parse_pockets(&mut self, pockets: &str) -> Result<(), BoardError> {
+        self.pockets = Pockets::try_from(pockets).map_err(|_| BoardError::InvalidFen)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+    use crate::variant::Crazyhouse;
+
+    fn crazyhouse_from_fen(fen: &str) -> Board {
+        let mut board: Board = Board::try_new_with_fen(Fen { state: fen.to_string() }).unwrap();
+        board.set_variant(Crazyhouse);
+        board
+    }
+
+    #[test]
+    fn drops_are_parsed_and_written() {
+        let mut board: Board = crazyhouse_from_fen("4k3/8/8/8/8/8/8/4K3[NPp] w - - 0 1");
+
+        let chess_move: Move = board.parse_uci("N@f3").unwrap();
+        assert_eq!(chess_move, Move::new_drop(PieceClass::Knight, Coordinate::try_from("f3").unwrap()));
+        assert_eq!(board.parse_san("N@f3").unwrap(), chess_move);
+        assert_eq!(board.parse_san("@e4").unwrap(), board.parse_san("P@e4").unwrap());
+        assert_eq!(board.uci(&chess_move), "N@f3");
+        assert_eq!(board.san(&chess_move), "N@f3");
+
+        board.make_move(&chess_move).unwrap();
+        assert_eq!(board.pocket(Team::White).count(PieceClass::Knight), 0);
+        assert_eq!(board.fen().state, "4k3/8/8/8/8/5N2/8/4K3[Pp] b - - 1 1");
+        assert!(matches!(board.parse_uci("Q@d4"), Err(BoardError::IllegalMove)));
+    }
+
+    #[test]
+    fn malformed_drops_are_rejected() {
+        let board: Board = crazyhouse_from_fen("4k3/8/8/8/8/8/8/4K3[NPp] w - - 0 1");
+        for input in ["N@é", "é@e4", "♞@f3", "@", "N@", "X@e4", "N@e44", "N@i9"] {
+            assert!(board.parse_uci(input).is_err(), "{}", input);
+            assert!(board.parse_san(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn pawns_are_not_dropped_on_the_first_or_last_row() {
+        let board: Board = crazyhouse_from_fen("4k3/8/8/8/8/8/8/4K3[P] w - - 0 1");
+
+        let drops: Vec<Move> = board.legal_moves().into_iter().filter(|chess_move| chess_move.drop.is_some()).collect();
+        assert_eq!(drops.len(), 6 * 8);
+        assert!(drops.iter().all(|chess_move| (1..7).contains(&chess_move.to.row())));
+        assert!(matches!(board.parse_uci("P@a8"), Err(BoardError::IllegalMove)));
+        assert!(matches!(board.parse_uci("P@a1"), Err(BoardError::IllegalMove)));
+        assert!(board.parse_uci("P@a7").is_ok());
+    }
+
+    #[test]
+    fn only_drops_which_block_the_check_are_legal() {
+        let board: Board = crazyhouse_from_fen("4r2k/8/8/8/8/8/8/4K3[N] w - - 0 1");
+
+        let mut drops: Vec<String> = board
+            .legal_moves()
+            .iter()
+            .filter(|chess_move| chess_move.drop.is_some())
+            .map(|chess_move| board.uci(chess_move))
+            .collect();
+        drops.sort();
+        assert_eq!(drops, ["N@e2", "N@e3", "N@e4", "N@e5", "N@e6", "N@e7"]);
+    }
+
+    #[test]
+    fn promoted_pieces_go_back_to_the_pocket_as_pawns() {
+        let mut board: Board = crazyhouse_from_fen("r3k3/1P6/8/8/8/8/8/4K3[] w - - 0 1");
+        board.make_move(&board.parse_uci("b7b8q").unwrap()).unwrap();
+        board.make_move(&board.parse_uci("a8b8").unwrap()).unwrap();
+
+        assert_eq!(board.pocket(Team::Black).count(PieceClass::Pawn), 1);
+        assert_eq!(board.pocket(Team::Black).count(PieceClass::Queen), 0);
+        assert_eq!(board.fen().board_pieces_state(), "1r2k3/8/8/8/8/8/8/4K3[p]");
+    }
+
+    #[test]
+    fn pockets_which_overflow_are_rejected() {
+        let fen = |queens: usize| Fen { state: format!("4k3/8/8/8/8/8/8/4K3[{}] w - - 0 1", "Q".repeat(queens)) };
+
+        let mut board: Board = Board::try_new_with_fen(fen(255)).unwrap();
+        assert_eq!(board.pocket(Team::White).count(PieceClass::Queen), 255);
+        assert!(!board.add_to_pocket(Team::White, PieceClass::Queen));
+        assert_eq!(board.pocket(Team::White).count(PieceClass::Queen), 255);
+        assert!(matches!(Board::try_new_with_fen(fen(256)), Err(BoardError::InvalidFen)));
+        assert!(Pockets::try_from("Q".repeat(256).as_str()).is_err());
+    }
+}
@@ -0,0 +1,348 @@
+use super::{Board, BoardError};
+use crate::castling::{back_row, CastlingSide};
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+
+/// The codes of the pieces in an encoded position. Pieces which carry part of the state of the position use the codes
+/// after the twelve plain pieces.
+const PIECE_CODES: [(PieceClass, Team); 12] = [
+    (PieceClass::Pawn, Team::White),
+    (PieceClass::Pawn, Team::Black),
+    (PieceClass::Knight, Team::White),
+    (PieceClass::Knight, Team::Black),
+    (PieceClass::Bishop, Team::White),
+    (PieceClass::Bishop, Team::Black),
+    (PieceClass::Rook, Team::White),
+    (PieceClass::Rook, Team::Black),
+    (PieceClass::Queen, Team::White),
+    (PieceClass::Queen, Team::Black),
+    (PieceClass::King, Team::White),
+    (PieceClass::King, Team::Black),
+];
+
+/// A pawn which has just moved two squares forward and can be captured en passant. Its team follows from its row.
+const EN_PASSANT_PAWN_CODE: u8 = 12;
+
+/// A rook which its team can still castle with.
+const WHITE_CASTLING_ROOK_CODE: u8 = 13;
+const BLACK_CASTLING_ROOK_CODE: u8 = 14;
+
+/// The black king when black has the turn to play.
+const BLACK_KING_TO_PLAY_CODE: u8 = 15;
+
+impl Board {
+    /// Encodes the position in a compact binary form of about 24 to 32 bytes: a bitmap of the occupied coordinates,
+    /// the pieces on them packed two to a byte, and the halfmove clock and the number of half moves played as variable
+    /// length integers. The turn to play, the castling rights, and the en passant coordinate are folded into the codes
+    /// of the black king, the rooks, and the pawn which just moved two squares. Pockets and checks given in variants are
+    /// not encoded.
+    pub fn encode_position(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(32);
+        let mut occupancy: u64 = 0;
+        let mut codes: Vec<u8> = Vec::with_capacity(32);
+
+        for (row_index, row) in self.map.iter().enumerate() {
+            for (column_index, piece) in row.iter().enumerate() {
+                if let Some(piece) = piece {
+                    let coordinate: Coordinate = Coordinate::try_from((row_index, column_index)).unwrap();
+                    occupancy |= 1 << coordinate.index();
+                    codes.push(self.piece_code(piece, &coordinate));
+                }
+            }
+        }
+
+        bytes.extend_from_slice(&occupancy.to_be_bytes());
+        for pair in codes.chunks(2) {
+            bytes.push(pair[0] << 4 | pair.get(1).copied().unwrap_or(0));
+        }
+        write_varint(&mut bytes, self.halfmove_clock as u64);
+        write_varint(&mut bytes, self.ply());
+        bytes
+    }
+
+    /// Decodes a position encoded by [`Board::encode_position`].
+    pub fn decode_position(bytes: &[u8]) -> Result<Self, BoardError> {
+        let mut reader: Reader = Reader { bytes, position: 0 };
+        let board: Self = Self::read_position(&mut reader)?;
+        match reader.position == bytes.len() {
+            true => Ok(board),
+            false => Err(BoardError::InvalidEncoding),
+        }
+    }
+
+    /// Encodes the game as its starting position, encoded by [`Board::encode_position`], followed by the index of every
+    /// move among the legal moves of its position, which takes a single byte for any position of standard chess. Like
+    /// the position, the variant is not encoded, so games are decoded as standard chess or Chess960.
+    pub fn encode_game(&self) -> Result<Vec<u8>, BoardError> {
+        let mut board: Self = self.starting_board()?;
+        let mut bytes: Vec<u8> = board.encode_position();
+
+        for node in self.history.iter() {
+            let chess_move: Move = Move {
+                from: node.from,
+                to: node.to,
+                promotion: node.promotion,
+                drop: node.drop,
+            };
+            let index: usize = board
+                .sorted_legal_moves()
+                .iter()
+                .position(|legal_move| *legal_move == chess_move)
+                .ok_or(BoardError::IllegalMove)?;
+            write_varint(&mut bytes, index as u64);
+            board.apply_move(&chess_move);
+        }
+        Ok(bytes)
+    }
+
+    /// Decodes a game encoded by [`Board::encode_game`] into a board with all of its moves in the history.
+    pub fn decode_game(bytes: &[u8]) -> Result<Self, BoardError> {
+        let mut reader: Reader = Reader { bytes, position: 0 };
+        let mut board: Self = Self::read_position(&mut reader)?;
+
+        while reader.position < bytes.len() {
+            let index: usize = reader.read_varint()? as usize;
+            let chess_move: Move = *board.sorted_legal_moves().get(index).ok_or(BoardError::InvalidEncoding)?;
+            board.apply_move(&chess_move);
+        }
+        Ok(board)
+    }
+
+    /// Gets the legal moves ordered by their coordinates, then by their promotion, which is the order that the moves of
+    /// an encoded game are numbered in.
+    fn sorted_legal_moves(&self) -> Vec<Move> {
+        let mut moves: Vec<Move> = self.legal_moves();
+        moves.sort_by_key(|chess_move| {
+            (
+                chess_move.from.index(),
+                chess_move.to.index(),
+                chess_move.promotion.map(|class| class as u8),
+                chess_move.drop.map(|class| class as u8),
+            )
+        });
+        moves
+    }
+
+    /// The number of half moves played since the start of the game, as counted by the fullmove number. A fullmove
+    /// number of 0 is counted as 1.
+    fn ply(&self) -> u64 {
+        2 * u64::from(self.fullmove_number.saturating_sub(1)) + matches!(self.turn_to_play, Team::Black) as u64
+    }
+
+    fn piece_code(&self, piece: &Piece, coordinate: &Coordinate) -> u8 {
+        let team: Team = piece.team();
+        match piece.class() {
+            PieceClass::Pawn if self.en_passant_pawn() == Some(*coordinate) => EN_PASSANT_PAWN_CODE,
+            PieceClass::Rook
+                if coordinate.row() == back_row(team)
+                    && [CastlingSide::KingSide, CastlingSide::QueenSide]
+                        .iter()
+                        .any(|side| self.castling_rights.rook_column(team, *side) == Some(coordinate.column())) =>
+            {
+                match team {
+                    Team::White => WHITE_CASTLING_ROOK_CODE,
+                    Team::Black => BLACK_CASTLING_ROOK_CODE,
+                }
+            }
+            PieceClass::King if team == Team::Black && self.turn_to_play == Team::Black => BLACK_KING_TO_PLAY_CODE,
+            class => PIECE_CODES.iter().position(|code| *code == (class, team)).unwrap() as u8,
+        }
+    }
+
+    /// Gets the coordinate of the pawn which can be captured en passant, which stands one row past the en passant
+    /// coordinate in the direction that it moved.
+    fn en_passant_pawn(&self) -> Option<Coordinate> {
+        let en_passant: Coordinate = self.en_passant?;
+        let row: usize = if en_passant.row() == 5 { 4 } else { 3 };
+        Coordinate::try_from((row, en_passant.column())).ok()
+    }
+
+    fn read_position(reader: &mut Reader) -> Result<Self, BoardError> {
+        let mut board: Self = Self::default();
+        let occupancy: u64 = u64::from_be_bytes(reader.read_bytes(8)?.try_into().unwrap());
+        let packed: &[u8] = reader.read_bytes((occupancy.count_ones() as usize).div_ceil(2))?;
+        let mut castling_rooks: Vec<(Team, Coordinate)> = Vec::new();
+
+        // Whether the codes of the black kings say that black has the turn to play, which must agree with the number of
+        // half moves played. Positions without a black king only have the number of half moves to go by.
+        let mut black_king_to_play: Option<bool> = None;
+
+        let coordinates = (0..64usize).filter(|index| occupancy & 1 << index != 0);
+        for (number, index) in coordinates.enumerate() {
+            let coordinate: Coordinate = Coordinate::try_from((index / 8, index % 8)).unwrap();
+            let code: u8 = (packed[number / 2] >> (4 * (1 - number % 2))) & 0xF;
+            let (class, team) = match code {
+                EN_PASSANT_PAWN_CODE => {
+                    let (team, en_passant_row) = match coordinate.row() {
+                        4 => (Team::White, 5),
+                        3 => (Team::Black, 2),
+                        _ => return Err(BoardError::InvalidEncoding),
+                    };
+                    board.en_passant = Some(Coordinate::try_from((en_passant_row, coordinate.column())).unwrap());
+                    (PieceClass::Pawn, team)
+                }
+                WHITE_CASTLING_ROOK_CODE | BLACK_CASTLING_ROOK_CODE => {
+                    let team: Team = if code == WHITE_CASTLING_ROOK_CODE { Team::White } else { Team::Black };
+                    castling_rooks.push((team, coordinate));
+                    (PieceClass::Rook, team)
+                }
+                BLACK_KING_TO_PLAY_CODE => (PieceClass::King, Team::Black),
+                code => PIECE_CODES[code as usize],
+            };
+            if (class, team) == (PieceClass::King, Team::Black) {
+                let to_play: bool = code == BLACK_KING_TO_PLAY_CODE;
+                if black_king_to_play.is_some_and(|black_king_to_play| black_king_to_play != to_play) {
+                    return Err(BoardError::InvalidEncoding);
+                }
+                black_king_to_play = Some(to_play);
+            }
+            board.set_piece(&coordinate, Some(Piece::new(class, team)));
+        }
+
+        // The castling rooks are written as a castling field so that the board switches to the Chess960 castling rules
+        // in the same cases as when it is set up from a FEN.
+        let mut castling_field: String = String::new();
+        for (team, coordinate) in castling_rooks {
+            let king_column: usize = board
+                .king_coordinate(team)
+                .filter(|king_coordinate| king_coordinate.row() == back_row(team))
+                .ok_or(BoardError::InvalidEncoding)?
+                .column();
+            let letter: char = match (king_column, coordinate.column()) {
+                (4, 7) => 'k',
+                (4, 0) => 'q',
+                (_, column) => (b'a' + column as u8) as char,
+            };
+            castling_field.push(if matches!(team, Team::White) { letter.to_ascii_uppercase() } else { letter });
+        }
+        if !castling_field.is_empty() {
+            board.parse_castling_field(&castling_field)?;
+        }
+
+        board.halfmove_clock = reader.read_varint()?.try_into().map_err(|_| BoardError::InvalidEncoding)?;
+        let ply: u64 = reader.read_varint()?;
+        board.fullmove_number = (ply / 2 + 1).try_into().map_err(|_| BoardError::InvalidEncoding)?;
+        board.turn_to_play = if ply % 2 == 1 { Team::Black } else { Team::White };
+        if black_king_to_play.is_some_and(|black_king_to_play| black_king_to_play != (ply % 2 == 1)) {
+            return Err(BoardError::InvalidEncoding);
+        }
+        Ok(board)
+    }
+}
+
+/// Reads an encoded position or game from the start.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], BoardError> {
+        let bytes: &[u8] = self.bytes.get(self.position..self.position + length).ok_or(BoardError::InvalidEncoding)?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    /// Reads an integer written with seven bits to a byte, least significant first, where the highest bit of a byte
+    /// is set when more bytes follow.
+    fn read_varint(&mut self) -> Result<u64, BoardError> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte: u8 = self.read_bytes(1)?[0];
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(BoardError::InvalidEncoding)
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::movegen::tests::board_from_fen;
+
+    fn round_trip(fen: &str) -> Board {
+        let board: Board = board_from_fen(fen);
+        let decoded: Board = Board::decode_position(&board.encode_position()).unwrap();
+        assert_eq!(decoded.fen().state, board.fen().state);
+        decoded
+    }
+
+    #[test]
+    fn positions_survive_a_round_trip() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "r3k2r/8/8/8/8/8/8/R3K2R b Kq - 5 40",
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            "4k3/8/8/8/8/8/8/4K3 w - - 99 300",
+        ] {
+            round_trip(fen);
+        }
+    }
+
+    #[test]
+    fn positions_are_compact() {
+        let board: Board = Board::new();
+        assert_eq!(board.encode_position().len(), 8 + 16 + 2);
+    }
+
+    #[test]
+    fn the_turn_follows_the_half_moves_without_a_black_king() {
+        let board: Board = round_trip("8/8/8/8/8/8/8/4K2R b - - 3 7");
+        assert_eq!(board.turn_to_play(), Team::Black);
+        assert_eq!(board.fullmove_number(), 7);
+    }
+
+    #[test]
+    fn fullmove_number_zero_is_encoded_as_the_first_move() {
+        let board: Board = board_from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 0");
+        let decoded: Board = Board::decode_position(&board.encode_position()).unwrap();
+
+        assert_eq!(decoded.turn_to_play(), Team::Black);
+        assert_eq!(decoded.fullmove_number(), 1);
+    }
+
+    #[test]
+    fn turns_which_disagree_with_the_black_king_are_rejected() {
+        let mut bytes: Vec<u8> = Board::new().encode_position();
+        *bytes.last_mut().unwrap() = 1;
+        assert!(matches!(Board::decode_position(&bytes), Err(BoardError::InvalidEncoding)));
+    }
+
+    #[test]
+    fn truncated_and_padded_positions_are_rejected() {
+        let bytes: Vec<u8> = Board::new().encode_position();
+
+        assert!(matches!(Board::decode_position(&bytes[..bytes.len() - 1]), Err(BoardError::InvalidEncoding)));
+        assert!(matches!(Board::decode_position(&[bytes.as_slice(), &[0]].concat()), Err(BoardError::InvalidEncoding)));
+    }
+
+    #[test]
+    fn games_survive_a_round_trip() {
+        let mut board: Board = Board::new();
+        for uci in ["e2e4", "c7c5", "g1f3", "d7d6", "f1b5", "c8d7", "e1g1", "d7b5", "e4e5", "b5f1"] {
+            board.make_move(&board.parse_uci(uci).unwrap()).unwrap();
+        }
+
+        let bytes: Vec<u8> = board.encode_game().unwrap();
+        assert_eq!(bytes.len(), Board::new().encode_position().len() + board.history().len());
+
+        let decoded: Board = Board::decode_game(&bytes).unwrap();
+        assert_eq!(decoded.fen().state, board.fen().state);
+        let moves = |board: &Board| board.history().iter().map(|node| (node.from, node.to)).collect::<Vec<_>>();
+        assert_eq!(moves(&decoded), moves(&board));
+    }
+}
@@ -0,0 +1,252 @@
+use super::{Board, HistoryNode};
+use crate::castling::CastlingSide;
+use crate::clock::Outcome;
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+
+/// Something which happened on a board, as reported to the observers of the board.
+#[derive(Debug, Clone)]
+pub enum BoardEvent {
+    /// A move was made with [`Board::make_move`] or [`Board::move_piece`].
+    MoveMade(MoveEvent),
+
+    /// The team is in check after the last move.
+    Check(Team),
+
+    /// The last move ended the game. Games only end on the board, so the outcome is either a win or a draw.
+    GameOver(Outcome),
+
+    /// The move in the history node was taken back with [`Board::undo_move`].
+    Undo(HistoryNode),
+}
+
+/// The details of a move which was made, as they were before the move changed the board.
+#[derive(Debug, Clone)]
+pub struct MoveEvent {
+    /// The piece which moved, or the piece which was dropped.
+    pub piece: Piece,
+    pub from: Coordinate,
+    pub to: Coordinate,
+
+    /// The piece which was captured and the coordinate it was captured on, which is not the destination of the move
+    /// when capturing en passant. Pieces removed by the explosions of Atomic chess are not included.
+    pub captured: Option<(Piece, Coordinate)>,
+    pub en_passant: bool,
+    pub castling: Option<CastlingSide>,
+    pub promotion: Option<PieceClass>,
+    pub drop: Option<PieceClass>,
+
+    /// The move in standard algebraic notation.
+    pub san: String,
+}
+
+/// Reacts to the events of the boards it observes. Closures taking the board and the event are observers, and so are
+/// senders of events, which pass the events on to another thread.
+pub trait BoardObserver: Send + Sync {
+    /// Called after the event happened, with the board as it is after the event.
+    fn notify(&self, board: &Board, event: &BoardEvent);
+}
+
+impl<F: Fn(&Board, &BoardEvent) + Send + Sync> BoardObserver for F {
+    fn notify(&self, board: &Board, event: &BoardEvent) {
+        self(board, event)
+    }
+}
+
+impl BoardObserver for Sender<BoardEvent> {
+    fn notify(&self, _board: &Board, event: &BoardEvent) {
+        // A receiver which was dropped has stopped listening, which is not a reason to stop the game.
+        self.send(event.clone()).ok();
+    }
+}
+
+/// The observers of a board. Copies of a board start without observers, so that boards which are cloned to look ahead
+/// do not report moves which were never played.
+#[derive(Default)]
+pub(crate) struct Observers(Vec<Arc<dyn BoardObserver>>);
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl std::fmt::Debug for Observers {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
+
+impl Board {
+    /// Adds an observer which is notified of every move made and taken back from now on.
+    pub fn add_observer(&mut self, observer: Arc<dyn BoardObserver>) {
+        self.observers.0.push(observer);
+    }
+
+    /// Removes the observer, which is compared by the address it points to.
+    pub fn remove_observer(&mut self, observer: &Arc<dyn BoardObserver>) {
+        self.observers.0.retain(|other| !Arc::ptr_eq(other, observer));
+    }
+
+    pub fn clear_observers(&mut self) {
+        self.observers.0.clear();
+    }
+
+    /// Describes the move before it is made, which is only done when someone is observing the board since writing the
+    /// move in standard algebraic notation looks ahead at the position after the move.
+    pub(crate) fn move_event(&self, chess_move: &Move) -> Option<MoveEvent> {
+        if self.observers.0.is_empty() {
+            return None;
+        }
+
+        let piece: Piece = match chess_move.drop {
+            Some(class) => Piece::new(class, self.turn_to_play),
+            None => self.get_piece(&chess_move.from)?,
+        };
+        let captured: Option<(Piece, Coordinate)> = self
+            .captured_coordinate(chess_move)
+            .and_then(|coordinate| Some((self.get_piece(&coordinate)?, coordinate)));
+        Some(MoveEvent {
+            piece,
+            from: chess_move.from,
+            to: chess_move.to,
+            en_passant: matches!(captured, Some((_, coordinate)) if coordinate != chess_move.to),
+            captured,
+            castling: self.castling_side(chess_move),
+            promotion: chess_move.promotion,
+            drop: chess_move.drop,
+            san: self.san(chess_move),
+        })
+    }
+
+    /// Reports the move which was just made, followed by the check or the end of the game that it led to.
+    pub(crate) fn notify_move(&self, event: MoveEvent) {
+        self.notify(&BoardEvent::MoveMade(event));
+
+        if self.is_in_check(self.turn_to_play) {
+            self.notify(&BoardEvent::Check(self.turn_to_play));
+        }
+        match self.winner() {
+            Some(team) => self.notify(&BoardEvent::GameOver(Outcome::Win(team))),
+            None if self.is_draw() => self.notify(&BoardEvent::GameOver(Outcome::Draw)),
+            None => {}
+        }
+    }
+
+    pub(crate) fn notify(&self, event: &BoardEvent) {
+        for observer in self.observers.0.iter() {
+            observer.notify(self, event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+    use crate::variant::Crazyhouse;
+    use std::sync::mpsc::{channel, Receiver};
+
+    /// Creates a board in the position with a sender of events observing it.
+    fn observed_board(fen: &str) -> (Board, Receiver<BoardEvent>) {
+        let mut board: Board = Board::new_with_fen(Fen { state: fen.to_string() });
+        let (sender, receiver) = channel();
+        board.add_observer(Arc::new(sender));
+        (board, receiver)
+    }
+
+    fn play(board: &mut Board, uci: &str) {
+        board.make_move(&board.parse_uci(uci).unwrap()).unwrap();
+    }
+
+    /// Gets the details of the next event, which must be a move.
+    fn next_move(receiver: &Receiver<BoardEvent>) -> MoveEvent {
+        match receiver.try_recv() {
+            Ok(BoardEvent::MoveMade(event)) => event,
+            event => panic!("expected a move, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn observers_are_added_and_removed() {
+        let mut board: Board = Board::new();
+        let (sender, receiver) = channel();
+        let observer: Arc<dyn BoardObserver> = Arc::new(sender);
+        board.add_observer(observer.clone());
+
+        play(&mut board, "e2e4");
+        assert_eq!(next_move(&receiver).san, "e4");
+
+        board.remove_observer(&observer);
+        play(&mut board, "e7e5");
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn moves_are_described_before_they_change_the_board() {
+        let (mut board, receiver) = observed_board("r3k3/7P/8/3pP3/8/8/8/4K3 w q d6 0 1");
+
+        play(&mut board, "e5d6");
+        let event: MoveEvent = next_move(&receiver);
+        assert!(event.en_passant);
+        let (captured, coordinate) = event.captured.unwrap();
+        assert_eq!((char::from(captured), coordinate.to_string()), ('p', "D5".to_string()));
+        assert_eq!(event.san, "exd6");
+
+        play(&mut board, "e8c8");
+        let event: MoveEvent = next_move(&receiver);
+        assert_eq!(event.castling, Some(CastlingSide::QueenSide));
+        assert_eq!(char::from(event.piece), 'k');
+
+        play(&mut board, "h7h8q");
+        let event: MoveEvent = next_move(&receiver);
+        assert_eq!(event.promotion, Some(PieceClass::Queen));
+        assert_eq!(char::from(event.piece), 'P');
+        assert_eq!(event.san, "h8=Q");
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn drops_are_described_with_the_piece_dropped() {
+        let (mut board, receiver) = observed_board("4k3/8/8/8/8/8/8/4K3[N] w - - 0 1");
+        board.set_variant(Crazyhouse);
+
+        play(&mut board, "N@f3");
+        let event: MoveEvent = next_move(&receiver);
+        assert_eq!(event.drop, Some(PieceClass::Knight));
+        assert_eq!(char::from(event.piece), 'N');
+        assert_eq!(event.to.to_string(), "F3");
+        assert!(event.captured.is_none());
+    }
+
+    #[test]
+    fn checks_mates_and_undos_are_reported() {
+        let (mut board, receiver) = observed_board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+
+        play(&mut board, "a1a8");
+        assert_eq!(next_move(&receiver).san, "Ra8#");
+        assert!(matches!(receiver.try_recv(), Ok(BoardEvent::Check(Team::Black))));
+        assert!(matches!(receiver.try_recv(), Ok(BoardEvent::GameOver(Outcome::Win(Team::White)))));
+
+        board.undo_move();
+        match receiver.try_recv() {
+            Ok(BoardEvent::Undo(node)) => assert_eq!(node.to.to_string(), "A8"),
+            event => panic!("expected an undo, got {:?}", event),
+        }
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn copies_of_a_board_are_not_observed() {
+        let (board, receiver) = observed_board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+
+        let mut copy: Board = board.clone();
+        play(&mut copy, "a1a8");
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(format!("{:?}", board.observers), "Observers(1)");
+        assert_eq!(format!("{:?}", copy.observers), "Observers(0)");
+    }
+}
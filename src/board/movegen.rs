@@ -0,0 +1,319 @@
+use super::attacks::{attackers_in, attacks_with_occupancy, pawn_direction};
+use super::Board;
+use crate::bitboard::Bitboard;
+use crate::castling::{back_row, CastlingSide};
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+
+impl Board {
+    /// Gets all of the legal moves of the team which has the turn to play.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.team_legal_moves(self.turn_to_play)
+    }
+
+    /// Gets all of the legal moves of a team. A move is legal when it follows the movement rules of the piece, does not
+    /// leave the king of the team in check, and is allowed by the variant played on the board.
+    pub(crate) fn team_legal_moves(&self, team: Team) -> Vec<Move> {
+        let mut moves: Vec<Move> = Vec::new();
+
+        for coordinate in self.team_occupancy(team) {
+            self.piece_pseudo_legal_moves(&coordinate, &mut moves);
+        }
+        if self.variant.requires_king_safety() {
+            moves.retain(|chess_move| !self.leaves_king_in_check(chess_move));
+        }
+
+        self.castling_moves(team, &mut moves);
+        if self.variant.allows_drops() {
+            self.drop_moves(team, &mut moves);
+        }
+        self.variant.filter_moves(self, team, &mut moves);
+
+        moves
+    }
+
+    /// Gets the coordinate of the piece which is captured by the move, if the move captures a piece.
+    pub(crate) fn captured_coordinate(&self, chess_move: &Move) -> Option<Coordinate> {
+        let piece: Piece = self.get_piece(&chess_move.from)?;
+
+        match self.get_piece(&chess_move.to) {
+            Some(other_piece) if other_piece.team() != piece.team() => Some(chess_move.to),
+            None if self.is_en_passant(chess_move) => {
+                Some(Coordinate::try_from((chess_move.from.row(), chess_move.to.column())).unwrap())
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks if the move is a pawn capturing en passant.
+    pub(crate) fn is_en_passant(&self, chess_move: &Move) -> bool {
+        matches!(self.get_piece(&chess_move.from), Some(piece) if matches!(piece.class(), PieceClass::Pawn))
+            && Some(chess_move.to) == self.en_passant
+            && chess_move.from.column() != chess_move.to.column()
+    }
+
+    /// Gets the castling side of the move if the move is the king castling. In Chess960 castling is written as the king
+    /// moving onto the rook it castles with, otherwise it is written as the king moving two squares.
+    pub(crate) fn castling_side(&self, chess_move: &Move) -> Option<CastlingSide> {
+        let piece: Piece = self.get_piece(&chess_move.from)?;
+        if !matches!(piece.class(), PieceClass::King)
+            || chess_move.from.row() != back_row(piece.team())
+            || chess_move.to.row() != chess_move.from.row()
+            || chess_move.to.column() == chess_move.from.column()
+        {
+            return None;
+        }
+
+        let side: CastlingSide = if chess_move.to.column() > chess_move.from.column() {
+            CastlingSide::KingSide
+        } else {
+            CastlingSide::QueenSide
+        };
+
+        if self.chess960 {
+            let rook_column: usize = self.castling_rights.rook_column(piece.team(), side)?;
+            (rook_column == chess_move.to.column()).then_some(side)
+        } else {
+            (chess_move.from.column().abs_diff(chess_move.to.column()) == 2 && self.castling_rights.has(piece.team(), side))
+                .then_some(side)
+        }
+    }
+
+    /// Gets the coordinates that the rook moves from and to if the move is the king castling.
+    pub(crate) fn castling_rook_move(&self, chess_move: &Move) -> Option<(Coordinate, Coordinate)> {
+        let side: CastlingSide = self.castling_side(chess_move)?;
+        let team: Team = self.get_piece(&chess_move.from)?.team();
+        let row: usize = chess_move.from.row();
+
+        Some((
+            Coordinate::try_from((row, self.castling_rights.rook_column(team, side)?)).unwrap(),
+            Coordinate::try_from((row, side.rook_destination_column())).unwrap(),
+        ))
+    }
+
+    /// Adds all of the moves that the piece at the coordinate can make according to its movement rules to the moves,
+    /// without checking whether the moves leave the king in check. Castling is not included.
+    fn piece_pseudo_legal_moves(&self, coordinate: &Coordinate, moves: &mut Vec<Move>) {
+        let piece: Piece = match self.get_piece(coordinate) {
+            Some(piece) => piece,
+            None => return,
+        };
+        let occupied: Bitboard = self.occupancy();
+        let own: Bitboard = self.team_occupancy(piece.team());
+
+        if !matches!(piece.class(), PieceClass::Pawn) {
+            for to in attacks_with_occupancy(&piece, coordinate, occupied) & !own {
+                moves.push(Move::new(*coordinate, to));
+            }
+            return;
+        }
+
+        let mut targets: Bitboard = Bitboard::EMPTY;
+
+        // Single and double pawn moves. The double move is only allowed from the rows that the variant allows it from,
+        // which in standard chess is the starting row of the pawns.
+        let direction: i8 = pawn_direction(piece.team());
+        if let Ok(single_coordinate) = coordinate.checked_add_individual(direction, 0) {
+            if !occupied.contains(&single_coordinate) {
+                targets.insert(&single_coordinate);
+
+                if self.variant.allows_double_step(piece.team(), coordinate.row()) {
+                    let double_coordinate: Coordinate = coordinate.checked_add_individual(direction * 2, 0).unwrap();
+                    if !occupied.contains(&double_coordinate) {
+                        targets.insert(&double_coordinate);
+                    }
+                }
+            }
+        }
+
+        // Pawn's attack moves, including capturing en passant when the en passant coordinate belongs to the opponent.
+        let mut capturable: Bitboard = occupied & !own;
+        if let Some(en_passant) = self.en_passant {
+            if piece.team() == self.turn_to_play {
+                capturable.insert(&en_passant);
+            }
+        }
+        targets |= attacks_with_occupancy(&piece, coordinate, occupied) & capturable;
+
+        for to in targets {
+            if to.row() == back_row(piece.team().opponent()) {
+                for class in self.variant.promotion_classes() {
+                    moves.push(Move::new_with_promotion(*coordinate, to, *class));
+                }
+            } else {
+                moves.push(Move::new(*coordinate, to));
+            }
+        }
+    }
+
+    /// Adds the castling moves of the team to the moves. Castling is allowed when the team has the castling right, all
+    /// of the coordinates between the king, the rook, and their destinations are empty, and the king does not start on,
+    /// pass through, or land on a coordinate attacked by the opponent.
+    fn castling_moves(&self, team: Team, moves: &mut Vec<Move>) {
+        let king_coordinate: Coordinate = match self.king_coordinate(team) {
+            Some(king_coordinate) if king_coordinate.row() == back_row(team) => king_coordinate,
+            _ => return,
+        };
+        let row: usize = king_coordinate.row();
+
+        for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+            let rook_coordinate: Coordinate = match self.castling_rights.rook_column(team, side) {
+                Some(rook_column) => Coordinate::try_from((row, rook_column)).unwrap(),
+                None => continue,
+            };
+            if !matches!(
+                self.get_piece(&rook_coordinate),
+                Some(rook) if rook.team() == team && matches!(rook.class(), PieceClass::Rook)
+            ) {
+                continue;
+            }
+
+            let king_destination: usize = side.king_destination_column();
+            let rook_destination: usize = side.rook_destination_column();
+
+            let mut occupied: Bitboard = self.occupancy();
+            occupied.remove(&king_coordinate);
+            occupied.remove(&rook_coordinate);
+
+            let columns = [king_coordinate.column(), rook_coordinate.column(), king_destination, rook_destination];
+            let span = *columns.iter().min().unwrap()..=*columns.iter().max().unwrap();
+            if span
+                .map(|column| Coordinate::try_from((row, column)).unwrap())
+                .any(|coordinate| occupied.contains(&coordinate))
+            {
+                continue;
+            }
+
+            let king_path = king_coordinate.column().min(king_destination)..=king_coordinate.column().max(king_destination);
+            if king_path
+                .map(|column| Coordinate::try_from((row, column)).unwrap())
+                .any(|coordinate| !attackers_in(&self.map, &coordinate, team.opponent(), occupied).is_empty())
+            {
+                continue;
+            }
+
+            if self.chess960 {
+                moves.push(Move::new(king_coordinate, rook_coordinate));
+            } else {
+                moves.push(Move::new(king_coordinate, Coordinate::try_from((row, king_destination)).unwrap()));
+            }
+        }
+    }
+
+    /// Checks if making the move would leave the king of the moving team attacked. Castling is not handled here since
+    /// the castling rules already guarantee the safety of the king.
+    fn leaves_king_in_check(&self, chess_move: &Move) -> bool {
+        let piece: Piece = match self.get_piece(&chess_move.from) {
+            Some(piece) => piece,
+            None => return false,
+        };
+
+        let mut map: [[Option<Piece>; 8]; 8] = self.map;
+        if let Some(captured_coordinate) = self.captured_coordinate(chess_move) {
+            map[captured_coordinate.row()][captured_coordinate.column()] = None;
+        }
+        map[chess_move.from.row()][chess_move.from.column()] = None;
+        map[chess_move.to.row()][chess_move.to.column()] = Some(piece);
+
+        let king_coordinate: Coordinate = if matches!(piece.class(), PieceClass::King) {
+            chess_move.to
+        } else {
+            match self.king_coordinate(piece.team()) {
+                Some(king_coordinate) => king_coordinate,
+                None => return false,
+            }
+        };
+
+        let mut occupied: Bitboard = Bitboard::EMPTY;
+        for (row_index, row) in map.iter().enumerate() {
+            for (column_index, item) in row.iter().enumerate() {
+                if item.is_some() {
+                    occupied.insert(&Coordinate::try_from((row_index, column_index)).unwrap());
+                }
+            }
+        }
+
+        !attackers_in(&map, &king_coordinate, piece.team().opponent(), occupied).is_empty()
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    /// Counts the leaf nodes of the tree of legal moves of the given depth.
+    pub(in crate::board) fn perft(board: &Board, depth: usize) -> usize {
+        if depth == 0 {
+            return 1;
+        }
+        board
+            .legal_moves()
+            .iter()
+            .map(|chess_move| {
+                let mut child: Board = board.clone();
+                child.make_move(chess_move).unwrap();
+                perft(&child, depth - 1)
+            })
+            .sum()
+    }
+
+    pub(in crate::board) fn board_from_fen(fen: &str) -> Board {
+        Board::try_new_with_fen(Fen { state: fen.to_string() }).unwrap()
+    }
+
+    #[test]
+    fn starting_position_matches_the_standard_fen() {
+        assert_eq!(
+            Board::new().fen().state,
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+    }
+
+    #[test]
+    fn perft_of_the_starting_position() {
+        let board: Board = Board::new();
+        assert_eq!(perft(&board, 1), 20);
+        assert_eq!(perft(&board, 2), 400);
+        assert_eq!(perft(&board, 3), 8902);
+    }
+
+    #[test]
+    fn perft_with_castling_en_passant_and_promotions() {
+        let board: Board = board_from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        assert_eq!(perft(&board, 1), 48);
+        assert_eq!(perft(&board, 2), 2039);
+    }
+
+    #[test]
+    fn perft_with_discovered_checks_and_en_passant_pins() {
+        let board: Board = board_from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1");
+        assert_eq!(perft(&board, 1), 14);
+        assert_eq!(perft(&board, 2), 191);
+        assert_eq!(perft(&board, 3), 2812);
+    }
+
+    #[test]
+    fn perft_with_promotions_to_every_class() {
+        let board: Board = board_from_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8");
+        assert_eq!(perft(&board, 1), 44);
+        assert_eq!(perft(&board, 2), 1486);
+    }
+
+    #[test]
+    fn castling_moves_the_rook_and_removes_the_castling_rights() {
+        let mut board: Board = board_from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        board.make_move(&Move::new(Coordinate::try_from("e1").unwrap(), Coordinate::try_from("g1").unwrap())).unwrap();
+
+        assert_eq!(board.fen().state, "r3k2r/8/8/8/8/8/8/R4RK1 b kq - 1 1");
+    }
+
+    #[test]
+    fn en_passant_removes_the_captured_pawn() {
+        let mut board: Board = board_from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        board.make_move(&Move::new(Coordinate::try_from("e5").unwrap(), Coordinate::try_from("d6").unwrap())).unwrap();
+
+        assert_eq!(board.fen().state, "4k3/8/3P4/8/8/8/8/4K3 b - - 0 1");
+    }
+}
@@ -0,0 +1,200 @@
+use super::attacks::{slides_along, KING_OFFSETS};
+use super::Board;
+use crate::coordinate::{Coordinate, CoordinatePath};
+use crate::piece::{Piece, Team};
+
+/// Represents a piece which is absolutely pinned to its king by a sliding piece of the opponent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pin {
+    /// The coordinate of the pinned piece.
+    pub pinned: Coordinate,
+
+    /// The coordinate of the opponent's sliding piece which is pinning the piece.
+    pub pinner: Coordinate,
+
+    /// The path going from the king to the pinner, excluding the king and including the pinner. The pinned piece stays
+    /// legal to move as long as it moves along this path.
+    pub ray: CoordinatePath,
+}
+
+/// Represents a piece which is masking an attack of a sliding piece of its own team on an opponent piece. Moving the
+/// piece off of the ray unmasks the attack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredAttack {
+    /// The coordinate of the piece which would unmask the attack by moving.
+    pub piece: Coordinate,
+
+    /// The coordinate of the sliding piece whose attack gets unmasked.
+    pub attacker: Coordinate,
+
+    /// The coordinate of the opponent piece that would be attacked.
+    pub target: Coordinate,
+
+    /// The path going from the target to the attacker, excluding the target and including the attacker.
+    pub ray: CoordinatePath,
+}
+
+impl Board {
+    /// Gets all of the pieces of the team which are absolutely pinned to their king along with the pieces pinning them.
+    pub fn pins(&self, team: Team) -> Vec<Pin> {
+        let king_coordinate: Coordinate = match self.king_coordinate(team) {
+            Some(king_coordinate) => king_coordinate,
+            None => return Vec::new(),
+        };
+
+        self.x_rays(&king_coordinate)
+            .into_iter()
+            .filter(|x_ray| x_ray.blocker.1.team() == team && x_ray.slider.1.team() != team)
+            .map(|x_ray| Pin {
+                pinned: x_ray.blocker.0,
+                pinner: x_ray.slider.0,
+                ray: x_ray.ray,
+            })
+            .collect()
+    }
+
+    /// Gets all of the pieces of the team which, by moving, would unmask an attack of one of the team's sliding pieces
+    /// on a piece of the opponent.
+    pub fn discovered_attack_candidates(&self, team: Team) -> Vec<DiscoveredAttack> {
+        self.team_occupancy(team.opponent())
+            .into_iter()
+            .flat_map(|target| {
+                self.x_rays(&target)
+                    .into_iter()
+                    .filter(|x_ray| x_ray.blocker.1.team() == team && x_ray.slider.1.team() == team)
+                    .map(move |x_ray| DiscoveredAttack {
+                        piece: x_ray.blocker.0,
+                        attacker: x_ray.slider.0,
+                        target,
+                        ray: x_ray.ray,
+                    })
+            })
+            .collect()
+    }
+
+    /// Gets all of the pieces of the team which, by moving, would give a discovered check to the opponent's king.
+    pub fn discovered_check_candidates(&self, team: Team) -> Vec<DiscoveredAttack> {
+        let king_coordinate: Option<Coordinate> = self.king_coordinate(team.opponent());
+        self.discovered_attack_candidates(team)
+            .into_iter()
+            .filter(|discovered_attack| Some(discovered_attack.target) == king_coordinate)
+            .collect()
+    }
+
+    /// Walks each of the rays going out of the coordinate and finds the rays where the first piece on the ray blocks a
+    /// sliding piece behind it which moves along the ray.
+    fn x_rays(&self, coordinate: &Coordinate) -> Vec<XRay> {
+        let mut x_rays: Vec<XRay> = Vec::new();
+
+        for (row_offset, column_offset) in KING_OFFSETS {
+            let mut ray: CoordinatePath = Vec::new();
+            let mut blocker: Option<(Coordinate, Piece)> = None;
+
+            for distance in 1..8 {
+                let other_coordinate: Coordinate = match coordinate
+                    .checked_add_individual(row_offset * distance, column_offset * distance)
+                {
+                    Ok(other_coordinate) => other_coordinate,
+                    Err(_) => break,
+                };
+                ray.push(other_coordinate);
+
+                let other_piece: Piece = match self.get_piece(&other_coordinate) {
+                    Some(other_piece) => other_piece,
+                    None => continue,
+                };
+                match blocker {
+                    None => blocker = Some((other_coordinate, other_piece)),
+                    Some(blocker) => {
+                        if slides_along(other_piece.class(), row_offset, column_offset) {
+                            x_rays.push(XRay {
+                                blocker,
+                                slider: (other_coordinate, other_piece),
+                                ray,
+                            });
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+
+        x_rays
+    }
+}
+
+/// A ray going out of a coordinate where a sliding piece is hidden behind a single blocking piece.
+struct XRay {
+    blocker: (Coordinate, Piece),
+    slider: (Coordinate, Piece),
+    ray: CoordinatePath,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::attacks::tests::{board_from_fen, coordinate, coordinates};
+
+    fn path(names: &[&str]) -> CoordinatePath {
+        names.iter().map(|name| coordinate(name)).collect()
+    }
+
+    #[test]
+    fn pieces_alone_between_their_king_and_a_slider_are_pinned() {
+        // The knight and bishop on b1 and c1 shield each other, so neither of them is pinned by the rook on a1.
+        let board: Board = board_from_fen("k3r3/8/8/b7/4N2q/8/3B1P2/rNB1K3 w - - 0 1");
+
+        let mut pins: Vec<Pin> = board.pins(Team::White);
+        pins.sort_by_key(|pin| pin.pinned.to_string());
+        assert_eq!(
+            pins,
+            vec![
+                Pin {
+                    pinned: coordinate("d2"),
+                    pinner: coordinate("a5"),
+                    ray: path(&["d2", "c3", "b4", "a5"]),
+                },
+                Pin {
+                    pinned: coordinate("e4"),
+                    pinner: coordinate("e8"),
+                    ray: path(&["e2", "e3", "e4", "e5", "e6", "e7", "e8"]),
+                },
+                Pin {
+                    pinned: coordinate("f2"),
+                    pinner: coordinate("h4"),
+                    ray: path(&["f2", "g3", "h4"]),
+                },
+            ]
+        );
+        assert_eq!(board.pinned(Team::White), coordinates(&["d2", "e4", "f2"]));
+
+        // Pieces in front of a slider of their own team are not pinned.
+        assert!(board.pins(Team::Black).is_empty());
+        assert!(board.pinned(Team::Black).is_empty());
+    }
+
+    #[test]
+    fn pieces_masking_a_slider_of_their_own_team_are_discovered_attack_candidates() {
+        let board: Board = board_from_fen("4k3/8/5q2/8/4N3/2N5/8/B3R1K1 w - - 0 1");
+
+        let mut candidates: Vec<DiscoveredAttack> = board.discovered_attack_candidates(Team::White);
+        candidates.sort_by_key(|candidate| candidate.piece.to_string());
+        let on_the_queen: DiscoveredAttack = DiscoveredAttack {
+            piece: coordinate("c3"),
+            attacker: coordinate("a1"),
+            target: coordinate("f6"),
+            ray: path(&["e5", "d4", "c3", "b2", "a1"]),
+        };
+        let on_the_king: DiscoveredAttack = DiscoveredAttack {
+            piece: coordinate("e4"),
+            attacker: coordinate("e1"),
+            target: coordinate("e8"),
+            ray: path(&["e7", "e6", "e5", "e4", "e3", "e2", "e1"]),
+        };
+        assert_eq!(candidates, vec![on_the_queen, on_the_king.clone()]);
+        assert_eq!(board.discovered_check_candidates(Team::White), vec![on_the_king]);
+
+        assert!(board.discovered_attack_candidates(Team::Black).is_empty());
+        assert!(board.pins(Team::White).is_empty());
+    }
+}
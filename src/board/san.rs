@@ -0,0 +1,189 @@
+use super::{Board, BoardError};
+use crate::castling::CastlingSide;
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+
+impl Board {
+    /// Writes the move in standard algebraic notation (e.g. `Nf3`, `exd5`, `e8=Q+`, `O-O`). The column or the row of the
+    /// moving piece is only written when another piece of the same class can make the same move, and the move ends with
+    /// `+` when it gives check or `#` when it checkmates.
+    pub fn san(&self, chess_move: &Move) -> String {
+        let mut san: String = match (chess_move.drop, self.castling_side(chess_move)) {
+            (Some(class), _) => {
+                format!("{}@{}", char::from(Piece::new(class, Team::White)), coordinate_name(&chess_move.to))
+            }
+            (None, Some(CastlingSide::KingSide)) => "O-O".to_string(),
+            (None, Some(CastlingSide::QueenSide)) => "O-O-O".to_string(),
+            (None, None) => self.san_without_suffix(chess_move),
+        };
+
+        let mut next_board: Board = self.clone();
+        next_board.apply_move(chess_move);
+        if next_board.is_in_check(next_board.turn_to_play()) {
+            san.push(if next_board.legal_moves().is_empty() { '#' } else { '+' });
+        }
+        san
+    }
+
+    /// Writes a move which is neither a drop nor castling in standard algebraic notation, without the check symbol.
+    fn san_without_suffix(&self, chess_move: &Move) -> String {
+        let piece: Piece = match self.get_piece(&chess_move.from) {
+            Some(piece) => piece,
+            None => return String::new(),
+        };
+        let is_capture: bool = self.captured_coordinate(chess_move).is_some();
+        let mut san: String = String::new();
+
+        if matches!(piece.class(), PieceClass::Pawn) {
+            if is_capture {
+                san.push(column_letter(chess_move.from.column()));
+            }
+        } else {
+            san.push(char::from(Piece::new(piece.class(), Team::White)));
+
+            // The pieces of the same class which can move to the same coordinate decide what tells the pieces apart.
+            let rivals: Vec<Coordinate> = self
+                .legal_moves()
+                .into_iter()
+                .filter(|other| {
+                    other.to == chess_move.to
+                        && other.from != chess_move.from
+                        && other.promotion == chess_move.promotion
+                        && self.castling_side(other).is_none()
+                        && matches!(self.get_piece(&other.from), Some(other_piece) if other_piece.class() == piece.class())
+                })
+                .map(|other| other.from)
+                .collect();
+            if !rivals.is_empty() {
+                let shares_column: bool = rivals.iter().any(|rival| rival.column() == chess_move.from.column());
+                let shares_row: bool = rivals.iter().any(|rival| rival.row() == chess_move.from.row());
+                if !shares_column || shares_row {
+                    san.push(column_letter(chess_move.from.column()));
+                }
+                if shares_column {
+                    san.push_str(&(8 - chess_move.from.row()).to_string());
+                }
+            }
+        }
+
+        if is_capture {
+            san.push('x');
+        }
+        san.push_str(&coordinate_name(&chess_move.to));
+        if let Some(promotion) = chess_move.promotion {
+            san.push('=');
+            san.push(char::from(Piece::new(promotion, Team::White)));
+        }
+        san
+    }
+
+    /// Parses a move written in standard algebraic notation (e.g. `Nf3`, `exd5`, `e8=Q+`, `O-O`) into one of the legal
+    /// moves of the team which has the turn to play. Check and annotation symbols at the end of the move are ignored.
+    pub fn parse_san(&self, san: &str) -> Result<Move, BoardError> {
+        let san: &str = san.trim_end_matches(['+', '#', '!', '?']);
+        let legal_moves: Vec<Move> = self.legal_moves();
+
+        // Castling is written by the side that the king castles towards rather than by its destination.
+        let castling_side: Option<CastlingSide> = match san {
+            "O-O" | "0-0" => Some(CastlingSide::KingSide),
+            "O-O-O" | "0-0-0" => Some(CastlingSide::QueenSide),
+            _ => None,
+        };
+        if let Some(side) = castling_side {
+            return legal_moves
+                .into_iter()
+                .find(|chess_move| self.castling_side(chess_move) == Some(side))
+                .ok_or(BoardError::IllegalMove);
+        }
+
+        // Drops are written as the class of the piece and its destination separated by an `@` (e.g. `N@f3`), with the
+        // class being optional for pawns.
+        if let Some((class, destination)) = san.split_once('@') {
+            let class: PieceClass = match class.chars().next() {
+                None | Some('P') => PieceClass::Pawn,
+                Some(character) => piece_class(character).ok_or(BoardError::InvalidSan)?,
+            };
+            let to: Coordinate = Coordinate::try_from(destination).map_err(|_| BoardError::InvalidSan)?;
+            let chess_move: Move = Move::new_drop(class, to);
+            return legal_moves.contains(&chess_move).then_some(chess_move).ok_or(BoardError::IllegalMove);
+        }
+
+        let mut characters: Vec<char> = san.chars().collect();
+
+        // The promotion is written at the end of the move, optionally after an equals sign.
+        let promotion: Option<PieceClass> = match characters.last().and_then(|character| piece_class(*character)) {
+            Some(class) if characters.len() > 2 => {
+                characters.pop();
+                if characters.last() == Some(&'=') {
+                    characters.pop();
+                }
+                Some(class)
+            }
+            _ => None,
+        };
+
+        // Pawn moves are written without a piece letter.
+        let class: PieceClass = match characters.first().and_then(|character| piece_class(*character)) {
+            Some(class) => {
+                characters.remove(0);
+                class
+            }
+            None => PieceClass::Pawn,
+        };
+
+        if characters.len() < 2 {
+            return Err(BoardError::InvalidSan);
+        }
+        let destination: String = characters.split_off(characters.len() - 2).into_iter().collect();
+        let to: Coordinate = Coordinate::try_from(destination).map_err(|_| BoardError::InvalidSan)?;
+
+        // Whatever remains is the optional capture symbol and the column or row of the moving piece used to tell apart
+        // the pieces which are able to make the same move.
+        let mut from_column: Option<usize> = None;
+        let mut from_row: Option<usize> = None;
+        for character in characters.into_iter().filter(|character| *character != 'x') {
+            match character {
+                'a'..='h' => from_column = Some(character as usize - 'a' as usize),
+                '1'..='8' => from_row = Some('8' as usize - character as usize),
+                _ => return Err(BoardError::InvalidSan),
+            }
+        }
+
+        let mut candidates = legal_moves.into_iter().filter(|chess_move| {
+            chess_move.to == to
+                && chess_move.promotion == promotion
+                && self.castling_side(chess_move).is_none()
+                && matches!(self.get_piece(&chess_move.from), Some(piece) if piece.class() == class)
+                && from_column.is_none_or(|column| chess_move.from.column() == column)
+                && from_row.is_none_or(|row| chess_move.from.row() == row)
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(chess_move), None) => Ok(chess_move),
+            (Some(_), Some(_)) => Err(BoardError::AmbiguousMove),
+            (None, _) => Err(BoardError::IllegalMove),
+        }
+    }
+}
+
+/// Gets the name of the coordinate as written in standard algebraic notation.
+fn coordinate_name(coordinate: &Coordinate) -> String {
+    coordinate.to_string().to_lowercase()
+}
+
+fn column_letter(column: usize) -> char {
+    (b'a' + column as u8) as char
+}
+
+/// Gets the class of the piece written with the character in standard algebraic notation.
+fn piece_class(character: char) -> Option<PieceClass> {
+    match character {
+        'K' => Some(PieceClass::King),
+        'Q' => Some(PieceClass::Queen),
+        'R' => Some(PieceClass::Rook),
+        'B' => Some(PieceClass::Bishop),
+        'N' => Some(PieceClass::Knight),
+        _ => None,
+    }
+}
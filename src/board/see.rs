@@ -0,0 +1,150 @@
+use super::Board;
+use crate::bitboard::Bitboard;
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+
+impl Board {
+    /// Performs a static exchange evaluation of the given move. This plays out the sequence of captures on the target
+    /// square of the move, where each side always recaptures with its least valuable attacker and is free to stop
+    /// capturing once continuing would lose material. Attackers which are hidden behind sliding pieces (x-rays) join the
+    /// exchange as soon as the pieces in front of them have captured.
+    ///
+    /// The value returned is the material balance of the exchange in centipawns from the point of view of the team
+    /// making the move. Pins are not taken into account.
+    pub fn see(&self, chess_move: &Move) -> i32 {
+        let piece: Piece = match self.get_piece(&chess_move.from) {
+            Some(piece) => piece,
+            None => return 0,
+        };
+        let target: Coordinate = chess_move.to;
+
+        // The occupancy of the board is tracked as a mask so that pieces which already took part in the exchange no
+        // longer block the sliders behind them.
+        let mut occupied: Bitboard = self.occupancy();
+        occupied.remove(&chess_move.from);
+
+        // The value of the piece captured by the initial move. A pawn moving diagonally to an empty square is an en
+        // passant capture where the captured pawn is beside the moving pawn.
+        let captured_value: i32 = match self.get_piece(&target) {
+            Some(captured) => captured.class().value(),
+            None if matches!(piece.class(), PieceClass::Pawn)
+                && chess_move.from.column() != target.column() =>
+            {
+                let captured_coordinate: Coordinate =
+                    Coordinate::try_from((chess_move.from.row(), target.column())).unwrap();
+                occupied.remove(&captured_coordinate);
+                PieceClass::Pawn.value()
+            }
+            None => 0,
+        };
+
+        // Holds the speculative material balance after each capture in the sequence, from the point of view of the team
+        // which made that capture.
+        let mut gains: Vec<i32> = vec![captured_value];
+        let mut value_on_target: i32 = piece.class().value();
+        if let Some(promotion) = chess_move.promotion {
+            gains[0] += promotion.value() - PieceClass::Pawn.value();
+            value_on_target = promotion.value();
+        }
+
+        let mut team: Team = piece.team().opponent();
+        while let Some((attacker_coordinate, attacker)) = self
+            .attackers_with_occupancy(&target, team, occupied)
+            .into_iter()
+            .map(|attacker_coordinate| (attacker_coordinate, self.get_piece(&attacker_coordinate).unwrap()))
+            .min_by_key(|(_, attacker)| attacker.class().value())
+        {
+            occupied.remove(&attacker_coordinate);
+
+            // The king may only recapture when the square is no longer defended by the other team.
+            if matches!(attacker.class(), PieceClass::King)
+                && !self
+                    .attackers_with_occupancy(&target, team.opponent(), occupied)
+                    .is_empty()
+            {
+                break;
+            }
+
+            gains.push(value_on_target - gains.last().unwrap());
+            value_on_target = attacker.class().value();
+            team = team.opponent();
+        }
+
+        // Going backwards through the exchange, each side chooses between capturing and standing pat.
+        for index in (1..gains.len()).rev() {
+            gains[index - 1] = -i32::max(-gains[index - 1], gains[index]);
+        }
+
+        gains[0]
+    }
+
+    /// Checks if the static exchange evaluation of the given move is greater than or equal to the threshold.
+    pub fn see_ge(&self, chess_move: &Move, threshold: i32) -> bool {
+        self.see(chess_move) >= threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    fn see(fen: &str, from: &str, to: &str) -> i32 {
+        let board: Board = Board::try_new_with_fen(Fen { state: fen.to_string() }).unwrap();
+        board.see(&Move::new(Coordinate::try_from(from).unwrap(), Coordinate::try_from(to).unwrap()))
+    }
+
+    #[test]
+    fn capturing_an_undefended_pawn_wins_the_pawn() {
+        assert_eq!(see("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "e1", "e5"), 100);
+    }
+
+    #[test]
+    fn capturing_a_defended_pawn_with_a_knight_loses_the_knight() {
+        let fen: &str = "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1";
+        assert_eq!(see(fen, "d3", "e5"), PieceClass::Pawn.value() - PieceClass::Knight.value());
+    }
+
+    #[test]
+    fn capturing_a_pawn_defended_by_a_pawn_loses_the_queen() {
+        let fen: &str = "4k3/8/3p4/4p3/8/8/8/4QK2 w - - 0 1";
+        assert_eq!(see(fen, "e1", "e5"), PieceClass::Pawn.value() - PieceClass::Queen.value());
+    }
+
+    #[test]
+    fn x_ray_attackers_join_the_exchange() {
+        assert_eq!(see("4r1k1/8/8/4p3/8/8/4R3/4R1K1 w - - 0 1", "e2", "e5"), 100);
+        assert_eq!(see("4r1k1/8/8/4p3/8/8/4R3/6K1 w - - 0 1", "e2", "e5"), 100 - 500);
+    }
+
+    #[test]
+    fn the_king_does_not_recapture_onto_a_defended_square() {
+        assert_eq!(see("8/8/8/4k3/3p4/8/5N2/B3K3 w - - 0 1", "f2", "d4"), 100);
+    }
+
+    #[test]
+    fn quiet_moves_onto_attacked_squares_lose_the_piece() {
+        assert_eq!(see("4k3/8/8/3p4/8/8/8/1R2K3 w - - 0 1", "b1", "c4"), -500);
+        assert_eq!(see("4k3/8/8/8/8/8/8/1R2K3 w - - 0 1", "b1", "b7"), 0);
+    }
+
+    #[test]
+    fn en_passant_captures_the_pawn_beside_the_moving_pawn() {
+        assert_eq!(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5", "d6"), 100);
+    }
+
+    #[test]
+    fn promotions_count_the_value_of_the_promoted_piece() {
+        let board: Board = Board::try_new_with_fen(Fen { state: "3rk3/2P5/8/8/8/8/8/4K3 w - - 0 1".to_string() })
+            .unwrap();
+        let capture: Move = Move::new_with_promotion(
+            Coordinate::try_from("c7").unwrap(),
+            Coordinate::try_from("d8").unwrap(),
+            PieceClass::Queen,
+        );
+        assert_eq!(board.see(&capture), 500 + 900 - 100 - 900);
+        assert!(board.see_ge(&capture, 400));
+        assert!(!board.see_ge(&capture, 401));
+    }
+}
@@ -0,0 +1,107 @@
+use super::{Board, BoardError};
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+
+impl Board {
+    /// Writes the move in the long algebraic notation of the UCI protocol (e.g. `e2e4`, `e7e8q`, `N@f3`). Castling is
+    /// written as the king moving two squares, or as the king capturing its own rook when the board follows the Chess960
+    /// rules.
+    pub fn uci(&self, chess_move: &Move) -> String {
+        if let Some(class) = chess_move.drop {
+            let letter: char = char::from(Piece::new(class, Team::White));
+            return format!("{}@{}", letter, chess_move.to.to_string().to_lowercase());
+        }
+
+        let mut uci: String = format!("{}{}", chess_move.from, chess_move.to).to_lowercase();
+        if let Some(promotion) = chess_move.promotion {
+            uci.push(match promotion {
+                PieceClass::Queen => 'q',
+                PieceClass::Rook => 'r',
+                PieceClass::Bishop => 'b',
+                PieceClass::Knight => 'n',
+                PieceClass::King => 'k',
+                PieceClass::Pawn => 'p',
+            });
+        }
+        uci
+    }
+
+    /// Parses a move written in the long algebraic notation of the UCI protocol into one of the legal moves of the team
+    /// which has the turn to play. Castling is accepted both as the king moving two squares and as the king capturing
+    /// its own rook, no matter which rules the board follows.
+    pub fn parse_uci(&self, uci: &str) -> Result<Move, BoardError> {
+        // Drops are written as the letter of the piece and its destination separated by an `@` (e.g. `N@f3`).
+        if let Some((letter, destination)) = uci.split_once('@') {
+            let class: PieceClass = letter
+                .chars()
+                .next()
+                .and_then(|letter| Piece::try_from(letter.to_ascii_uppercase()).ok())
+                .map(|piece| piece.class())
+                .ok_or(BoardError::InvalidUci)?;
+            let to: Coordinate = Coordinate::try_from(destination).map_err(|_| BoardError::InvalidUci)?;
+            let chess_move: Move = Move::new_drop(class, to);
+            return self.legal_moves().contains(&chess_move).then_some(chess_move).ok_or(BoardError::IllegalMove);
+        }
+
+        let from: Coordinate = uci.get(0..2).and_then(|from| Coordinate::try_from(from).ok()).ok_or(BoardError::InvalidUci)?;
+        let to: Coordinate = uci.get(2..4).and_then(|to| Coordinate::try_from(to).ok()).ok_or(BoardError::InvalidUci)?;
+        let promotion: Option<PieceClass> = match uci.get(4..) {
+            Some("") => None,
+            Some("q") => Some(PieceClass::Queen),
+            Some("r") => Some(PieceClass::Rook),
+            Some("b") => Some(PieceClass::Bishop),
+            Some("n") => Some(PieceClass::Knight),
+            Some("k") => Some(PieceClass::King),
+            _ => return Err(BoardError::InvalidUci),
+        };
+        let chess_move: Move = Move { from, to, promotion, drop: None };
+
+        let legal_moves: Vec<Move> = self.legal_moves();
+        if legal_moves.contains(&chess_move) {
+            return Ok(chess_move);
+        }
+
+        // The move may be castling written the way of the other rules, so it is matched by where the king and the rook
+        // end up.
+        legal_moves
+            .into_iter()
+            .find(|legal_move| {
+                legal_move.from == from
+                    && promotion.is_none()
+                    && self.castling_side(legal_move).is_some_and(|side| {
+                        let (rook_from, _) = self.castling_rook_move(legal_move).unwrap();
+                        to == rook_from || to.column() == side.king_destination_column()
+                    })
+            })
+            .ok_or(BoardError::IllegalMove)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_are_parsed_and_written() {
+        let board: Board = Board::new();
+        let chess_move: Move = board.parse_uci("e2e4").unwrap();
+
+        assert_eq!(board.uci(&chess_move), "e2e4");
+        assert!(matches!(board.parse_uci("e2e5"), Err(BoardError::IllegalMove)));
+        assert!(matches!(board.parse_uci("e2e4x"), Err(BoardError::InvalidUci)));
+    }
+
+    #[test]
+    fn multibyte_input_is_rejected() {
+        let mut board: Board = Board::new();
+        for input in ["é", "e2é", "ée2e4", "e2e4é", "e2€4", "éé"] {
+            assert!(matches!(board.parse_uci(input), Err(BoardError::InvalidUci)), "{}", input);
+            assert!(board.parse_san(input).is_err(), "{}", input);
+        }
+
+        board.make_move(&board.parse_uci("e2e4").unwrap()).unwrap();
+        assert!(board.parse_uci("ée7e5").is_err());
+        assert!(board.parse_san("Né").is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::coordinate::Coordinate;
+use crate::piece::Team;
+
+/// Represents the two sides of the board that a king can castle towards.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum CastlingSide {
+    KingSide,
+    QueenSide,
+}
+
+impl CastlingSide {
+    /// The column that the king ends up on after castling towards this side.
+    pub fn king_destination_column(&self) -> usize {
+        match self {
+            CastlingSide::KingSide => 6,
+            CastlingSide::QueenSide => 2,
+        }
+    }
+
+    /// The column that the rook ends up on after castling towards this side.
+    pub fn rook_destination_column(&self) -> usize {
+        match self {
+            CastlingSide::KingSide => 5,
+            CastlingSide::QueenSide => 3,
+        }
+    }
+}
+
+/// The castling rights of both teams. Each right is stored as the column of the rook which the king castles with, so a
+/// right is lost by setting its column to `None`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub struct CastlingRights {
+    white_king_side: Option<usize>,
+    white_queen_side: Option<usize>,
+    black_king_side: Option<usize>,
+    black_queen_side: Option<usize>,
+}
+
+impl CastlingRights {
+    /// Creates the castling rights of a standard game of chess where both teams can castle to both sides.
+    pub fn standard() -> Self {
+        Self {
+            white_king_side: Some(7),
+            white_queen_side: Some(0),
+            black_king_side: Some(7),
+            black_queen_side: Some(0),
+        }
+    }
+
+    /// Gets the column of the rook that the team castles with towards the side, if the team still has this right.
+    pub fn rook_column(&self, team: Team, side: CastlingSide) -> Option<usize> {
+        match (team, side) {
+            (Team::White, CastlingSide::KingSide) => self.white_king_side,
+            (Team::White, CastlingSide::QueenSide) => self.white_queen_side,
+            (Team::Black, CastlingSide::KingSide) => self.black_king_side,
+            (Team::Black, CastlingSide::QueenSide) => self.black_queen_side,
+        }
+    }
+
+    pub fn has(&self, team: Team, side: CastlingSide) -> bool {
+        self.rook_column(team, side).is_some()
+    }
+
+    pub fn set(&mut self, team: Team, side: CastlingSide, rook_column: Option<usize>) {
+        match (team, side) {
+            (Team::White, CastlingSide::KingSide) => self.white_king_side = rook_column,
+            (Team::White, CastlingSide::QueenSide) => self.white_queen_side = rook_column,
+            (Team::Black, CastlingSide::KingSide) => self.black_king_side = rook_column,
+            (Team::Black, CastlingSide::QueenSide) => self.black_queen_side = rook_column,
+        }
+    }
+
+    /// Removes both of the castling rights of the team.
+    pub fn remove_team(&mut self, team: Team) {
+        self.set(team, CastlingSide::KingSide, None);
+        self.set(team, CastlingSide::QueenSide, None);
+    }
+
+    /// Removes the castling right which relies on a rook standing on the coordinate, if there is one.
+    pub fn remove_rook(&mut self, coordinate: &Coordinate) {
+        for team in [Team::White, Team::Black] {
+            for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+                if coordinate.row() == back_row(team) && self.rook_column(team, side) == Some(coordinate.column()) {
+                    self.set(team, side, None);
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::fmt::Display for CastlingRights {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "-");
+        }
+
+        for (right, character) in [
+            (self.white_king_side, 'K'),
+            (self.white_queen_side, 'Q'),
+            (self.black_king_side, 'k'),
+            (self.black_queen_side, 'q'),
+        ] {
+            if right.is_some() {
+                write!(f, "{}", character)?;
+            }
+        }
+        std::fmt::Result::Ok(())
+    }
+}
+
+impl TryFrom<&str> for CastlingRights {
+    type Error = &'static str;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        let mut castling_rights: Self = Self::default();
+        if string == "-" {
+            return Ok(castling_rights);
+        }
+
+        for character in string.chars() {
+            match character {
+                'K' => castling_rights.white_king_side = Some(7),
+                'Q' => castling_rights.white_queen_side = Some(0),
+                'k' => castling_rights.black_king_side = Some(7),
+                'q' => castling_rights.black_queen_side = Some(0),
+                _ => return Err("Not a valid castling character"),
+            }
+        }
+        Ok(castling_rights)
+    }
+}
+
+/// The row that the pieces of the team start the game on.
+pub fn back_row(team: Team) -> usize {
+    match team {
+        Team::Black => 0,
+        Team::White => 7,
+    }
+}
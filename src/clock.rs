@@ -0,0 +1,509 @@
+use crate::board::Board;
+use crate::piece::Team;
+use crate::variant::has_mating_material;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A source of the current time for a [`Clock`], measured from any fixed point in the past.
+pub trait TimeSource: Debug + Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+/// Measures time with the monotonic clock of the system.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemTimeSource {
+    start: Instant,
+}
+
+impl SystemTimeSource {
+    pub fn new() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+impl Default for SystemTimeSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A time source which only moves forward when it is told to, so that clocks can be driven deterministically. Clones
+/// share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct ManualTimeSource {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl ManualTimeSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the time forward by the duration.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+
+    pub fn set(&self, now: Duration) {
+        *self.now.lock().unwrap() = now;
+    }
+}
+
+impl TimeSource for ManualTimeSource {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// How the time that a player spends on a move is adjusted.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub enum Delay {
+    /// The time spent is taken from the clock as it is.
+    #[default]
+    None,
+
+    /// The increment is added to the clock after every move.
+    Fischer(Duration),
+
+    /// The time spent is given back after every move, up to the delay.
+    Bronstein(Duration),
+
+    /// The clock only starts counting down once the delay has passed on every move, as with US delay.
+    Simple(Duration),
+}
+
+/// A stage of a time control, which gives a player time for a number of moves.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct TimeControlStage {
+    /// The number of moves that the time is given for, or `None` if it is for the rest of the game.
+    pub moves: Option<u32>,
+
+    /// The time added to the clock of a player when the stage starts.
+    pub time: Duration,
+
+    pub delay: Delay,
+}
+
+/// The time that each player has for the game, which is divided into stages. Once a player has made the moves of a
+/// stage, the time of the next stage is added to their clock.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct TimeControl {
+    stages: Vec<TimeControlStage>,
+}
+
+impl TimeControl {
+    /// Creates a time control from its stages, which must end with a stage for the rest of the game.
+    pub fn new(stages: Vec<TimeControlStage>) -> Result<Self, ClockError> {
+        let is_valid = |(last, rest): (&TimeControlStage, &[TimeControlStage])| {
+            last.moves.is_none() && rest.iter().all(|stage| stage.moves.is_some_and(|moves| moves > 0))
+        };
+        match stages.split_last().is_some_and(is_valid) {
+            true => Ok(Self { stages }),
+            false => Err(ClockError::InvalidTimeControl),
+        }
+    }
+
+    /// A single stage for the whole game with the delay.
+    pub fn single(time: Duration, delay: Delay) -> Self {
+        Self { stages: vec![TimeControlStage { moves: None, time, delay }] }
+    }
+
+    /// The whole game must be played in the time, with no increment or delay.
+    pub fn sudden_death(time: Duration) -> Self {
+        Self::single(time, Delay::None)
+    }
+
+    pub fn fischer(time: Duration, increment: Duration) -> Self {
+        Self::single(time, Delay::Fischer(increment))
+    }
+
+    pub fn bronstein(time: Duration, delay: Duration) -> Self {
+        Self::single(time, Delay::Bronstein(delay))
+    }
+
+    pub fn simple_delay(time: Duration, delay: Duration) -> Self {
+        Self::single(time, Delay::Simple(delay))
+    }
+
+    pub fn stages(&self) -> &[TimeControlStage] {
+        &self.stages
+    }
+}
+
+/// The time of a single player.
+#[derive(Debug, Clone, Copy)]
+struct PlayerClock {
+    remaining: Duration,
+    stage: usize,
+    moves_in_stage: u32,
+}
+
+/// A chess clock which counts down the time of the player whose turn it is, according to a [`TimeControl`].
+#[derive(Debug, Clone)]
+pub struct Clock {
+    time_control: TimeControl,
+    time_source: Arc<dyn TimeSource>,
+    players: HashMap<Team, PlayerClock>,
+
+    /// The team whose time is being counted, which is `None` before the clock has been started and after a flag has
+    /// fallen.
+    running: Option<Team>,
+
+    /// The time that the running team has spent on its move before the clock was last paused.
+    elapsed_before_pause: Duration,
+
+    /// The time at which the clock last started counting, or `None` while it is paused.
+    counting_since: Option<Duration>,
+
+    flagged: Option<Team>,
+}
+
+impl Clock {
+    /// Creates a clock which measures time with the time source.
+    pub fn new(time_control: TimeControl, time_source: Arc<dyn TimeSource>) -> Self {
+        let first_stage_time: Duration = time_control.stages[0].time;
+        let player = PlayerClock { remaining: first_stage_time, stage: 0, moves_in_stage: 0 };
+        Self {
+            time_control,
+            time_source,
+            players: HashMap::from([(Team::White, player), (Team::Black, player)]),
+            running: None,
+            elapsed_before_pause: Duration::ZERO,
+            counting_since: None,
+            flagged: None,
+        }
+    }
+
+    /// Creates a clock which measures time with the monotonic clock of the system.
+    pub fn with_system_time(time_control: TimeControl) -> Self {
+        Self::new(time_control, Arc::new(SystemTimeSource::new()))
+    }
+
+    pub fn time_control(&self) -> &TimeControl {
+        &self.time_control
+    }
+
+    /// Starts counting the time of the team.
+    pub fn start(&mut self, team: Team) -> Result<(), ClockError> {
+        if let Some(team) = self.flag() {
+            return Err(ClockError::FlagFallen(team));
+        }
+        self.running = Some(team);
+        self.elapsed_before_pause = Duration::ZERO;
+        self.counting_since = Some(self.time_source.now());
+        Ok(())
+    }
+
+    /// Ends the move of the running team and starts counting the time of the opponent. Fails if the flag of the
+    /// running team fell before the move was made.
+    pub fn press(&mut self) -> Result<(), ClockError> {
+        if let Some(team) = self.flag() {
+            return Err(ClockError::FlagFallen(team));
+        }
+        let team: Team = self.running.ok_or(ClockError::NotRunning)?;
+        if self.counting_since.is_none() {
+            return Err(ClockError::Paused);
+        }
+
+        let elapsed: Duration = self.elapsed();
+        let stages: &[TimeControlStage] = &self.time_control.stages;
+        let player: &mut PlayerClock = self.players.get_mut(&team).unwrap();
+        let stage: TimeControlStage = stages[player.stage];
+
+        player.remaining = player.remaining.saturating_sub(charged_time(elapsed, stage.delay));
+        match stage.delay {
+            Delay::Fischer(increment) => player.remaining += increment,
+            Delay::Bronstein(delay) => player.remaining += elapsed.min(delay),
+            Delay::None | Delay::Simple(_) => {}
+        }
+
+        player.moves_in_stage += 1;
+        if stage.moves == Some(player.moves_in_stage) {
+            player.stage += 1;
+            player.moves_in_stage = 0;
+            player.remaining += stages[player.stage].time;
+        }
+
+        self.start(team.opponent())
+    }
+
+    /// Stops counting time until the clock is resumed.
+    pub fn pause(&mut self) {
+        if self.flag().is_none() && self.counting_since.is_some() {
+            self.elapsed_before_pause = self.elapsed();
+            self.counting_since = None;
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.running.is_some() && self.counting_since.is_none() {
+            self.counting_since = Some(self.time_source.now());
+        }
+    }
+
+    /// Gets the team whose time is being counted, even while the clock is paused.
+    pub fn running_team(&self) -> Option<Team> {
+        self.running
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.running.is_some() && self.counting_since.is_none()
+    }
+
+    /// Gets the time left on the clock of the team, including the time spent on the current move. The time of a simple
+    /// delay that has not passed yet is not included.
+    pub fn remaining(&self, team: Team) -> Duration {
+        let player: &PlayerClock = &self.players[&team];
+        if self.running != Some(team) {
+            return player.remaining;
+        }
+        let delay: Delay = self.time_control.stages[player.stage].delay;
+        player.remaining.saturating_sub(charged_time(self.elapsed(), delay))
+    }
+
+    /// Gets the number of the stage of the time control that the team is in, starting from 0.
+    pub fn stage(&self, team: Team) -> usize {
+        self.players[&team].stage
+    }
+
+    /// Checks if the time of the running team has run out, and stops the clock if it has. Returns the team whose flag
+    /// fell.
+    pub fn flag(&mut self) -> Option<Team> {
+        if self.flagged.is_none() {
+            if let Some(team) = self.running.filter(|team| self.remaining(*team).is_zero()) {
+                self.players.get_mut(&team).unwrap().remaining = Duration::ZERO;
+                self.flagged = Some(team);
+                self.running = None;
+                self.counting_since = None;
+            }
+        }
+        self.flagged
+    }
+
+    /// Gets the team whose flag has fallen, without checking the time of the running team.
+    pub fn flagged(&self) -> Option<Team> {
+        self.flagged
+    }
+
+    /// Gets the outcome of the game played on the board with the clock. A result on the board takes precedence over the
+    /// clock. A team whose flag falls loses unless the opponent has no material to checkmate with, which draws.
+    pub fn outcome(&mut self, board: &Board) -> Option<Outcome> {
+        if let Some(winner) = board.winner() {
+            return Some(Outcome::Win(winner));
+        }
+        if board.is_draw() {
+            return Some(Outcome::Draw);
+        }
+        self.flag().map(|team| match has_mating_material(board, team.opponent()) {
+            true => Outcome::WinOnTime(team.opponent()),
+            false => Outcome::DrawOnTime,
+        })
+    }
+
+    /// The time that the running team has spent on its move.
+    fn elapsed(&self) -> Duration {
+        let counting: Duration = self
+            .counting_since
+            .map_or(Duration::ZERO, |since| self.time_source.now().saturating_sub(since));
+        self.elapsed_before_pause + counting
+    }
+}
+
+/// Gets the time taken from the clock for the time spent on a move, before any increment is added.
+fn charged_time(elapsed: Duration, delay: Delay) -> Duration {
+    match delay {
+        Delay::Simple(delay) => elapsed.saturating_sub(delay),
+        _ => elapsed,
+    }
+}
+
+/// The way that a game played with a clock ended.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Outcome {
+    /// The team won on the board.
+    Win(Team),
+
+    /// The team won because the time of the opponent ran out.
+    WinOnTime(Team),
+
+    /// The game was drawn on the board.
+    Draw,
+
+    /// The time of a team ran out while its opponent had no material to checkmate with.
+    DrawOnTime,
+}
+
+/// Represents an error encountered when using a [`Clock`].
+#[derive(Debug)]
+pub enum ClockError {
+    InvalidTimeControl,
+    NotRunning,
+    Paused,
+    FlagFallen(Team),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    fn seconds(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    fn manual_clock(time_control: TimeControl) -> (Clock, ManualTimeSource) {
+        let time_source: ManualTimeSource = ManualTimeSource::new();
+        (Clock::new(time_control, Arc::new(time_source.clone())), time_source)
+    }
+
+    /// Lets the running team spend the time on its move and then presses the clock.
+    fn play(clock: &mut Clock, time_source: &ManualTimeSource, spent: Duration) {
+        time_source.advance(spent);
+        clock.press().unwrap();
+    }
+
+    #[test]
+    fn sudden_death_takes_the_time_spent() {
+        let (mut clock, time_source) = manual_clock(TimeControl::sudden_death(seconds(60)));
+        clock.start(Team::White).unwrap();
+
+        time_source.advance(seconds(10));
+        assert_eq!(clock.remaining(Team::White), seconds(50));
+        assert_eq!(clock.remaining(Team::Black), seconds(60));
+
+        clock.press().unwrap();
+        time_source.advance(seconds(4));
+        assert_eq!(clock.running_team(), Some(Team::Black));
+        assert_eq!(clock.remaining(Team::White), seconds(50));
+        assert_eq!(clock.remaining(Team::Black), seconds(56));
+    }
+
+    #[test]
+    fn fischer_increments_are_added_after_every_move() {
+        let (mut clock, time_source) = manual_clock(TimeControl::fischer(seconds(60), seconds(2)));
+        clock.start(Team::White).unwrap();
+
+        play(&mut clock, &time_source, seconds(10));
+        assert_eq!(clock.remaining(Team::White), seconds(52));
+        play(&mut clock, &time_source, seconds(1));
+        assert_eq!(clock.remaining(Team::Black), seconds(61));
+    }
+
+    #[test]
+    fn bronstein_delays_give_back_the_time_spent_up_to_the_delay() {
+        let (mut clock, time_source) = manual_clock(TimeControl::bronstein(seconds(60), seconds(5)));
+        clock.start(Team::White).unwrap();
+
+        play(&mut clock, &time_source, seconds(3));
+        assert_eq!(clock.remaining(Team::White), seconds(60));
+        play(&mut clock, &time_source, seconds(8));
+        assert_eq!(clock.remaining(Team::Black), seconds(57));
+    }
+
+    #[test]
+    fn simple_delays_count_down_once_the_delay_has_passed() {
+        let (mut clock, time_source) = manual_clock(TimeControl::simple_delay(seconds(60), seconds(5)));
+        clock.start(Team::White).unwrap();
+
+        time_source.advance(seconds(3));
+        assert_eq!(clock.remaining(Team::White), seconds(60));
+        time_source.advance(seconds(5));
+        assert_eq!(clock.remaining(Team::White), seconds(57));
+
+        clock.press().unwrap();
+        assert_eq!(clock.remaining(Team::White), seconds(57));
+    }
+
+    #[test]
+    fn stages_add_their_time_once_their_moves_are_made() {
+        // 90 minutes for 40 moves followed by 30 minutes for the rest of the game, with 30 seconds added every move.
+        let time_control: TimeControl = TimeControl::new(vec![
+            TimeControlStage { moves: Some(40), time: seconds(90 * 60), delay: Delay::Fischer(seconds(30)) },
+            TimeControlStage { moves: None, time: seconds(30 * 60), delay: Delay::Fischer(seconds(30)) },
+        ])
+        .unwrap();
+        let (mut clock, time_source) = manual_clock(time_control);
+        clock.start(Team::White).unwrap();
+
+        for _ in 0..39 {
+            play(&mut clock, &time_source, seconds(60));
+            play(&mut clock, &time_source, seconds(1));
+        }
+        assert_eq!(clock.stage(Team::White), 0);
+        assert_eq!(clock.remaining(Team::White), seconds(90 * 60 - 39 * 30));
+
+        play(&mut clock, &time_source, seconds(60));
+        assert_eq!(clock.stage(Team::White), 1);
+        assert_eq!(clock.remaining(Team::White), seconds(90 * 60 - 40 * 30 + 30 * 60));
+        assert_eq!(clock.stage(Team::Black), 0);
+    }
+
+    #[test]
+    fn time_controls_must_end_with_a_stage_for_the_rest_of_the_game() {
+        let stage = |moves: Option<u32>| TimeControlStage { moves, time: seconds(60), delay: Delay::None };
+
+        assert!(TimeControl::new(vec![]).is_err());
+        assert!(TimeControl::new(vec![stage(Some(40))]).is_err());
+        assert!(TimeControl::new(vec![stage(Some(0)), stage(None)]).is_err());
+        assert!(TimeControl::new(vec![stage(Some(40)), stage(None)]).is_ok());
+    }
+
+    #[test]
+    fn paused_clocks_do_not_count_time() {
+        let (mut clock, time_source) = manual_clock(TimeControl::sudden_death(seconds(60)));
+        clock.start(Team::White).unwrap();
+        time_source.advance(seconds(5));
+
+        clock.pause();
+        time_source.advance(seconds(100));
+        assert!(clock.is_paused());
+        assert_eq!(clock.remaining(Team::White), seconds(55));
+        assert!(matches!(clock.press(), Err(ClockError::Paused)));
+
+        clock.resume();
+        time_source.advance(seconds(5));
+        assert!(!clock.is_paused());
+        assert_eq!(clock.remaining(Team::White), seconds(50));
+    }
+
+    #[test]
+    fn flags_fall_once_the_time_runs_out() {
+        let (mut clock, time_source) = manual_clock(TimeControl::sudden_death(seconds(10)));
+        clock.start(Team::White).unwrap();
+
+        time_source.advance(seconds(9));
+        assert_eq!(clock.flag(), None);
+        time_source.advance(seconds(2));
+        assert_eq!(clock.flag(), Some(Team::White));
+        assert_eq!(clock.flagged(), Some(Team::White));
+        assert_eq!(clock.running_team(), None);
+        assert_eq!(clock.remaining(Team::White), Duration::ZERO);
+
+        assert!(matches!(clock.press(), Err(ClockError::FlagFallen(Team::White))));
+        assert!(matches!(clock.start(Team::Black), Err(ClockError::FlagFallen(Team::White))));
+    }
+
+    #[test]
+    fn timeouts_against_a_lone_king_are_drawn() {
+        let board: Board = Board::new_with_fen(Fen { state: "4k3/8/8/8/8/8/8/4K2R w - - 0 1".to_string() });
+
+        let (mut clock, time_source) = manual_clock(TimeControl::sudden_death(seconds(10)));
+        clock.start(Team::White).unwrap();
+        assert_eq!(clock.outcome(&board), None);
+        time_source.advance(seconds(10));
+        assert_eq!(clock.outcome(&board), Some(Outcome::DrawOnTime));
+
+        let (mut clock, time_source) = manual_clock(TimeControl::sudden_death(seconds(10)));
+        clock.start(Team::Black).unwrap();
+        time_source.advance(seconds(10));
+        assert_eq!(clock.outcome(&board), Some(Outcome::WinOnTime(Team::White)));
+    }
+}
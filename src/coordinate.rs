@@ -19,6 +19,11 @@ impl Coordinate {
         self.column as usize
     }
 
+    /// The index of the coordinate when the board is laid out row by row, starting from the top left corner.
+    pub fn index(&self) -> usize {
+        self.row() * 8 + self.column()
+    }
+
     pub fn checked_add(&self, other: Self) -> Result<Self, CoordinateError> {
         let row: i8 = self.row as i8 + other.row as i8;
         let column: i8 = self.column as i8 + other.column as i8;
@@ -80,7 +85,7 @@ impl Coordinate {
     }
 
     fn valid_within_board_bound(&self, number: i8) -> bool {
-        0 <= number && number < 8
+        (0..8).contains(&number)
     }
 }
 
@@ -88,26 +93,27 @@ impl TryFrom<&str> for Coordinate {
     type Error = CoordinateError;
 
     fn try_from(string: &str) -> Result<Self, Self::Error> {
-        // The coordinate must have a length of two
-        if string.len() != 2 {
-            return Err(CoordinateError::InvalidCoordinateLength);
-        }
+        // The coordinate must be made of exactly two ASCII characters
+        let [column_specifier, row_specifier]: [u8; 2] = match string.as_bytes() {
+            &[column, row] if column.is_ascii() && row.is_ascii() => [column, row],
+            _ => return Err(CoordinateError::InvalidCoordinateLength),
+        };
 
         // Getting the values of the two characters
-        let column_specifier: char = string.chars().nth(0).unwrap().to_ascii_lowercase();
-        let row_specifier: char = string.chars().nth(1).unwrap().to_ascii_lowercase();
+        let column_specifier: char = char::from(column_specifier).to_ascii_lowercase();
+        let row_specifier: char = char::from(row_specifier).to_ascii_lowercase();
 
         // Checking that the two characters are within the range of allowed characters
-        if !(column_specifier >= 'a' && column_specifier <= 'h') {
+        if !('a'..='h').contains(&column_specifier) {
             return Err(CoordinateError::InvalidColumnSpecifier);
         }
-        if !(row_specifier >= '1' && row_specifier <= '8') {
+        if !('1'..='8').contains(&row_specifier) {
             return Err(CoordinateError::InvalidRowSpecifier);
         }
 
         Ok(Self {
-            row: '8' as u8 - (row_specifier as u8),
-            column: column_specifier as u8 - 'a' as u8,
+            row: b'8' - (row_specifier as u8),
+            column: column_specifier as u8 - b'a',
         })
     }
 }
@@ -160,12 +166,12 @@ macro_rules! indices_try_from {
     };
 }
 
-impl ToString for Coordinate {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let row_specifier: u8 = 8 - self.row;
-        let column_specifier: char = ('A' as u8 + self.column) as char;
+        let column_specifier: char = (b'A' + self.column) as char;
 
-        format!("{}{}", column_specifier, row_specifier)
+        write!(f, "{}{}", column_specifier, row_specifier)
     }
 }
 
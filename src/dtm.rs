@@ -0,0 +1,418 @@
+use crate::board::Board;
+use crate::moves::Move;
+use crate::piece::Team;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+
+mod generator;
+mod material;
+
+use material::{normalized_name, Material, Squares, MAX_PIECES};
+
+/// The bytes at the start of a table file.
+const MAGIC: [u8; 4] = *b"CDTM";
+const VERSION: u8 = 1;
+
+/// The extension of table files.
+const EXTENSION: &str = "dtm";
+
+/// The distance to mate of a position in plies with perfect play, from the point of view of the team to play.
+/// `Loss(0)` is a position where the team to play is checkmated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dtm {
+    Win(u16),
+    Draw,
+    Loss(u16),
+}
+
+impl Dtm {
+    /// Gets the value of the position before a move which leads to a position of this value.
+    pub fn previous(&self) -> Dtm {
+        match self {
+            Dtm::Win(plies) => Dtm::Loss(plies + 1),
+            Dtm::Draw => Dtm::Draw,
+            Dtm::Loss(plies) => Dtm::Win(plies + 1),
+        }
+    }
+
+    /// Stores the value in a single byte, where draws are zero, wins are their odd number of plies, and losses are their
+    /// even number of plies plus two. Returns `None` for distances which do not fit.
+    fn to_byte(self) -> Option<u8> {
+        match self {
+            Dtm::Win(plies) => u8::try_from(plies).ok(),
+            Dtm::Draw => Some(0),
+            Dtm::Loss(plies) => u8::try_from(plies + 2).ok(),
+        }
+    }
+
+    fn from_byte(byte: u8) -> Self {
+        match byte {
+            0 => Dtm::Draw,
+            byte if byte % 2 == 1 => Dtm::Win(byte as u16),
+            byte => Dtm::Loss(byte as u16 - 2),
+        }
+    }
+}
+
+impl Ord for Dtm {
+    /// Orders the values from the point of view of the team to play, so that faster wins and slower losses are greater.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let rank = |dtm: &Dtm| -> i32 {
+            match dtm {
+                Dtm::Win(plies) => i32::from(u16::MAX) * 2 - *plies as i32,
+                Dtm::Draw => 0,
+                Dtm::Loss(plies) => *plies as i32 - i32::from(u16::MAX) * 2,
+            }
+        };
+        rank(self).cmp(&rank(other))
+    }
+}
+
+impl PartialOrd for Dtm {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The distance to mate of every position of an endgame, such as `KRvK`, with the stronger team as white.
+#[derive(Debug, Clone)]
+pub struct DtmTable {
+    material: Material,
+    values: Vec<u8>,
+}
+
+impl DtmTable {
+    /// The name of the endgame of the table.
+    pub fn material(&self) -> String {
+        self.material.name()
+    }
+
+    /// Serializes the table. The values are run length encoded as pairs of a count and a value, since positions next to
+    /// each other in the index often have the same value.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let name: String = self.material.name();
+        let mut bytes: Vec<u8> = Vec::new();
+        bytes.extend_from_slice(&MAGIC);
+        bytes.push(VERSION);
+        bytes.push(name.len() as u8);
+        bytes.extend_from_slice(name.as_bytes());
+        bytes.extend_from_slice(&(self.values.len() as u32).to_le_bytes());
+
+        let mut values = self.values.iter().peekable();
+        while let Some(value) = values.next() {
+            let mut count: u8 = 1;
+            while count < u8::MAX && values.peek() == Some(&value) {
+                values.next();
+                count += 1;
+            }
+            bytes.push(count);
+            bytes.push(*value);
+        }
+
+        bytes
+    }
+
+    /// Deserializes a table written by [`DtmTable::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DtmError> {
+        if bytes.len() < 6 || bytes[0..4] != MAGIC || bytes[4] != VERSION {
+            return Err(DtmError::InvalidFormat);
+        }
+        let name_end: usize = 6 + bytes[5] as usize;
+        let name: &str = bytes
+            .get(6..name_end)
+            .and_then(|name| std::str::from_utf8(name).ok())
+            .ok_or(DtmError::InvalidFormat)?;
+        let material: Material = Material::parse(name)?;
+
+        let length: usize = bytes
+            .get(name_end..name_end + 4)
+            .map(|length| u32::from_le_bytes(length.try_into().unwrap()) as usize)
+            .ok_or(DtmError::InvalidFormat)?;
+        if length != material.size() || !(bytes.len() - name_end - 4).is_multiple_of(2) {
+            return Err(DtmError::InvalidFormat);
+        }
+
+        let mut values: Vec<u8> = Vec::with_capacity(length);
+        for pair in bytes[name_end + 4..].chunks_exact(2) {
+            values.extend(std::iter::repeat_n(pair[1], pair[0] as usize));
+        }
+        if values.len() != length {
+            return Err(DtmError::InvalidFormat);
+        }
+
+        Ok(Self { material, values })
+    }
+
+    /// Loads a table from a file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DtmError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Writes the table to a file.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), DtmError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Gets the value of the position with the pieces on the squares.
+    fn value(&self, squares: &Squares, white_to_play: bool) -> Dtm {
+        Dtm::from_byte(self.values[self.material.index(squares, white_to_play)])
+    }
+}
+
+/// A collection of distance to mate tables, which are generated from the rules of [`Board`] or loaded from files.
+#[derive(Debug, Clone, Default)]
+pub struct DtmTablebase {
+    tables: HashMap<String, DtmTable>,
+}
+
+impl DtmTablebase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads all of the `.dtm` tables in the directory.
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self, DtmError> {
+        let mut tablebase: Self = Self::new();
+        for entry in std::fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == EXTENSION) {
+                tablebase.insert(DtmTable::open(path)?);
+            }
+        }
+        Ok(tablebase)
+    }
+
+    /// Writes every table to the directory, in a file named after its material.
+    pub fn write<P: AsRef<Path>>(&self, directory: P) -> Result<(), DtmError> {
+        for (name, table) in self.tables.iter() {
+            table.write(directory.as_ref().join(format!("{}.{}", name, EXTENSION)))?;
+        }
+        Ok(())
+    }
+
+    pub fn insert(&mut self, table: DtmTable) {
+        self.tables.insert(table.material(), table);
+    }
+
+    /// Gets the table of the material, which is written with the stronger team first.
+    pub fn table(&self, material: &str) -> Option<&DtmTable> {
+        self.tables.get(material)
+    }
+
+    /// Generates the table of the material, such as `KQvK` or `KBNvK`, along with the tables of the endgames that it
+    /// can turn into. Tables which are already in the tablebase are not generated again.
+    pub fn generate(&mut self, material: &str) -> Result<(), DtmError> {
+        let material: Material = Material::parse(material)?;
+        let name: String = normalized_name(material.pieces());
+        if self.tables.contains_key(&name) {
+            return Ok(());
+        }
+
+        // The table is generated with the stronger team as white, which is how it gets probed.
+        let material: Material = Material::parse(&name)?;
+        for successor in material.successors() {
+            if successor != "KvK" {
+                self.generate(&successor)?;
+            }
+        }
+
+        let values: Vec<u8> = generator::generate(&material, self)?;
+        self.insert(DtmTable { material, values });
+        Ok(())
+    }
+
+    /// Gets the distance to mate of the position. En passant captures are not part of the tables, so positions where
+    /// the team to play could capture en passant are scored as if it could not. Positions where the team which just
+    /// moved is in check can not be reached and are not scored.
+    pub fn probe(&self, board: &Board) -> Result<Dtm, DtmError> {
+        if !board.castling_rights().is_empty() {
+            return Err(DtmError::CastlingRights);
+        }
+        if board.is_in_check(board.turn_to_play().opponent()) {
+            return Err(DtmError::IllegalPosition);
+        }
+
+        let pieces = board.map().into_iter().flatten().flatten().collect::<Vec<_>>();
+        if pieces.len() > MAX_PIECES {
+            return Err(DtmError::TooManyPieces);
+        }
+        if pieces.len() == 2 {
+            return Ok(Dtm::Draw);
+        }
+
+        let name: String = normalized_name(&pieces);
+        let table: &DtmTable = self.tables.get(&name).ok_or(DtmError::MissingTable(name))?;
+
+        // Tables are stored with the stronger team as white, so the colors are swapped when black is the stronger team.
+        let white_to_play: bool = matches!(board.turn_to_play(), Team::White);
+        let squares: Option<Squares> = table.material.squares(board, false);
+        let (squares, white_to_play) = match squares {
+            Some(squares) => (squares, white_to_play),
+            None => (
+                table.material.squares(board, true).ok_or(DtmError::InvalidFormat)?,
+                !white_to_play,
+            ),
+        };
+
+        Ok(table.value(&squares, white_to_play))
+    }
+
+    /// Gets the move which keeps the best value for the team to play, which is the fastest mate when winning and the
+    /// slowest one when losing, along with the value that it keeps. Returns `None` when there are no legal moves.
+    pub fn best_move(&self, board: &Board) -> Result<Option<(Move, Dtm)>, DtmError> {
+        let mut best: Option<(Move, Dtm)> = None;
+        for chess_move in board.legal_moves() {
+            let mut next_board: Board = board.clone();
+            next_board.apply_move(&chess_move);
+            let value: Dtm = self.probe(&next_board)?.previous();
+
+            if best.is_none_or(|(_, best_value)| value > best_value) {
+                best = Some((chess_move, value));
+            }
+        }
+        Ok(best)
+    }
+}
+
+/// Represents an error encountered when generating, reading, or probing distance to mate tables.
+#[derive(Debug)]
+pub enum DtmError {
+    Io(std::io::Error),
+    InvalidFormat,
+    InvalidMaterial(String),
+    MissingTable(String),
+    CastlingRights,
+    TooManyPieces,
+    IllegalPosition,
+}
+
+impl From<std::io::Error> for DtmError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    fn board(fen: &str) -> Board {
+        Board::try_new_with_fen(Fen { state: fen.to_string() }).unwrap()
+    }
+
+    #[test]
+    fn values_survive_being_stored_in_a_byte() {
+        for dtm in [Dtm::Win(1), Dtm::Win(33), Dtm::Draw, Dtm::Loss(0), Dtm::Loss(32)] {
+            assert_eq!(Dtm::from_byte(dtm.to_byte().unwrap()), dtm);
+        }
+        assert_eq!(Dtm::Win(1000).to_byte(), None);
+    }
+
+    #[test]
+    fn faster_wins_and_slower_losses_are_better() {
+        assert!(Dtm::Win(1) > Dtm::Win(3));
+        assert!(Dtm::Win(99) > Dtm::Draw);
+        assert!(Dtm::Draw > Dtm::Loss(100));
+        assert!(Dtm::Loss(4) > Dtm::Loss(2));
+        assert_eq!(Dtm::Loss(0).previous(), Dtm::Win(1));
+    }
+
+    #[test]
+    fn bare_kings_are_drawn() {
+        assert_eq!(DtmTablebase::new().probe(&board("8/8/3k4/8/8/4K3/8/8 w - - 0 1")).unwrap(), Dtm::Draw);
+    }
+
+    #[test]
+    fn positions_where_the_team_which_moved_is_in_check_are_illegal() {
+        let tablebase: DtmTablebase = DtmTablebase::new();
+
+        assert!(matches!(
+            tablebase.probe(&board("4k3/8/8/8/8/8/8/4QK2 w - - 0 1")),
+            Err(DtmError::IllegalPosition)
+        ));
+        assert!(matches!(
+            tablebase.probe(&board("8/8/8/3kK3/8/8/8/8 b - - 0 1")),
+            Err(DtmError::IllegalPosition)
+        ));
+        assert!(matches!(
+            tablebase.probe(&board("4k3/8/8/8/8/8/8/4QK2 b - - 0 1")),
+            Err(DtmError::MissingTable(name)) if name == "KQvK"
+        ));
+    }
+
+    /// The longest of the distances to mate which are wins for the team to play.
+    fn longest_win(table: &DtmTable) -> u16 {
+        table
+            .values
+            .iter()
+            .filter_map(|byte| match Dtm::from_byte(*byte) {
+                Dtm::Win(plies) => Some(plies),
+                _ => None,
+            })
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn king_and_queen_mate_a_lone_king_in_at_most_nineteen_plies() {
+        let mut tablebase: DtmTablebase = DtmTablebase::new();
+        tablebase.generate("KvKQ").unwrap();
+        let table: &DtmTable = tablebase.table("KQvK").unwrap();
+        assert_eq!(longest_win(table), 19);
+
+        let mate_in_one: Board = board("6k1/8/6K1/8/8/8/8/Q7 w - - 0 1");
+        assert_eq!(tablebase.probe(&mate_in_one).unwrap(), Dtm::Win(1));
+        let (chess_move, value) = tablebase.best_move(&mate_in_one).unwrap().unwrap();
+        assert_eq!(value, Dtm::Win(1));
+        let mut next_board: Board = mate_in_one.clone();
+        next_board.make_move(&chess_move).unwrap();
+        assert!(next_board.is_checkmate());
+        assert_eq!(tablebase.probe(&next_board).unwrap(), Dtm::Loss(0));
+
+        // The colors are swapped to probe the table when black has the queen.
+        assert_eq!(tablebase.probe(&board("q7/8/8/8/8/6k1/8/6K1 b - - 0 1")).unwrap(), Dtm::Win(1));
+        assert_eq!(tablebase.probe(&board("3qk3/8/8/8/8/8/8/4K3 w - - 0 1")).unwrap(), Dtm::Loss(16));
+        assert_eq!(tablebase.probe(&board("4k3/8/8/8/8/8/8/3QK3 b - - 0 1")).unwrap(), Dtm::Loss(16));
+
+        // The black king can capture the queen, which is not defended by the white king.
+        assert_eq!(tablebase.probe(&board("3Qk3/8/8/8/8/8/8/7K b - - 0 1")).unwrap(), Dtm::Draw);
+    }
+
+    #[test]
+    fn tables_survive_being_stored_in_bytes() {
+        let mut tablebase: DtmTablebase = DtmTablebase::new();
+        tablebase.generate("KQvK").unwrap();
+        let table: &DtmTable = tablebase.table("KQvK").unwrap();
+
+        let bytes: Vec<u8> = table.to_bytes();
+        let read: DtmTable = DtmTable::from_bytes(&bytes).unwrap();
+        assert_eq!(read.material(), "KQvK");
+        assert_eq!(read.values, table.values);
+
+        assert!(matches!(DtmTable::from_bytes(&bytes[..bytes.len() - 1]), Err(DtmError::InvalidFormat)));
+        assert!(matches!(DtmTable::from_bytes(&bytes[1..]), Err(DtmError::InvalidFormat)));
+    }
+
+    #[test]
+    fn king_and_pawn_against_king_is_won_or_drawn_by_the_squares() {
+        let mut tablebase: DtmTablebase = DtmTablebase::new();
+        tablebase.generate("KPvK").unwrap();
+        for material in ["KPvK", "KQvK", "KRvK", "KBvK", "KNvK"] {
+            assert!(tablebase.table(material).is_some(), "{}", material);
+        }
+        assert!(tablebase.table("KBvK").unwrap().values.iter().all(|byte| Dtm::from_byte(*byte) == Dtm::Draw));
+
+        // The black king holds the draw in front of a rook pawn, but can not catch a pawn which is about to promote.
+        assert_eq!(tablebase.probe(&board("k7/8/K7/P7/8/8/8/8 w - - 0 1")).unwrap(), Dtm::Draw);
+        assert_eq!(tablebase.probe(&board("k7/8/K7/P7/8/8/8/8 b - - 0 1")).unwrap(), Dtm::Draw);
+        assert!(matches!(tablebase.probe(&board("8/1P6/8/8/8/7k/8/K7 w - - 0 1")).unwrap(), Dtm::Win(_)));
+        assert!(matches!(tablebase.probe(&board("8/7K/8/8/8/8/6p1/k7 b - - 0 1")).unwrap(), Dtm::Win(_)));
+
+        // A king on the sixth row in front of its pawn wins no matter who is to play.
+        assert!(matches!(tablebase.probe(&board("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1")).unwrap(), Dtm::Win(_)));
+        assert!(matches!(tablebase.probe(&board("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1")).unwrap(), Dtm::Loss(_)));
+    }
+}
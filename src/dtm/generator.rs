@@ -0,0 +1,195 @@
+use super::material::{coordinate, Material, Squares};
+use super::{Dtm, DtmError, DtmTablebase};
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{PieceClass, Team};
+
+/// Computes the distance to mate of every position of the material through retrograde analysis. The tables of the
+/// materials that captures and promotions lead to must already be in the tablebase.
+///
+/// Every position is first looked at once with the move generation of [`Board`]: mates are found, the moves which
+/// capture or promote are scored through the smaller tables, and the number of positions that the other moves lead to
+/// is counted. The results are then spread backwards one ply at a time, starting from the mates. A position is won as
+/// soon as one of the positions it leads to is lost, and lost once all of the positions it leads to are won, with the
+/// ply at which that happens being its distance to mate. The positions left at the end are draws.
+pub(super) fn generate(material: &Material, tablebase: &DtmTablebase) -> Result<Vec<u8>, DtmError> {
+    let size: usize = material.size();
+    let mut valid: Vec<bool> = vec![false; size];
+    let mut resolved: Vec<bool> = vec![false; size];
+    let mut remaining: Vec<u8> = vec![0; size];
+    let mut exits: Vec<Option<Dtm>> = vec![None; size];
+    let mut values: Vec<u8> = vec![0; size];
+
+    // The positions waiting to be resolved at every ply.
+    let mut pending: Vec<Vec<u32>> = Vec::new();
+
+    for index in 0..size {
+        let (squares, white_to_play) = material.decode(index);
+        let board: Board = match valid_board(material, &squares, white_to_play, index) {
+            Some(board) => board,
+            None => continue,
+        };
+        valid[index] = true;
+
+        let legal_moves: Vec<Move> = board.legal_moves();
+        if legal_moves.is_empty() {
+            if board.is_in_check(board.turn_to_play()) {
+                push(&mut pending, 0, index);
+            }
+            continue;
+        }
+
+        let mut successors: Vec<usize> = Vec::new();
+        for chess_move in legal_moves.iter() {
+            if board.captured_coordinate(chess_move).is_some() || chess_move.promotion.is_some() {
+                let mut next_board: Board = board.clone();
+                next_board.apply_move(chess_move);
+                let exit: Dtm = tablebase.probe(&next_board)?.previous();
+                exits[index] = Some(exits[index].map_or(exit, |best| best.max(exit)));
+            } else {
+                let mut next_squares: Squares = squares;
+                let slot: usize = (0..material.pieces().len())
+                    .find(|slot| squares[*slot] == chess_move.from.index())
+                    .unwrap();
+                next_squares[slot] = chess_move.to.index();
+                successors.push(material.index(&next_squares, !white_to_play));
+            }
+        }
+        successors.sort_unstable();
+        successors.dedup();
+        remaining[index] = successors.len() as u8;
+
+        match exits[index] {
+            Some(Dtm::Win(plies)) => push(&mut pending, plies as usize, index),
+            Some(Dtm::Loss(plies)) if successors.is_empty() => push(&mut pending, plies as usize, index),
+            _ => {}
+        }
+    }
+
+    let mut ply: usize = 0;
+    while ply < pending.len() {
+        for index in std::mem::take(&mut pending[ply]) {
+            let index: usize = index as usize;
+            if resolved[index] {
+                continue;
+            }
+
+            // Wins always take an odd number of plies and losses an even number.
+            let value: Dtm = if ply % 2 == 1 {
+                Dtm::Win(ply as u16)
+            } else {
+                Dtm::Loss(ply as u16)
+            };
+            resolved[index] = true;
+            // Endgames with this few pieces are mated in far fewer plies than a byte holds.
+            values[index] = value.to_byte().unwrap();
+
+            for predecessor in predecessors(material, index) {
+                if !valid[predecessor] || resolved[predecessor] {
+                    continue;
+                }
+
+                match value {
+                    Dtm::Loss(_) => push(&mut pending, ply + 1, predecessor),
+                    _ => {
+                        remaining[predecessor] -= 1;
+                        if remaining[predecessor] == 0 {
+                            match exits[predecessor] {
+                                None => push(&mut pending, ply + 1, predecessor),
+                                Some(Dtm::Loss(plies)) => push(&mut pending, (ply + 1).max(plies as usize), predecessor),
+                                Some(_) => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        ply += 1;
+    }
+
+    Ok(values)
+}
+
+/// Creates the board of the position at the index if the position is one that is stored there. Positions are left out
+/// when pieces share a square, when a pawn is on the first or last row, when the position is stored at another index,
+/// or when the team which just moved is in check.
+fn valid_board(material: &Material, squares: &Squares, white_to_play: bool, index: usize) -> Option<Board> {
+    let mut occupied: Bitboard = Bitboard::EMPTY;
+    for (slot, piece) in material.pieces().iter().enumerate() {
+        let coordinate: Coordinate = coordinate(squares[slot]);
+        if occupied.contains(&coordinate) {
+            return None;
+        }
+        if matches!(piece.class(), PieceClass::Pawn) && (coordinate.row() == 0 || coordinate.row() == 7) {
+            return None;
+        }
+        occupied.insert(&coordinate);
+    }
+    if material.index(squares, white_to_play) != index {
+        return None;
+    }
+
+    let board: Board = material.board(squares, white_to_play);
+    (!board.is_in_check(board.turn_to_play().opponent())).then_some(board)
+}
+
+/// Gets the indices of the positions which lead to the position at the index through a move which neither captures nor
+/// promotes. Pieces other than pawns move backwards the same way as they move forwards, while pawns step back one row,
+/// or two rows when they are on the row that they land on after their first move.
+fn predecessors(material: &Material, index: usize) -> Vec<usize> {
+    let (squares, white_to_play) = material.decode(index);
+    let board: Board = material.board(&squares, white_to_play);
+    let mover: Team = board.turn_to_play().opponent();
+    let occupied: Bitboard = board.occupancy();
+
+    let mut predecessors: Vec<usize> = Vec::new();
+    for (slot, piece) in material.pieces().iter().enumerate() {
+        if piece.team() != mover {
+            continue;
+        }
+        let coordinate: Coordinate = coordinate(squares[slot]);
+
+        let origins: Vec<Coordinate> = if matches!(piece.class(), PieceClass::Pawn) {
+            // White pawns move towards the row 0, so they come from the rows below them.
+            let back: i8 = if matches!(mover, Team::White) { 1 } else { -1 };
+            let starting_row: usize = if matches!(mover, Team::White) { 6 } else { 1 };
+            let double_row: usize = if matches!(mover, Team::White) { 4 } else { 3 };
+
+            let mut origins: Vec<Coordinate> = Vec::new();
+            if let Ok(single) = coordinate.checked_add_individual(back, 0) {
+                if !occupied.contains(&single) && single.row() != 0 && single.row() != 7 {
+                    origins.push(single);
+                    if coordinate.row() == double_row {
+                        let double: Coordinate = coordinate.checked_add_individual(2 * back, 0).unwrap();
+                        if double.row() == starting_row && !occupied.contains(&double) {
+                            origins.push(double);
+                        }
+                    }
+                }
+            }
+            origins
+        } else {
+            (board.piece_attacks(&coordinate) & !occupied).into_iter().collect()
+        };
+
+        for origin in origins {
+            let mut previous_squares: Squares = squares;
+            previous_squares[slot] = origin.index();
+            predecessors.push(material.index(&previous_squares, !white_to_play));
+        }
+    }
+
+    predecessors.sort_unstable();
+    predecessors.dedup();
+    predecessors
+}
+
+/// Adds the position at the index to the positions waiting to be resolved at the ply.
+fn push(pending: &mut Vec<Vec<u32>>, ply: usize, index: usize) {
+    if pending.len() <= ply {
+        pending.resize(ply + 1, Vec::new());
+    }
+    pending[ply].push(index as u32);
+}
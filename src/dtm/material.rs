@@ -0,0 +1,386 @@
+use super::DtmError;
+use crate::board::Board;
+use crate::coordinate::Coordinate;
+use crate::piece::{Piece, PieceClass, Team};
+
+/// The largest number of pieces, kings included, that tables can be generated for.
+pub(super) const MAX_PIECES: usize = 4;
+
+/// The squares of the pieces of a position, indexed by the slots of a material. Squares are numbered by
+/// [`Coordinate::index`].
+pub(super) type Squares = [usize; MAX_PIECES];
+
+/// The pieces of an endgame, such as `KRvK`, along with the indexing of its positions. Every piece has a slot, with the
+/// white king first, and each position is stored once no matter how it is mirrored or in which order identical pieces
+/// are placed in their slots.
+#[derive(Debug, Clone)]
+pub(super) struct Material {
+    /// The pieces in slot order, which is the white pieces and then the black pieces, each starting with the king.
+    pieces: Vec<Piece>,
+
+    /// The ranges of slots holding identical pieces.
+    groups: Vec<std::ops::Range<usize>>,
+
+    /// The symmetries that positions can be mirrored with. Positions with pawns can only be mirrored from one side of
+    /// the board to the other, while positions without pawns can also be flipped vertically and along the diagonal.
+    symmetries: usize,
+
+    /// The position of every square in the region that the white king is mirrored to, if the square is in it.
+    region: [Option<usize>; 64],
+    region_squares: Vec<usize>,
+}
+
+impl Material {
+    /// Parses a material written with the pieces of white and black separated by a `v`, such as `KQvKR`.
+    pub(super) fn parse(name: &str) -> Result<Self, DtmError> {
+        let invalid = || DtmError::InvalidMaterial(name.to_string());
+
+        let (white, black) = name.split_once('v').ok_or_else(invalid)?;
+        let mut pieces: Vec<Piece> = Vec::new();
+        for (side, team) in [(white, Team::White), (black, Team::Black)] {
+            if !side.starts_with('K') || side[1..].contains('K') {
+                return Err(invalid());
+            }
+            let mut classes: Vec<PieceClass> = side
+                .chars()
+                .map(|letter| piece_class(letter).ok_or_else(invalid))
+                .collect::<Result<_, _>>()?;
+            classes.sort_by_key(|class| *class as u8);
+            pieces.extend(classes.into_iter().map(|class| Piece::new(class, team)));
+        }
+        if pieces.len() > MAX_PIECES {
+            return Err(DtmError::TooManyPieces);
+        }
+
+        let mut groups: Vec<std::ops::Range<usize>> = Vec::new();
+        for (slot, piece) in pieces.iter().enumerate() {
+            match groups.last_mut() {
+                Some(group) if same_piece(&pieces[group.start], piece) => group.end += 1,
+                _ => groups.push(slot..slot + 1),
+            }
+        }
+
+        let has_pawns: bool = pieces.iter().any(|piece| matches!(piece.class(), PieceClass::Pawn));
+        let mut region: [Option<usize>; 64] = [None; 64];
+        let mut region_squares: Vec<usize> = Vec::new();
+        for (square, item) in region.iter_mut().enumerate() {
+            let (rank, file) = (7 - square / 8, square % 8);
+            let in_region: bool = if has_pawns {
+                file <= 3
+            } else {
+                file <= 3 && rank <= file
+            };
+            if in_region {
+                *item = Some(region_squares.len());
+                region_squares.push(square);
+            }
+        }
+
+        Ok(Self {
+            pieces,
+            groups,
+            symmetries: if has_pawns { 2 } else { 8 },
+            region,
+            region_squares,
+        })
+    }
+
+    /// Writes the material the way it is parsed.
+    pub(super) fn name(&self) -> String {
+        let mut name: String = String::new();
+        for team in [Team::White, Team::Black] {
+            if team == Team::Black {
+                name.push('v');
+            }
+            for piece in self.pieces.iter().filter(|piece| piece.team() == team) {
+                name.push(piece_letter(piece.class()));
+            }
+        }
+        name
+    }
+
+    pub(super) fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    /// The number of positions in the index space, including the positions which are not valid or are stored elsewhere.
+    pub(super) fn size(&self) -> usize {
+        2 * self.region_squares.len() * 64usize.pow(self.pieces.len() as u32 - 1)
+    }
+
+    /// Gets the index of the position with the pieces on the squares. Of all of the ways that the position can be
+    /// mirrored and that its identical pieces can be ordered, the one with the lowest index is used.
+    pub(super) fn index(&self, squares: &Squares, white_to_play: bool) -> usize {
+        let count: usize = self.pieces.len();
+        let mut best: usize = usize::MAX;
+
+        for symmetry in 0..self.symmetries {
+            let mut mapped: Squares = [0; MAX_PIECES];
+            for slot in 0..count {
+                mapped[slot] = transform(squares[slot], symmetry);
+            }
+            for group in self.groups.iter() {
+                mapped[group.clone()].sort_unstable();
+            }
+
+            let region: usize = match self.region[mapped[0]] {
+                Some(region) => region,
+                None => continue,
+            };
+            let mut index: usize = (white_to_play as usize) * self.region_squares.len() + region;
+            for square in mapped[1..count].iter() {
+                index = index * 64 + square;
+            }
+            best = best.min(index);
+        }
+
+        best
+    }
+
+    /// Gets the squares of the pieces and whether white is to play from the index of a position.
+    pub(super) fn decode(&self, mut index: usize) -> (Squares, bool) {
+        let count: usize = self.pieces.len();
+        let mut squares: Squares = [0; MAX_PIECES];
+        for slot in (1..count).rev() {
+            squares[slot] = index % 64;
+            index /= 64;
+        }
+
+        squares[0] = self.region_squares[index % self.region_squares.len()];
+        (squares, index / self.region_squares.len() == 1)
+    }
+
+    /// Creates the board of the position with the pieces on the squares.
+    pub(super) fn board(&self, squares: &Squares, white_to_play: bool) -> Board {
+        let pieces: Vec<(Coordinate, Piece)> = self
+            .pieces
+            .iter()
+            .enumerate()
+            .map(|(slot, piece)| (coordinate(squares[slot]), *piece))
+            .collect();
+        let turn_to_play: Team = if white_to_play { Team::White } else { Team::Black };
+
+        Board::with_pieces(&pieces, turn_to_play)
+    }
+
+    /// Gets the squares of the pieces of the board in slot order, with the colors swapped when `flipped` is set. Returns
+    /// `None` when the board does not have exactly the pieces of the material.
+    pub(super) fn squares(&self, board: &Board, flipped: bool) -> Option<Squares> {
+        let mut squares: Squares = [0; MAX_PIECES];
+        let mut filled: [bool; MAX_PIECES] = [false; MAX_PIECES];
+        let mut count: usize = 0;
+
+        for (row_index, row) in board.map().iter().enumerate() {
+            for (column_index, item) in row.iter().enumerate() {
+                let piece: Piece = match item {
+                    Some(piece) => *piece,
+                    None => continue,
+                };
+                let (team, square) = if flipped {
+                    (piece.team().opponent(), 8 * (7 - row_index) + column_index)
+                } else {
+                    (piece.team(), 8 * row_index + column_index)
+                };
+
+                let slot: usize = (0..self.pieces.len()).find(|slot| {
+                    !filled[*slot] && self.pieces[*slot].class() == piece.class() && self.pieces[*slot].team() == team
+                })?;
+                squares[slot] = square;
+                filled[slot] = true;
+                count += 1;
+            }
+        }
+
+        (count == self.pieces.len()).then_some(squares)
+    }
+
+    /// Gets the materials that the endgame can turn into through a capture or a promotion.
+    pub(super) fn successors(&self) -> Vec<String> {
+        let mut successors: Vec<String> = Vec::new();
+
+        for (slot, piece) in self.pieces.iter().enumerate() {
+            if matches!(piece.class(), PieceClass::King) {
+                continue;
+            }
+
+            let mut captured: Vec<Piece> = self.pieces.clone();
+            captured.remove(slot);
+            successors.push(normalized_name(&captured));
+
+            if matches!(piece.class(), PieceClass::Pawn) {
+                for class in [PieceClass::Queen, PieceClass::Rook, PieceClass::Bishop, PieceClass::Knight] {
+                    let mut promoted: Vec<Piece> = self.pieces.clone();
+                    promoted[slot] = Piece::new(class, piece.team());
+                    successors.push(normalized_name(&promoted));
+                }
+            }
+        }
+
+        successors.sort();
+        successors.dedup();
+        successors
+    }
+}
+
+/// Writes the pieces of a material with the stronger team as white, which is the name that its table is stored under.
+pub(super) fn normalized_name(pieces: &[Piece]) -> String {
+    let side = |team: Team| -> (i32, String) {
+        let mut classes: Vec<PieceClass> = pieces
+            .iter()
+            .filter(|piece| piece.team() == team)
+            .map(|piece| piece.class())
+            .collect();
+        classes.sort_by_key(|class| *class as u8);
+        (
+            classes.iter().map(|class| class.value()).sum(),
+            classes.into_iter().map(piece_letter).collect(),
+        )
+    };
+
+    let white = side(Team::White);
+    let black = side(Team::Black);
+    if black > white {
+        format!("{}v{}", black.1, white.1)
+    } else {
+        format!("{}v{}", white.1, black.1)
+    }
+}
+
+/// Mirrors the square with one of the eight symmetries of the board. The first two symmetries only mirror the board
+/// from one side to the other, so that they keep the direction in which pawns move.
+fn transform(square: usize, symmetry: usize) -> usize {
+    let mut square: usize = square;
+    if symmetry & 1 != 0 {
+        square ^= 7;
+    }
+    if symmetry & 2 != 0 {
+        square ^= 56;
+    }
+    if symmetry & 4 != 0 {
+        square = ((square >> 3) | (square << 3)) & 63;
+    }
+    square
+}
+
+pub(super) fn coordinate(square: usize) -> Coordinate {
+    Coordinate::try_from((square / 8, square % 8)).unwrap()
+}
+
+fn same_piece(a: &Piece, b: &Piece) -> bool {
+    a.class() == b.class() && a.team() == b.team()
+}
+
+fn piece_class(letter: char) -> Option<PieceClass> {
+    match letter {
+        'K' => Some(PieceClass::King),
+        'Q' => Some(PieceClass::Queen),
+        'R' => Some(PieceClass::Rook),
+        'B' => Some(PieceClass::Bishop),
+        'N' => Some(PieceClass::Knight),
+        'P' => Some(PieceClass::Pawn),
+        _ => None,
+    }
+}
+
+fn piece_letter(class: PieceClass) -> char {
+    match class {
+        PieceClass::King => 'K',
+        PieceClass::Queen => 'Q',
+        PieceClass::Rook => 'R',
+        PieceClass::Bishop => 'B',
+        PieceClass::Knight => 'N',
+        PieceClass::Pawn => 'P',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    fn square(name: &str) -> usize {
+        Coordinate::try_from(name).unwrap().index()
+    }
+
+    /// Gets the squares of the pieces, in slot order.
+    fn squares(names: &[&str]) -> Squares {
+        let mut squares: Squares = [0; MAX_PIECES];
+        for (slot, name) in names.iter().enumerate() {
+            squares[slot] = square(name);
+        }
+        squares
+    }
+
+    #[test]
+    fn materials_are_parsed_and_written() {
+        let material: Material = Material::parse("KQvKR").unwrap();
+        assert_eq!(material.name(), "KQvKR");
+        assert!(same_piece(&material.pieces()[0], &Piece::new(PieceClass::King, Team::White)));
+        assert!(same_piece(&material.pieces()[2], &Piece::new(PieceClass::King, Team::Black)));
+
+        for name in ["KQ", "QvK", "KvKK", "KXvK", ""] {
+            assert!(matches!(Material::parse(name), Err(DtmError::InvalidMaterial(_))), "{}", name);
+        }
+        assert!(matches!(Material::parse("KQRvKR"), Err(DtmError::TooManyPieces)));
+    }
+
+    #[test]
+    fn the_stronger_team_is_named_first() {
+        let pieces: Vec<Piece> = Material::parse("KvKQ").unwrap().pieces().to_vec();
+        assert_eq!(normalized_name(&pieces), "KQvK");
+        assert_eq!(Material::parse("KPvK").unwrap().successors(), ["KBvK", "KNvK", "KQvK", "KRvK", "KvK"]);
+        assert_eq!(Material::parse("KRvKN").unwrap().successors(), ["KNvK", "KRvK"]);
+    }
+
+    #[test]
+    fn mirrored_positions_share_an_index() {
+        let material: Material = Material::parse("KQvK").unwrap();
+        let index: usize = material.index(&squares(&["b2", "c3", "h8"]), true);
+
+        for mirrored in [["g2", "f3", "a8"], ["b7", "c6", "h1"], ["g7", "f6", "a1"], ["b2", "c3", "h8"]] {
+            assert_eq!(material.index(&squares(&mirrored), true), index, "{:?}", mirrored);
+        }
+        assert_ne!(material.index(&squares(&["b2", "c3", "h8"]), false), index);
+
+        // Every index decodes to a position which has that index again.
+        let (decoded, white_to_play) = material.decode(index);
+        assert!(white_to_play);
+        assert_eq!(material.index(&decoded, white_to_play), index);
+    }
+
+    #[test]
+    fn positions_with_pawns_are_only_mirrored_from_side_to_side() {
+        let material: Material = Material::parse("KPvK").unwrap();
+        let index: usize = material.index(&squares(&["b2", "c3", "h8"]), true);
+
+        assert_eq!(material.index(&squares(&["g2", "f3", "a8"]), true), index);
+        assert_ne!(material.index(&squares(&["b7", "c6", "h1"]), true), index);
+
+        // The white king is kept on one half of the board with pawns, and on a tenth of it without them.
+        assert_eq!(material.size(), 2 * 32 * 64 * 64);
+        assert_eq!(Material::parse("KQvK").unwrap().size(), 2 * 10 * 64 * 64);
+    }
+
+    #[test]
+    fn identical_pieces_can_be_swapped() {
+        let material: Material = Material::parse("KNNvK").unwrap();
+
+        assert_eq!(
+            material.index(&squares(&["a1", "c3", "d4", "h8"]), false),
+            material.index(&squares(&["a1", "d4", "c3", "h8"]), false)
+        );
+    }
+
+    #[test]
+    fn squares_are_read_from_the_board() {
+        let material: Material = Material::parse("KQvK").unwrap();
+        let board: Board = material.board(&squares(&["e1", "d1", "e8"]), true);
+        assert_eq!(material.squares(&board, false), Some(squares(&["e1", "d1", "e8"])));
+        assert_eq!(material.squares(&board, true), None);
+
+        // Flipping the board swaps the colors and mirrors the rows, which reads the queen of black as the one of white.
+        let board: Board = Board::new_with_fen(Fen { state: "3qk3/8/8/8/8/8/8/4K3 w - - 0 1".to_string() });
+        assert_eq!(material.squares(&board, false), None);
+        assert_eq!(material.squares(&board, true), Some(squares(&["e1", "d1", "e8"])));
+    }
+}
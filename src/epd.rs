@@ -0,0 +1,307 @@
+use crate::board::{Board, BoardError, Fen};
+use crate::moves::Move;
+
+/// An operation of an EPD record, made of an opcode such as `bm` or `id` and the operands which follow it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdOperation {
+    pub opcode: String,
+    pub operands: Vec<String>,
+}
+
+/// A position in the Extended Position Description format: the first four fields of a FEN, followed by operations which
+/// describe the position (e.g. `bm Nf3; id "WAC.001";`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Epd {
+    /// The piece placement, the team to play, the castling rights, and the en passant coordinate.
+    pub position: String,
+    pub operations: Vec<EpdOperation>,
+}
+
+impl Epd {
+    /// Creates an EPD of the position on the board. The clocks of the board are kept in the `hmvc` and `fmvn`
+    /// operations.
+    pub fn from_board(board: &Board) -> Self {
+        let fen: Fen = board.fen();
+        let fields: Vec<&str> = fen.state.split_whitespace().collect();
+        let mut epd: Epd = Epd {
+            position: fields[..4].join(" "),
+            operations: Vec::new(),
+        };
+        epd.set_operation("hmvc", &[&board.halfmove_clock().to_string()]);
+        epd.set_operation("fmvn", &[&board.fullmove_number().to_string()]);
+        epd
+    }
+
+    /// Parses a single line of EPD.
+    pub fn parse(line: &str) -> Result<Self, EpdError> {
+        let line: &str = line.trim();
+        let mut rest: &str = line;
+        let mut fields: Vec<&str> = Vec::new();
+        while fields.len() < 4 {
+            let (field, remaining) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            if field.is_empty() {
+                return Err(EpdError::MissingFields);
+            }
+            fields.push(field);
+            rest = remaining.trim_start();
+        }
+
+        Ok(Epd {
+            position: fields.join(" "),
+            operations: parse_operations(rest)?,
+        })
+    }
+
+    /// Creates a board in the position, with its clocks taken from the `hmvc` and `fmvn` operations when they are
+    /// present.
+    pub fn board(&self) -> Result<Board, BoardError> {
+        let halfmove_clock: &str = self.first_operand("hmvc").unwrap_or("0");
+        let fullmove_number: &str = self.first_operand("fmvn").unwrap_or("1");
+        Board::try_new_with_fen(Fen {
+            state: format!("{} {} {}", self.position, halfmove_clock, fullmove_number),
+        })
+    }
+
+    pub fn operation(&self, opcode: &str) -> Option<&EpdOperation> {
+        self.operations.iter().find(|operation| operation.opcode == opcode)
+    }
+
+    /// Sets the operands of the operation with the opcode, adding the operation if the EPD does not have it yet.
+    pub fn set_operation(&mut self, opcode: &str, operands: &[&str]) {
+        let operands: Vec<String> = operands.iter().map(|operand| operand.to_string()).collect();
+        match self.operations.iter_mut().find(|operation| operation.opcode == opcode) {
+            Some(operation) => operation.operands = operands,
+            None => self.operations.push(EpdOperation {
+                opcode: opcode.to_string(),
+                operands,
+            }),
+        }
+    }
+
+    fn first_operand(&self, opcode: &str) -> Option<&str> {
+        self.operation(opcode)
+            .and_then(|operation| operation.operands.first())
+            .map(|operand| operand.as_str())
+    }
+
+    /// The name of the position given by the `id` operation.
+    pub fn id(&self) -> Option<&str> {
+        self.first_operand("id")
+    }
+
+    /// The comment given by the `c0` operation.
+    pub fn comment(&self) -> Option<&str> {
+        self.first_operand("c0")
+    }
+
+    /// The best moves of the position given by the `bm` operation.
+    pub fn best_moves(&self) -> Result<Vec<Move>, BoardError> {
+        self.moves("bm")
+    }
+
+    /// The moves to avoid in the position given by the `am` operation.
+    pub fn avoid_moves(&self) -> Result<Vec<Move>, BoardError> {
+        self.moves("am")
+    }
+
+    /// The number of moves that the team to play needs to checkmate, given by the `dm` operation.
+    pub fn direct_mate(&self) -> Option<u32> {
+        self.first_operand("dm").and_then(|operand| operand.parse().ok())
+    }
+
+    /// The depth in half moves that the position was analysed to, given by the `acd` operation.
+    pub fn analysis_depth(&self) -> Option<u32> {
+        self.first_operand("acd").and_then(|operand| operand.parse().ok())
+    }
+
+    /// The line of moves predicted from the position given by the `pv` operation, where every move is played from the
+    /// position reached by the moves before it.
+    pub fn principal_variation(&self) -> Result<Vec<Move>, BoardError> {
+        let mut board: Board = self.board()?;
+        let mut moves: Vec<Move> = Vec::new();
+        for san in self.operation("pv").map_or(&[][..], |operation| &operation.operands[..]) {
+            let chess_move: Move = board.parse_san(san)?;
+            board.make_move(&chess_move)?;
+            moves.push(chess_move);
+        }
+        Ok(moves)
+    }
+
+    /// Parses the operands of the operation as moves written in standard algebraic notation, all played from the
+    /// position of the EPD.
+    fn moves(&self, opcode: &str) -> Result<Vec<Move>, BoardError> {
+        let board: Board = self.board()?;
+        self.operation(opcode)
+            .map_or(&[][..], |operation| &operation.operands[..])
+            .iter()
+            .map(|san| board.parse_san(san))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Epd {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.position)?;
+        for operation in self.operations.iter() {
+            write!(f, " {}", operation.opcode)?;
+            for operand in operation.operands.iter() {
+                // Identifiers and comments are always quoted, other operands only when they would not be read back as
+                // a single operand.
+                let is_string: bool = matches!(
+                    operation.opcode.as_bytes(),
+                    [b'i', b'd'] | [b'c', b'0'..=b'9']
+                );
+                if is_string || operand.is_empty() || operand.contains([' ', ';', '"']) {
+                    write!(f, " \"{}\"", operand.replace('"', "\\\""))?;
+                } else {
+                    write!(f, " {}", operand)?;
+                }
+            }
+            write!(f, ";")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses every non-empty line of the text as an EPD, skipping lines which start with `#`.
+pub fn parse_epd(text: &str) -> Result<Vec<Epd>, EpdError> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(Epd::parse)
+        .collect()
+}
+
+/// Parses the operations which follow the position, each of which ends with a `;`. Operands are separated by
+/// whitespace unless they are enclosed in double quotes.
+fn parse_operations(text: &str) -> Result<Vec<EpdOperation>, EpdError> {
+    let mut operations: Vec<EpdOperation> = Vec::new();
+    let mut characters = text.chars().peekable();
+
+    loop {
+        while characters.next_if(|character| character.is_whitespace()).is_some() {}
+        if characters.peek().is_none() {
+            return Ok(operations);
+        }
+
+        let mut opcode: String = String::new();
+        while let Some(character) = characters.next_if(|character| !character.is_whitespace() && *character != ';') {
+            opcode.push(character);
+        }
+        if opcode.is_empty() || !opcode.starts_with(|character: char| character.is_ascii_alphabetic()) {
+            return Err(EpdError::InvalidOperation);
+        }
+
+        let mut operands: Vec<String> = Vec::new();
+        loop {
+            while characters.next_if(|character| character.is_whitespace()).is_some() {}
+            match characters.next() {
+                // The last operation of a line is allowed to miss its semicolon.
+                None | Some(';') => break,
+                Some('"') => {
+                    let mut operand: String = String::new();
+                    loop {
+                        match characters.next() {
+                            None => return Err(EpdError::UnterminatedString),
+                            Some('"') => break,
+                            Some('\\') if characters.peek() == Some(&'"') => operand.push(characters.next().unwrap()),
+                            Some(character) => operand.push(character),
+                        }
+                    }
+                    operands.push(operand);
+                }
+                Some(first) => {
+                    let mut operand: String = first.to_string();
+                    while let Some(character) =
+                        characters.next_if(|character| !character.is_whitespace() && *character != ';')
+                    {
+                        operand.push(character);
+                    }
+                    operands.push(operand);
+                }
+            }
+        }
+
+        operations.push(EpdOperation { opcode, operands });
+    }
+}
+
+/// Represents an error encountered when parsing an EPD.
+#[derive(Debug)]
+pub enum EpdError {
+    MissingFields,
+    InvalidOperation,
+    UnterminatedString,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STARTING_POSITION: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -";
+    const WAC_001: &str = r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";"#;
+
+    #[test]
+    fn operations_are_parsed() {
+        let epd: Epd = Epd::parse(WAC_001).unwrap();
+
+        assert_eq!(epd.position, "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - -");
+        assert_eq!(epd.id(), Some("WAC.001"));
+        let best_moves: Vec<Move> = epd.best_moves().unwrap();
+        assert_eq!(best_moves.len(), 1);
+        assert_eq!(epd.board().unwrap().san(&best_moves[0]), "Qg6");
+    }
+
+    #[test]
+    fn quoted_operands_keep_their_spaces_and_semicolons() {
+        let line: &str = r#"4k3/8/8/8/8/8/8/4K3 w - - c0 "A \"quiet\" one; or not"; id "kings only";"#;
+        let epd: Epd = Epd::parse(line).unwrap();
+
+        assert_eq!(epd.comment(), Some(r#"A "quiet" one; or not"#));
+        assert_eq!(epd.id(), Some("kings only"));
+        assert!(matches!(Epd::parse(r#"4k3/8/8/8/8/8/8/4K3 w - - id "open"#), Err(EpdError::UnterminatedString)));
+    }
+
+    #[test]
+    fn move_lists_are_parsed_from_the_position() {
+        let operations: &str = "bm e4 d4 Nf3; am f3 g4; pv e4 e5 Nf3 Nc6; acd 12; dm 3";
+        let epd: Epd = Epd::parse(&format!("{} {}", STARTING_POSITION, operations)).unwrap();
+        let board: Board = epd.board().unwrap();
+        let names = |moves: Vec<Move>| moves.iter().map(|chess_move| board.uci(chess_move)).collect::<Vec<_>>();
+
+        assert_eq!(names(epd.best_moves().unwrap()), ["e2e4", "d2d4", "g1f3"]);
+        assert_eq!(names(epd.avoid_moves().unwrap()), ["f2f3", "g2g4"]);
+        assert_eq!(epd.principal_variation().unwrap().len(), 4);
+        assert_eq!(epd.analysis_depth(), Some(12));
+        assert_eq!(epd.direct_mate(), Some(3));
+
+        let epd: Epd = Epd::parse(&format!("{} bm e5;", STARTING_POSITION)).unwrap();
+        assert!(epd.best_moves().is_err());
+        assert!(matches!(Epd::parse("8/8/8 w -"), Err(EpdError::MissingFields)));
+        assert!(matches!(Epd::parse("4k3/8/8/8/8/8/8/4K3 w - - 1x;"), Err(EpdError::InvalidOperation)));
+    }
+
+    #[test]
+    fn records_survive_being_written_and_read_back() {
+        let mut epd: Epd = Epd::parse(WAC_001).unwrap();
+        epd.set_operation("c0", &["Say \"hi\"; then leave"]);
+        epd.set_operation("pv", &["Qg6", "fxg6", "Nxg6#"]);
+
+        let written: String = epd.to_string();
+        assert!(written.contains(r#"id "WAC.001";"#));
+        assert!(written.contains("pv Qg6 fxg6 Nxg6#;"));
+        assert_eq!(epd.principal_variation().unwrap().len(), 3);
+        assert_eq!(Epd::parse(&written).unwrap(), epd);
+    }
+
+    #[test]
+    fn clocks_are_kept_in_operations() {
+        let mut board: Board = Board::new();
+        board.make_move(&board.parse_uci("g1f3").unwrap()).unwrap();
+        let epd: Epd = Epd::from_board(&board);
+
+        assert_eq!(epd.to_string(), "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - hmvc 1; fmvn 1;");
+        assert_eq!(epd.board().unwrap().fen().state, board.fen().state);
+        assert_eq!(parse_epd(&format!("# a suite\n\n{}\n{}\n", epd, WAC_001)).unwrap().len(), 2);
+    }
+}
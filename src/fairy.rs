@@ -0,0 +1,28 @@
+//! Chess with fairy pieces on boards of other sizes, such as Capablanca chess and Grand chess.
+//!
+//! [`crate::board::Board`] is built around an 8 by 8 board and the six pieces of [`crate::piece::PieceClass`], which
+//! its bitboards, tablebases, and opening books all rely on. Games with other boards and pieces are played on a
+//! [`FairyBoard`] instead, whose pieces are defined by their movement in Betza notation. The two boards share the
+//! teams, the reading of the FEN fields after the piece placement, and the [`crate::castling::CastlingRights`], so
+//! only the parts which depend on the size of the board and on the pieces are kept apart.
+
+mod betza;
+mod board;
+mod geometry;
+mod rules;
+
+pub use betza::{parse_betza, Movement};
+pub use board::{FairyBoard, FairyMove, FairyPiece};
+pub use geometry::{Geometry, Square, MAX_BOARD_SIZE};
+pub use rules::{FairyRules, PieceDefinition};
+
+/// Represents an error encountered when defining or playing a game with fairy pieces.
+#[derive(Debug)]
+pub enum FairyError {
+    InvalidGeometry,
+    InvalidBetza(String),
+    UnknownPiece(char),
+    InvalidFen,
+    InvalidMove,
+    IllegalMove,
+}
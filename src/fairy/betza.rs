@@ -0,0 +1,213 @@
+use super::FairyError;
+
+/// A single way that a piece can move, as a step relative to the team moving it, where a positive `forward` moves
+/// towards the opponent and a positive `right` moves to the right of the player.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Movement {
+    pub forward: i32,
+    pub right: i32,
+
+    /// The largest number of times that the step can be repeated in one move, where 0 means as many times as the board
+    /// allows. Leapers take the step once, while riders keep taking it until they are blocked.
+    pub range: usize,
+
+    /// Whether the movement can be used to move to an empty square.
+    pub moves: bool,
+
+    /// Whether the movement can be used to capture a piece of the opponent.
+    pub captures: bool,
+
+    /// Whether the movement can only be used by a piece which has not moved yet, such as the double step of a pawn.
+    pub initial: bool,
+
+    /// Whether the movement is blocked by a piece on the way, as with the double step of a pawn. Leaps along a line are
+    /// blocked by any piece between the two squares, and other leaps are blocked by the square next to the piece in the
+    /// longer direction of the leap.
+    pub lame: bool,
+}
+
+/// Parses the movement of a piece written in Betza notation, such as `N` for the knight, `BN` for the archbishop, or
+/// `fmWfcFifmnD` for the pawn.
+///
+/// The atoms `W`, `F`, `D`, `N`, `A`, `H`, `C`, `Z`, and `G` are leaps, which become riders when they are doubled
+/// (e.g. `WW`) or limited riders when followed by a number (e.g. `W3`), and `K`, `Q`, `R`, and `B` stand for the pieces
+/// of standard chess. The lowercase modifiers before an atom restrict it: `f`, `b`, `l`, `r`, `v`, and `s` keep the
+/// forward, backward, left, right, vertical, and sideways directions, `m` and `c` keep moving and capturing, `i` keeps
+/// the movement for the first move of the piece, and `n` makes it lame.
+pub fn parse_betza(notation: &str) -> Result<Vec<Movement>, FairyError> {
+    let invalid = || FairyError::InvalidBetza(notation.to_string());
+    let mut movements: Vec<Movement> = Vec::new();
+    let mut characters = notation.chars().peekable();
+
+    while characters.peek().is_some() {
+        let mut modifiers: Vec<char> = Vec::new();
+        while let Some(character) = characters.next_if(|character| character.is_ascii_lowercase()) {
+            if !"fblrvsmcin".contains(character) {
+                return Err(invalid());
+            }
+            modifiers.push(character);
+        }
+
+        let atom: char = characters.next().ok_or_else(invalid)?;
+        let atoms: Vec<(char, usize)> = match atom {
+            'K' => vec![('W', 1), ('F', 1)],
+            'Q' => vec![('W', 0), ('F', 0)],
+            'R' => vec![('W', 0)],
+            'B' => vec![('F', 0)],
+            _ => {
+                let range: usize = if characters.next_if_eq(&atom).is_some() {
+                    0
+                } else {
+                    let mut digits: String = String::new();
+                    while let Some(digit) = characters.next_if(|character| character.is_ascii_digit()) {
+                        digits.push(digit);
+                    }
+                    if digits.is_empty() { 1 } else { digits.parse().map_err(|_| invalid())? }
+                };
+                vec![(atom, range)]
+            }
+        };
+
+        for (atom, range) in atoms {
+            let (long, short) = leap(atom).ok_or_else(invalid)?;
+            for (forward, right) in symmetric_steps(long, short) {
+                if !keeps_direction(&modifiers, forward, right) {
+                    continue;
+                }
+                let restricts_mode: bool = modifiers.contains(&'m') || modifiers.contains(&'c');
+                movements.push(Movement {
+                    forward,
+                    right,
+                    range,
+                    moves: !restricts_mode || modifiers.contains(&'m'),
+                    captures: !restricts_mode || modifiers.contains(&'c'),
+                    initial: modifiers.contains(&'i'),
+                    lame: modifiers.contains(&'n'),
+                });
+            }
+        }
+    }
+
+    if movements.is_empty() {
+        return Err(invalid());
+    }
+    Ok(movements)
+}
+
+/// Gets the longer and the shorter side of the step of a leap atom.
+fn leap(atom: char) -> Option<(i32, i32)> {
+    match atom {
+        'W' => Some((1, 0)),
+        'F' => Some((1, 1)),
+        'D' => Some((2, 0)),
+        'N' => Some((2, 1)),
+        'A' => Some((2, 2)),
+        'H' => Some((3, 0)),
+        'C' => Some((3, 1)),
+        'Z' => Some((3, 2)),
+        'G' => Some((3, 3)),
+        _ => None,
+    }
+}
+
+/// Gets the distinct steps made of the two sides in every direction.
+fn symmetric_steps(long: i32, short: i32) -> Vec<(i32, i32)> {
+    let mut steps: Vec<(i32, i32)> = Vec::new();
+    for (a, b) in [(long, short), (short, long)] {
+        for (forward, right) in [(a, b), (a, -b), (-a, b), (-a, -b)] {
+            if !steps.contains(&(forward, right)) {
+                steps.push((forward, right));
+            }
+        }
+    }
+    steps
+}
+
+/// Checks if the step is kept by the direction modifiers. Forward and backward modifiers are combined with left and
+/// right modifiers by keeping the steps that match both, so that `fl` is the forward left direction, while modifiers of
+/// the same kind keep the steps which match either of them.
+fn keeps_direction(modifiers: &[char], forward: i32, right: i32) -> bool {
+    let vertical: Vec<char> = modifiers.iter().copied().filter(|modifier| "fbv".contains(*modifier)).collect();
+    let horizontal: Vec<char> = modifiers.iter().copied().filter(|modifier| "lrs".contains(*modifier)).collect();
+
+    let keeps_vertical: bool = vertical.is_empty()
+        || vertical.iter().any(|modifier| match modifier {
+            'f' => forward > 0,
+            'b' => forward < 0,
+            _ => forward.abs() > right.abs(),
+        });
+    let keeps_horizontal: bool = horizontal.is_empty()
+        || horizontal.iter().any(|modifier| match modifier {
+            'l' => right < 0,
+            'r' => right > 0,
+            _ => right.abs() > forward.abs(),
+        });
+
+    keeps_vertical && keeps_horizontal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leapers_step_once_in_every_direction() {
+        let movements: Vec<Movement> = parse_betza("N").unwrap();
+
+        assert_eq!(movements.len(), 8);
+        assert!(movements.iter().all(|movement| movement.range == 1 && movement.moves && movement.captures));
+        assert!(movements.iter().any(|movement| movement.forward == 2 && movement.right == -1));
+        assert!(movements.iter().any(|movement| movement.forward == -1 && movement.right == 2));
+    }
+
+    #[test]
+    fn compound_pieces_combine_their_atoms() {
+        let movements: Vec<Movement> = parse_betza("BN").unwrap();
+
+        assert_eq!(movements.iter().filter(|movement| movement.range == 0).count(), 4);
+        assert_eq!(movements.iter().filter(|movement| movement.range == 1).count(), 8);
+        assert_eq!(parse_betza("K").unwrap().len(), 8);
+        assert_eq!(parse_betza("Q").unwrap().len(), 8);
+    }
+
+    #[test]
+    fn doubled_atoms_ride_and_numbers_limit_the_ride() {
+        assert!(parse_betza("WW").unwrap().iter().all(|movement| movement.range == 0));
+        assert!(parse_betza("W3").unwrap().iter().all(|movement| movement.range == 3));
+        assert_eq!(parse_betza("R").unwrap(), parse_betza("WW").unwrap());
+    }
+
+    #[test]
+    fn modifiers_restrict_the_pawn() {
+        let movements: Vec<Movement> = parse_betza("fmWfcFifmnD").unwrap();
+        let expected: Vec<(i32, i32, bool, bool, bool, bool)> = vec![
+            (1, 0, true, false, false, false),
+            (1, 1, false, true, false, false),
+            (1, -1, false, true, false, false),
+            (2, 0, true, false, true, true),
+        ];
+
+        assert_eq!(movements.len(), expected.len());
+        for (forward, right, moves, captures, initial, lame) in expected {
+            assert!(movements.contains(&Movement { forward, right, range: 1, moves, captures, initial, lame }));
+        }
+    }
+
+    #[test]
+    fn sideways_and_vertical_modifiers_split_the_directions() {
+        let sideways: Vec<Movement> = parse_betza("sW").unwrap();
+        assert_eq!(sideways.len(), 2);
+        assert!(sideways.iter().all(|movement| movement.forward == 0));
+
+        let forward_left: Vec<Movement> = parse_betza("flN").unwrap();
+        assert_eq!(forward_left.len(), 2);
+        assert!(forward_left.iter().all(|movement| movement.forward > 0 && movement.right < 0));
+    }
+
+    #[test]
+    fn invalid_notation_is_rejected() {
+        for notation in ["", "X", "f", "xN", "Wf", "w"] {
+            assert!(matches!(parse_betza(notation), Err(FairyError::InvalidBetza(_))), "{}", notation);
+        }
+    }
+}
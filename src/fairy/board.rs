@@ -0,0 +1,694 @@
+use super::betza::Movement;
+use super::geometry::{Geometry, Square};
+use super::rules::{FairyRules, PieceDefinition};
+use super::FairyError;
+use crate::board::Fen;
+use crate::castling::{CastlingRights, CastlingSide};
+use crate::piece::Team;
+use std::sync::Arc;
+
+/// A piece on a [`FairyBoard`], identified by the uppercase letter of its definition in the rules of the board.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct FairyPiece {
+    letter: char,
+    team: Team,
+
+    /// Whether the piece has moved since the start of the game, which decides whether it can use its initial
+    /// movements such as the double step of a pawn.
+    moved: bool,
+}
+
+impl FairyPiece {
+    pub fn letter(&self) -> char {
+        self.letter
+    }
+
+    pub fn team(&self) -> Team {
+        self.team
+    }
+
+    pub fn has_moved(&self) -> bool {
+        self.moved
+    }
+
+    /// The letter of the piece as written in a FEN, which is uppercase for white and lowercase for black.
+    pub fn fen_letter(&self) -> char {
+        match self.team {
+            Team::White => self.letter,
+            Team::Black => self.letter.to_ascii_lowercase(),
+        }
+    }
+}
+
+/// A move on a [`FairyBoard`]. Castling is written as the king moving to its destination.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct FairyMove {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<char>,
+}
+
+/// A board of any size up to 12 by 12 with pieces defined by their movement in Betza notation, played by the rules of
+/// a [`FairyRules`]. The king of each team is the piece marked as royal.
+#[derive(Debug, Clone)]
+pub struct FairyBoard {
+    rules: Arc<FairyRules>,
+    squares: Vec<Option<FairyPiece>>,
+    turn_to_play: Team,
+
+    /// The castling rights of both teams, which hold the columns of the rooks in the corners of the back rows.
+    castling_rights: CastlingRights,
+
+    /// The square that a pawn passed over with its double step on the last move, which pawns of the opponent can
+    /// capture en passant.
+    en_passant: Option<Square>,
+
+    halfmove_clock: u16,
+    fullmove_number: u16,
+}
+
+impl FairyBoard {
+    /// Creates a board in the starting position of the rules.
+    pub fn new(rules: FairyRules) -> Result<Self, FairyError> {
+        let starting_fen: String = rules.starting_fen.clone();
+        Self::try_new_with_fen(rules, &starting_fen)
+    }
+
+    /// Creates a board from a FEN, where empty squares can be counted with numbers of more than one digit. Pieces
+    /// standing where they stand in the starting position of the rules are considered not to have moved. The fields
+    /// after the piece placement are read the way [`crate::board::Board`] reads them.
+    pub fn try_new_with_fen(rules: FairyRules, fen: &str) -> Result<Self, FairyError> {
+        let fen: Fen = Fen { state: fen.to_string() };
+        let starting_fen: Fen = Fen { state: rules.starting_fen.clone() };
+        let starting_squares: Vec<Option<FairyPiece>> = parse_placement(&rules, &starting_fen.board_pieces_state())?;
+        let fields: Vec<&str> = fen.state.split_whitespace().collect();
+        let mut squares: Vec<Option<FairyPiece>> = parse_placement(&rules, &fen.board_pieces_state())?;
+        for (square, starting_square) in squares.iter_mut().zip(starting_squares.iter()) {
+            if let Some(piece) = square {
+                piece.moved = !matches!(
+                    starting_square,
+                    Some(starting_piece) if starting_piece.letter == piece.letter && starting_piece.team == piece.team
+                );
+            }
+        }
+
+        let turn_to_play: Team = fen.turn_to_play().ok_or(FairyError::InvalidFen)?;
+
+        // The rights are parsed as on a standard board, and then moved to the corners of the wider or narrower board.
+        let mut castling_rights: CastlingRights =
+            CastlingRights::try_from(fields.get(2).copied().unwrap_or("-")).map_err(|_| FairyError::InvalidFen)?;
+        for team in [Team::White, Team::Black] {
+            for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+                if castling_rights.has(team, side) {
+                    castling_rights.set(team, side, Some(corner_column(&rules.geometry, side)));
+                }
+            }
+        }
+
+        let en_passant: Option<Square> = match fields.get(3) {
+            None | Some(&"-") => None,
+            Some(field) => Some(rules.geometry.parse_square(field).ok_or(FairyError::InvalidFen)?),
+        };
+        let clock = |index: usize, default: u16| -> Result<u16, FairyError> {
+            fields.get(index).map_or(Ok(default), |field| field.parse().map_err(|_| FairyError::InvalidFen))
+        };
+
+        Ok(Self {
+            halfmove_clock: clock(4, 0)?,
+            fullmove_number: clock(5, 1)?,
+            rules: Arc::new(rules),
+            squares,
+            turn_to_play,
+            castling_rights,
+            en_passant,
+        })
+    }
+
+    pub fn rules(&self) -> &FairyRules {
+        &self.rules
+    }
+
+    pub fn geometry(&self) -> Geometry {
+        self.rules.geometry
+    }
+
+    pub fn get_piece(&self, square: Square) -> Option<FairyPiece> {
+        self.squares[self.rules.geometry.index(square)]
+    }
+
+    fn set_piece(&mut self, square: Square, piece: Option<FairyPiece>) {
+        let index: usize = self.rules.geometry.index(square);
+        self.squares[index] = piece;
+    }
+
+    pub fn turn_to_play(&self) -> Team {
+        self.turn_to_play
+    }
+
+    pub fn en_passant(&self) -> Option<Square> {
+        self.en_passant
+    }
+
+    pub fn halfmove_clock(&self) -> u16 {
+        self.halfmove_clock
+    }
+
+    pub fn fullmove_number(&self) -> u16 {
+        self.fullmove_number
+    }
+
+    /// Gets the FEN of the board.
+    pub fn fen(&self) -> String {
+        let geometry: Geometry = self.rules.geometry;
+        let mut rows: Vec<String> = Vec::new();
+        for row in 0..geometry.rows() {
+            let mut row_string: String = String::new();
+            let mut empty: usize = 0;
+            for column in 0..geometry.columns() {
+                match self.get_piece(geometry.square(row, column).unwrap()) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            row_string.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        row_string.push(piece.fen_letter());
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                row_string.push_str(&empty.to_string());
+            }
+            rows.push(row_string);
+        }
+
+        format!(
+            "{} {} {} {} {} {}",
+            rows.join("/"),
+            if matches!(self.turn_to_play, Team::White) { 'w' } else { 'b' },
+            self.castling_rights,
+            self.en_passant.map_or("-".to_string(), |square| geometry.square_name(square)),
+            self.halfmove_clock,
+            self.fullmove_number,
+        )
+    }
+
+    /// Writes the move as its two squares followed by the lowercase letter of the promotion (e.g. `e2e4`, `a9a10q`).
+    pub fn move_name(&self, fairy_move: &FairyMove) -> String {
+        let geometry: Geometry = self.rules.geometry;
+        let mut name: String = geometry.square_name(fairy_move.from) + &geometry.square_name(fairy_move.to);
+        if let Some(promotion) = fairy_move.promotion {
+            name.push(promotion.to_ascii_lowercase());
+        }
+        name
+    }
+
+    /// Parses a move written the way [`FairyBoard::move_name`] writes it into one of the legal moves of the board.
+    pub fn parse_move(&self, name: &str) -> Result<FairyMove, FairyError> {
+        let geometry: Geometry = self.rules.geometry;
+        // The squares are split at the column letter of the second square, which comes after the digits of the first.
+        let second: usize = name
+            .char_indices()
+            .skip(1)
+            .find(|(_, character)| character.is_ascii_alphabetic())
+            .map(|(index, _)| index)
+            .ok_or(FairyError::InvalidMove)?;
+        let digits_end: usize = name[second + 1..]
+            .find(|character: char| !character.is_ascii_digit())
+            .map_or(name.len(), |index| second + 1 + index);
+
+        let from: Square = geometry.parse_square(&name[..second]).ok_or(FairyError::InvalidMove)?;
+        let to: Square = geometry.parse_square(&name[second..digits_end]).ok_or(FairyError::InvalidMove)?;
+        let promotion: Option<char> = match &name[digits_end..] {
+            "" => None,
+            letter if letter.len() == 1 => letter.chars().next().map(|letter| letter.to_ascii_uppercase()),
+            _ => return Err(FairyError::InvalidMove),
+        };
+
+        let fairy_move: FairyMove = FairyMove { from, to, promotion };
+        self.legal_moves().contains(&fairy_move).then_some(fairy_move).ok_or(FairyError::IllegalMove)
+    }
+
+    /// Gets all of the legal moves of the team which has the turn to play.
+    pub fn legal_moves(&self) -> Vec<FairyMove> {
+        let team: Team = self.turn_to_play;
+        let mut moves: Vec<FairyMove> = Vec::new();
+        for square in self.rules.geometry.squares() {
+            if matches!(self.get_piece(square), Some(piece) if piece.team == team) {
+                self.piece_pseudo_legal_moves(square, &mut moves);
+            }
+        }
+        moves.retain(|fairy_move| {
+            let mut next_board: Self = self.clone();
+            next_board.apply_move(fairy_move);
+            !next_board.is_in_check(team)
+        });
+
+        self.castling_moves(team, &mut moves);
+        moves
+    }
+
+    /// Performs a move on the board. Checks that the move is legal before performing it.
+    pub fn make_move(&mut self, fairy_move: &FairyMove) -> Result<(), FairyError> {
+        if !self.legal_moves().contains(fairy_move) {
+            return Err(FairyError::IllegalMove);
+        }
+        self.apply_move(fairy_move);
+        Ok(())
+    }
+
+    /// Checks if the royal piece of the team is attacked by the opponent.
+    pub fn is_in_check(&self, team: Team) -> bool {
+        self.rules.geometry.squares().any(|square| {
+            matches!(self.get_piece(square), Some(piece) if piece.team == team && self.definition(&piece).royal)
+                && self.is_attacked(square, team.opponent())
+        })
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check(self.turn_to_play) && self.legal_moves().is_empty()
+    }
+
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_in_check(self.turn_to_play) && self.legal_moves().is_empty()
+    }
+
+    /// Checks if a winner can be declared, which happens when the team to play has been checkmated, and returns the
+    /// team which won.
+    pub fn winner(&self) -> Option<Team> {
+        self.is_checkmate().then(|| self.turn_to_play.opponent())
+    }
+
+    /// Checks if the game is drawn by a stalemate or by the fifty move rule.
+    pub fn is_draw(&self) -> bool {
+        self.is_stalemate() || self.halfmove_clock >= 100
+    }
+
+    fn definition(&self, piece: &FairyPiece) -> &PieceDefinition {
+        self.rules.piece(piece.letter).unwrap()
+    }
+
+    /// Converts a step relative to the team into numbers of rows and columns. White moves forward towards the row 0,
+    /// and the right of black is the left of white.
+    fn step(team: Team, movement: &Movement) -> (i32, i32) {
+        match team {
+            Team::White => (-movement.forward, movement.right),
+            Team::Black => (movement.forward, -movement.right),
+        }
+    }
+
+    /// Checks if a lame movement from the square is blocked by a piece on the way to the target of its step.
+    fn is_blocked(&self, from: Square, rows: i32, columns: i32) -> bool {
+        let geometry: Geometry = self.rules.geometry;
+        if rows == 0 || columns == 0 || rows.abs() == columns.abs() {
+            let distance: i32 = rows.abs().max(columns.abs());
+            (1..distance).any(|step| {
+                let square = geometry.offset(from, rows.signum() * step, columns.signum() * step);
+                square.is_some_and(|square| self.get_piece(square).is_some())
+            })
+        } else if rows.abs() > columns.abs() {
+            geometry.offset(from, rows.signum(), 0).is_some_and(|square| self.get_piece(square).is_some())
+        } else {
+            geometry.offset(from, 0, columns.signum()).is_some_and(|square| self.get_piece(square).is_some())
+        }
+    }
+
+    /// Gets the squares that the movement of the piece on the square reaches, stopping at the first occupied square.
+    fn reachable_squares(&self, from: Square, piece: &FairyPiece, movement: &Movement) -> Vec<Square> {
+        let geometry: Geometry = self.rules.geometry;
+        let (rows, columns) = Self::step(piece.team, movement);
+        if movement.initial && piece.moved || movement.lame && self.is_blocked(from, rows, columns) {
+            return Vec::new();
+        }
+
+        let range: usize = if movement.range == 0 { usize::MAX } else { movement.range };
+        let mut squares: Vec<Square> = Vec::new();
+        let mut current: Square = from;
+        while squares.len() < range {
+            current = match geometry.offset(current, rows, columns) {
+                Some(square) => square,
+                None => break,
+            };
+            squares.push(current);
+            if self.get_piece(current).is_some() {
+                break;
+            }
+        }
+        squares
+    }
+
+    /// Checks if any piece of the team attacks the square.
+    fn is_attacked(&self, target: Square, by: Team) -> bool {
+        self.rules.geometry.squares().any(|from| {
+            let piece: FairyPiece = match self.get_piece(from) {
+                Some(piece) if piece.team == by => piece,
+                _ => return false,
+            };
+            self.definition(&piece)
+                .movements
+                .iter()
+                .filter(|movement| movement.captures)
+                .any(|movement| self.reachable_squares(from, &piece, movement).contains(&target))
+        })
+    }
+
+    /// Adds all of the moves that the piece on the square can make according to its movements to the moves, without
+    /// checking whether the moves leave the king in check. Castling is not included.
+    fn piece_pseudo_legal_moves(&self, from: Square, moves: &mut Vec<FairyMove>) {
+        let piece: FairyPiece = self.get_piece(from).unwrap();
+        let definition: &PieceDefinition = self.definition(&piece);
+
+        let mut targets: Vec<Square> = Vec::new();
+        for movement in definition.movements.iter() {
+            for to in self.reachable_squares(from, &piece, movement) {
+                let allowed: bool = match self.get_piece(to) {
+                    None => movement.moves || (definition.pawn && movement.captures && Some(to) == self.en_passant),
+                    Some(other_piece) => movement.captures && other_piece.team != piece.team,
+                };
+                if allowed && !targets.contains(&to) {
+                    targets.push(to);
+                }
+            }
+        }
+
+        for to in targets {
+            if !definition.pawn {
+                moves.push(FairyMove { from, to, promotion: None });
+                continue;
+            }
+
+            let rows_left: usize = match piece.team {
+                Team::White => to.row(),
+                Team::Black => self.rules.geometry.rows() - 1 - to.row(),
+            };
+            if rows_left >= self.rules.promotion_rows {
+                moves.push(FairyMove { from, to, promotion: None });
+                continue;
+            }
+
+            // Promotion is optional until the last row, where a pawn which can not be promoted can not move.
+            if rows_left > 0 {
+                moves.push(FairyMove { from, to, promotion: None });
+            }
+            for letter in self.rules.promotion_pieces.iter() {
+                if self.can_promote_to(piece.team, *letter) {
+                    moves.push(FairyMove { from, to, promotion: Some(*letter) });
+                }
+            }
+        }
+    }
+
+    /// Checks if a pawn of the team can be promoted to the piece, which is always the case unless pawns can only be
+    /// promoted to pieces that the team has lost.
+    fn can_promote_to(&self, team: Team, letter: char) -> bool {
+        if !self.rules.promote_to_captured_only {
+            return true;
+        }
+        let count = |squares: &[Option<FairyPiece>]| {
+            squares
+                .iter()
+                .flatten()
+                .filter(|piece| piece.team == team && piece.letter == letter)
+                .count()
+        };
+        let starting_fen: Fen = Fen { state: self.rules.starting_fen.clone() };
+        let starting_squares: Vec<Option<FairyPiece>> =
+            parse_placement(&self.rules, &starting_fen.board_pieces_state()).unwrap_or_default();
+        count(&self.squares) < count(&starting_squares)
+    }
+
+    /// Adds the castling moves of the team to the moves. The king castles with the rook in the corner of its row when
+    /// all of the squares between the king, the rook, and their destinations are empty, and the king does not start
+    /// on, pass through, or land on an attacked square.
+    fn castling_moves(&self, team: Team, moves: &mut Vec<FairyMove>) {
+        if !self.rules.castling {
+            return;
+        }
+        let geometry: Geometry = self.rules.geometry;
+        let row: usize = back_row(&geometry, team);
+
+        let king: Square = match geometry.squares().find(|square| {
+            square.row() == row
+                && matches!(self.get_piece(*square), Some(piece) if piece.team == team && self.definition(&piece).royal)
+        }) {
+            Some(king) => king,
+            None => return,
+        };
+
+        for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+            let rook_column: usize = match self.castling_rights.rook_column(team, side) {
+                Some(rook_column) => rook_column,
+                None => continue,
+            };
+            let (king_destination, rook_destination) = castling_columns(&geometry, side);
+            let rook: Square = geometry.square(row, rook_column).unwrap();
+            if !matches!(self.get_piece(rook), Some(piece) if piece.team == team && piece.letter == 'R') {
+                continue;
+            }
+
+            let columns = [king.column(), rook_column, king_destination, rook_destination];
+            let span = *columns.iter().min().unwrap()..=*columns.iter().max().unwrap();
+            if span
+                .map(|column| geometry.square(row, column).unwrap())
+                .any(|square| square != king && square != rook && self.get_piece(square).is_some())
+            {
+                continue;
+            }
+
+            let king_path = king.column().min(king_destination)..=king.column().max(king_destination);
+            if king_path
+                .map(|column| geometry.square(row, column).unwrap())
+                .any(|square| self.is_attacked(square, team.opponent()))
+            {
+                continue;
+            }
+
+            moves.push(FairyMove { from: king, to: geometry.square(row, king_destination).unwrap(), promotion: None });
+        }
+    }
+
+    /// Performs a move on the board without checking that it is legal.
+    fn apply_move(&mut self, fairy_move: &FairyMove) {
+        let geometry: Geometry = self.rules.geometry;
+        let (from, to) = (fairy_move.from, fairy_move.to);
+        let mut piece: FairyPiece = self.get_piece(from).unwrap();
+        let definition: PieceDefinition = self.definition(&piece).clone();
+
+        let mut resets_halfmove_clock: bool = definition.pawn || self.get_piece(to).is_some();
+
+        // A pawn moving diagonally onto the en passant square captures the pawn which passed over it.
+        let is_en_passant: bool = Some(to) == self.en_passant && self.get_piece(to).is_none();
+        if definition.pawn && is_en_passant && from.column() != to.column() {
+            self.set_piece(geometry.square(from.row(), to.column()).unwrap(), None);
+            resets_halfmove_clock = true;
+        }
+
+        // Castling moves the rook to the other side of the king.
+        let is_castling: bool = from.row() == to.row() && from.column().abs_diff(to.column()) > 1;
+        if definition.royal && self.rules.castling && is_castling {
+            let side: CastlingSide = if to.column() > from.column() {
+                CastlingSide::KingSide
+            } else {
+                CastlingSide::QueenSide
+            };
+            let rook_column: usize = corner_column(&geometry, side);
+            let (_, rook_destination) = castling_columns(&geometry, side);
+            let mut rook: FairyPiece = self.get_piece(geometry.square(from.row(), rook_column).unwrap()).unwrap();
+            rook.moved = true;
+            self.set_piece(geometry.square(from.row(), rook_column).unwrap(), None);
+            self.set_piece(geometry.square(from.row(), rook_destination).unwrap(), Some(rook));
+        }
+
+        piece.moved = true;
+        if let Some(promotion) = fairy_move.promotion {
+            piece.letter = promotion;
+        }
+        self.set_piece(from, None);
+        self.set_piece(to, Some(piece));
+
+        // Moving the king or a rook, or capturing a rook, gives up the castling rights which rely on them.
+        if definition.royal {
+            self.castling_rights.remove_team(piece.team);
+        }
+        for team in [Team::White, Team::Black] {
+            for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+                let rook: Option<Square> = self
+                    .castling_rights
+                    .rook_column(team, side)
+                    .and_then(|rook_column| geometry.square(back_row(&geometry, team), rook_column));
+                if rook.is_some_and(|rook| rook == from || rook == to) {
+                    self.castling_rights.set(team, side, None);
+                }
+            }
+        }
+
+        // A pawn which moves two rows straight forward can be captured en passant on the next move.
+        self.en_passant = if definition.pawn && from.column() == to.column() && from.row().abs_diff(to.row()) == 2 {
+            geometry.square((from.row() + to.row()) / 2, from.column())
+        } else {
+            None
+        };
+
+        if resets_halfmove_clock {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if matches!(piece.team, Team::Black) {
+            self.fullmove_number += 1;
+        }
+        self.turn_to_play = self.turn_to_play.opponent();
+    }
+}
+
+/// Parses the piece placement field of a FEN into the squares of a board of the rules.
+fn parse_placement(rules: &FairyRules, placement: &str) -> Result<Vec<Option<FairyPiece>>, FairyError> {
+    let geometry: Geometry = rules.geometry;
+    let rows: Vec<&str> = placement.split('/').collect();
+    if rows.len() != geometry.rows() {
+        return Err(FairyError::InvalidFen);
+    }
+
+    let mut squares: Vec<Option<FairyPiece>> = vec![None; geometry.size()];
+    for (row, row_data) in rows.into_iter().enumerate() {
+        let mut column: usize = 0;
+        let mut empty: usize = 0;
+        for character in row_data.chars() {
+            if let Some(digit) = character.to_digit(10) {
+                empty = empty * 10 + digit as usize;
+                continue;
+            }
+            column += std::mem::take(&mut empty);
+
+            rules.piece(character).ok_or(FairyError::UnknownPiece(character))?;
+            let square: Square = geometry.square(row, column).ok_or(FairyError::InvalidFen)?;
+            squares[geometry.index(square)] = Some(FairyPiece {
+                letter: character.to_ascii_uppercase(),
+                team: if character.is_ascii_uppercase() { Team::White } else { Team::Black },
+                moved: false,
+            });
+            column += 1;
+        }
+        if column + empty != geometry.columns() {
+            return Err(FairyError::InvalidFen);
+        }
+    }
+    Ok(squares)
+}
+
+/// The row that the pieces of the team start the game on.
+fn back_row(geometry: &Geometry, team: Team) -> usize {
+    match team {
+        Team::Black => 0,
+        Team::White => geometry.rows() - 1,
+    }
+}
+
+/// Gets the column of the corner that the rook which the king castles with towards the side starts in.
+fn corner_column(geometry: &Geometry, side: CastlingSide) -> usize {
+    match side {
+        CastlingSide::KingSide => geometry.columns() - 1,
+        CastlingSide::QueenSide => 0,
+    }
+}
+
+/// Gets the columns that the king and the rook end up on after castling towards the side. As in standard chess, the
+/// king lands on the third column on the queen side and on the second column from the edge on the king side, and the
+/// rook lands next to it on the side of the center.
+fn castling_columns(geometry: &Geometry, side: CastlingSide) -> (usize, usize) {
+    match side {
+        CastlingSide::KingSide => (geometry.columns() - 2, geometry.columns() - 3),
+        CastlingSide::QueenSide => (2, 3),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(rules: FairyRules, fen: &str) -> FairyBoard {
+        FairyBoard::try_new_with_fen(rules, fen).unwrap()
+    }
+
+    fn move_names(board: &FairyBoard, from: &str) -> Vec<String> {
+        let from: Square = board.geometry().parse_square(from).unwrap();
+        let mut names: Vec<String> = board
+            .legal_moves()
+            .iter()
+            .filter(|fairy_move| fairy_move.from == from)
+            .map(|fairy_move| board.move_name(fairy_move))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn starting_positions_have_the_expected_moves() {
+        assert_eq!(FairyBoard::new(FairyRules::capablanca()).unwrap().legal_moves().len(), 28);
+        assert_eq!(FairyBoard::new(FairyRules::grand()).unwrap().legal_moves().len(), 65);
+    }
+
+    #[test]
+    fn starting_fens_are_written_back() {
+        for rules in [FairyRules::capablanca(), FairyRules::grand()] {
+            let starting_fen: String = rules.starting_fen.clone();
+            assert_eq!(FairyBoard::new(rules).unwrap().fen(), starting_fen);
+        }
+    }
+
+    #[test]
+    fn archbishop_moves_like_a_bishop_or_a_knight() {
+        let board: FairyBoard = board(FairyRules::capablanca(), "k9/10/10/10/4A5/10/10/K9 w - - 0 1");
+        let moves: Vec<String> = move_names(&board, "e4");
+
+        assert_eq!(moves.len(), 22);
+        for name in ["e4h7", "e4a8", "e4b1", "e4g5", "e4c3", "e4f2"] {
+            assert!(moves.contains(&name.to_string()), "{}", name);
+        }
+        assert!(!moves.contains(&"e4e5".to_string()));
+    }
+
+    #[test]
+    fn chancellor_moves_like_a_rook_or_a_knight() {
+        let board: FairyBoard = board(FairyRules::capablanca(), "k9/10/10/10/4C5/10/10/K9 w - - 0 1");
+        let moves: Vec<String> = move_names(&board, "e4");
+
+        assert_eq!(moves.len(), 24);
+        for name in ["e4e8", "e4j4", "e4a4", "e4e1", "e4f6", "e4c3"] {
+            assert!(moves.contains(&name.to_string()), "{}", name);
+        }
+        assert!(!moves.contains(&"e4f5".to_string()));
+    }
+
+    #[test]
+    fn kings_castle_with_the_rooks_in_the_corners() {
+        let mut board: FairyBoard = board(FairyRules::capablanca(), "r4k3r/10/10/10/10/10/10/R4K3R w KQkq - 0 1");
+        assert!(move_names(&board, "f1").contains(&"f1i1".to_string()));
+        assert!(move_names(&board, "f1").contains(&"f1c1".to_string()));
+
+        board.make_move(&board.parse_move("f1i1").unwrap()).unwrap();
+        assert_eq!(board.fen(), "r4k3r/10/10/10/10/10/10/R6RK1 b kq - 1 1");
+
+        board.make_move(&board.parse_move("a8a1").unwrap()).unwrap();
+        assert_eq!(board.fen(), "5k3r/10/10/10/10/10/10/r6RK1 w k - 0 2");
+    }
+
+    #[test]
+    fn grand_pawns_only_promote_to_lost_pieces() {
+        let board: FairyBoard = board(FairyRules::grand(), "k9/4P5/10/10/10/10/10/10/10/1Q2K5 w - - 0 1");
+
+        assert_eq!(move_names(&board, "e9"), vec!["e9e10a", "e9e10b", "e9e10c", "e9e10n", "e9e10r"]);
+        assert!(board.parse_move("e9e10q").is_err());
+        assert!(board.parse_move("e9e10").is_err());
+    }
+
+    #[test]
+    fn grand_pawns_can_stay_unpromoted_before_the_last_row() {
+        let board: FairyBoard = board(FairyRules::grand(), "k9/10/10/4P5/10/10/10/10/10/4K5 w - - 0 1");
+
+        assert_eq!(
+            move_names(&board, "e7"),
+            vec!["e7e8", "e7e8a", "e7e8b", "e7e8c", "e7e8n", "e7e8q", "e7e8r"]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use super::FairyError;
+
+/// The largest number of rows or columns that a board can have.
+pub const MAX_BOARD_SIZE: usize = 12;
+
+/// The size of a board, with up to [`MAX_BOARD_SIZE`] rows and columns.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Geometry {
+    rows: usize,
+    columns: usize,
+}
+
+impl Geometry {
+    pub fn new(rows: usize, columns: usize) -> Result<Self, FairyError> {
+        if !(1..=MAX_BOARD_SIZE).contains(&rows) || !(1..=MAX_BOARD_SIZE).contains(&columns) {
+            return Err(FairyError::InvalidGeometry);
+        }
+        Ok(Self { rows, columns })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The number of squares on the board.
+    pub fn size(&self) -> usize {
+        self.rows * self.columns
+    }
+
+    /// Gets the square at the row and the column, if it is on the board.
+    pub fn square(&self, row: usize, column: usize) -> Option<Square> {
+        (row < self.rows && column < self.columns).then_some(Square { row, column })
+    }
+
+    /// Gets the square which is offset from the square by the numbers of rows and columns, if it is on the board.
+    pub fn offset(&self, square: Square, rows: i32, columns: i32) -> Option<Square> {
+        let row: usize = usize::try_from(square.row as i32 + rows).ok()?;
+        let column: usize = usize::try_from(square.column as i32 + columns).ok()?;
+        self.square(row, column)
+    }
+
+    /// Gets the index of the square, counting the squares row by row from the first column of the row 0.
+    pub fn index(&self, square: Square) -> usize {
+        square.row * self.columns + square.column
+    }
+
+    /// Gets all of the squares of the board in the order of their indices.
+    pub fn squares(&self) -> impl Iterator<Item = Square> + '_ {
+        (0..self.rows).flat_map(move |row| (0..self.columns).map(move |column| Square { row, column }))
+    }
+
+    /// Parses a square written as its column letter followed by its rank number (e.g. `e4`, `j10`).
+    pub fn parse_square(&self, string: &str) -> Option<Square> {
+        let mut characters = string.chars();
+        let column_letter: char = characters.next()?.to_ascii_lowercase();
+        if !column_letter.is_ascii_lowercase() {
+            return None;
+        }
+        let rank: usize = characters.as_str().parse().ok()?;
+        if rank == 0 || rank > self.rows {
+            return None;
+        }
+        self.square(self.rows - rank, (column_letter as u8 - b'a') as usize)
+    }
+
+    /// Writes the square as its column letter followed by its rank number.
+    pub fn square_name(&self, square: Square) -> String {
+        format!("{}{}", (b'a' + square.column as u8) as char, self.rows - square.row)
+    }
+}
+
+/// A square of a board. Like [`crate::coordinate::Coordinate`], the row 0 is the last rank of the board as seen by
+/// white and the column 0 is the `a` column.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Square {
+    row: usize,
+    column: usize,
+}
+
+impl Square {
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boards_have_between_one_and_twelve_rows_and_columns() {
+        assert!(Geometry::new(1, 1).is_ok());
+        assert!(Geometry::new(MAX_BOARD_SIZE, MAX_BOARD_SIZE).is_ok());
+        for (rows, columns) in [(0, 8), (8, 0), (MAX_BOARD_SIZE + 1, 8), (8, MAX_BOARD_SIZE + 1)] {
+            assert!(matches!(Geometry::new(rows, columns), Err(FairyError::InvalidGeometry)));
+        }
+    }
+
+    #[test]
+    fn squares_off_the_board_do_not_exist() {
+        let geometry: Geometry = Geometry::new(8, 10).unwrap();
+        assert!(geometry.square(7, 9).is_some());
+        assert_eq!(geometry.square(8, 0), None);
+        assert_eq!(geometry.square(0, 10), None);
+
+        let corner: Square = geometry.square(7, 0).unwrap();
+        assert_eq!(geometry.offset(corner, -2, 1), geometry.square(5, 1));
+        assert_eq!(geometry.offset(corner, 1, 0), None);
+        assert_eq!(geometry.offset(corner, 0, -1), None);
+        assert_eq!(geometry.offset(corner, -7, 9), geometry.square(0, 9));
+    }
+
+    #[test]
+    fn squares_are_indexed_row_by_row() {
+        let geometry: Geometry = Geometry::new(10, 10).unwrap();
+
+        assert_eq!(geometry.size(), 100);
+        assert_eq!(geometry.index(geometry.square(1, 0).unwrap()), 10);
+        let indices: Vec<usize> = geometry.squares().map(|square| geometry.index(square)).collect();
+        assert_eq!(indices, (0..geometry.size()).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn squares_are_named_by_column_letter_and_rank() {
+        let geometry: Geometry = Geometry::new(10, 10).unwrap();
+
+        assert_eq!(geometry.parse_square("a1"), geometry.square(9, 0));
+        assert_eq!(geometry.parse_square("J10"), geometry.square(0, 9));
+        assert_eq!(geometry.square_name(geometry.square(0, 9).unwrap()), "j10");
+        for square in geometry.squares() {
+            assert_eq!(geometry.parse_square(&geometry.square_name(square)), Some(square));
+        }
+    }
+
+    #[test]
+    fn names_of_squares_off_the_board_are_rejected() {
+        let geometry: Geometry = Geometry::new(8, 10).unwrap();
+
+        for name in ["", "a", "a0", "a9", "k1", "1a", "a-1", "é1", "aé"] {
+            assert_eq!(geometry.parse_square(name), None, "{}", name);
+        }
+    }
+}
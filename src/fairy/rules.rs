@@ -0,0 +1,218 @@
+use super::betza::{parse_betza, Movement};
+use super::geometry::Geometry;
+use super::FairyError;
+
+/// The kind of a piece, which is identified by the uppercase letter it is written with in a FEN.
+#[derive(Debug, Clone)]
+pub struct PieceDefinition {
+    pub name: String,
+    pub letter: char,
+    pub movements: Vec<Movement>,
+
+    /// Whether the piece is the king of its team, which must not be left attacked.
+    pub royal: bool,
+
+    /// Whether the piece moves like a pawn, which allows it to be promoted and to capture en passant.
+    pub pawn: bool,
+}
+
+impl PieceDefinition {
+    /// Creates a piece which moves as described by the Betza notation.
+    pub fn new(name: &str, letter: char, betza: &str) -> Result<Self, FairyError> {
+        Ok(Self {
+            name: name.to_string(),
+            letter: letter.to_ascii_uppercase(),
+            movements: parse_betza(betza)?,
+            royal: false,
+            pawn: false,
+        })
+    }
+
+    pub fn king() -> Self {
+        Self { royal: true, ..Self::new("King", 'K', "K").unwrap() }
+    }
+
+    pub fn queen() -> Self {
+        Self::new("Queen", 'Q', "Q").unwrap()
+    }
+
+    pub fn rook() -> Self {
+        Self::new("Rook", 'R', "R").unwrap()
+    }
+
+    pub fn bishop() -> Self {
+        Self::new("Bishop", 'B', "B").unwrap()
+    }
+
+    pub fn knight() -> Self {
+        Self::new("Knight", 'N', "N").unwrap()
+    }
+
+    pub fn pawn() -> Self {
+        Self { pawn: true, ..Self::new("Pawn", 'P', "fmWfcFifmnD").unwrap() }
+    }
+
+    /// The archbishop, which moves like a bishop or a knight.
+    pub fn archbishop() -> Self {
+        Self::new("Archbishop", 'A', "BN").unwrap()
+    }
+
+    /// The chancellor, which moves like a rook or a knight.
+    pub fn chancellor() -> Self {
+        Self::new("Chancellor", 'C', "RN").unwrap()
+    }
+
+    /// The amazon, which moves like a queen or a knight.
+    pub fn amazon() -> Self {
+        Self::new("Amazon", 'M', "QN").unwrap()
+    }
+}
+
+/// The rules of a game played on a [`super::FairyBoard`]: the size of the board, the pieces, the starting position, and
+/// how pawns are promoted.
+#[derive(Debug, Clone)]
+pub struct FairyRules {
+    pub name: String,
+    pub geometry: Geometry,
+    pub pieces: Vec<PieceDefinition>,
+    pub starting_fen: String,
+
+    /// The letters of the pieces that pawns can be promoted to.
+    pub promotion_pieces: Vec<char>,
+
+    /// The number of rows at the far end of the board on which pawns can be promoted. Promotion is only compulsory on
+    /// the last row.
+    pub promotion_rows: usize,
+
+    /// Whether pawns can only be promoted to pieces that their team has lost, so that a team never has more pieces of a
+    /// kind than it started with.
+    pub promote_to_captured_only: bool,
+
+    /// Whether the king can castle with the rooks in the corners of its row, moving to the third column from its side
+    /// of the board while the rook moves to the column next to it.
+    pub castling: bool,
+}
+
+impl FairyRules {
+    /// Capablanca chess, played on a board of 8 rows and 10 columns with an archbishop and a chancellor added to the
+    /// pieces of standard chess.
+    pub fn capablanca() -> Self {
+        Self {
+            name: "Capablanca".to_string(),
+            geometry: Geometry::new(8, 10).unwrap(),
+            pieces: vec![
+                PieceDefinition::king(),
+                PieceDefinition::queen(),
+                PieceDefinition::rook(),
+                PieceDefinition::bishop(),
+                PieceDefinition::knight(),
+                PieceDefinition::pawn(),
+                PieceDefinition::archbishop(),
+                PieceDefinition::chancellor(),
+            ],
+            starting_fen: "rnabqkbcnr/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1".to_string(),
+            promotion_pieces: vec!['Q', 'C', 'A', 'R', 'B', 'N'],
+            promotion_rows: 1,
+            promote_to_captured_only: false,
+            castling: true,
+        }
+    }
+
+    /// Grand chess, played on a board of 10 rows and 10 columns with an archbishop and a chancellor added to the pieces
+    /// of standard chess. Pawns start on the third row and can be promoted on the last three rows, but only to pieces
+    /// that their team has lost. There is no castling.
+    pub fn grand() -> Self {
+        Self {
+            name: "Grand".to_string(),
+            geometry: Geometry::new(10, 10).unwrap(),
+            starting_fen: "r8r/1nbqkcabn1/pppppppppp/10/10/10/10/PPPPPPPPPP/1NBQKCABN1/R8R w - - 0 1".to_string(),
+            promotion_rows: 3,
+            promote_to_captured_only: true,
+            castling: false,
+            ..Self::capablanca()
+        }
+    }
+
+    /// Gets the definition of the piece written with the letter, in either case.
+    pub fn piece(&self, letter: char) -> Option<&PieceDefinition> {
+        let letter: char = letter.to_ascii_uppercase();
+        self.pieces.iter().find(|piece| piece.letter == letter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pieces_are_written_with_uppercase_letters() {
+        let piece: PieceDefinition = PieceDefinition::new("Camel", 'l', "C").unwrap();
+
+        assert_eq!(piece.letter, 'L');
+        assert_eq!(piece.movements.len(), 8);
+        assert!(!piece.royal && !piece.pawn);
+        assert!(matches!(PieceDefinition::new("Nothing", 'X', "fX"), Err(FairyError::InvalidBetza(_))));
+    }
+
+    #[test]
+    fn only_kings_are_royal_and_only_pawns_are_pawns() {
+        let rules: FairyRules = FairyRules::capablanca();
+
+        for piece in rules.pieces.iter() {
+            assert_eq!(piece.royal, piece.letter == 'K', "{}", piece.name);
+            assert_eq!(piece.pawn, piece.letter == 'P', "{}", piece.name);
+        }
+    }
+
+    #[test]
+    fn pieces_are_found_by_their_letter_in_either_case() {
+        let rules: FairyRules = FairyRules::grand();
+
+        assert_eq!(rules.piece('c').map(|piece| piece.name.as_str()), Some("Chancellor"));
+        assert_eq!(rules.piece('A').map(|piece| piece.name.as_str()), Some("Archbishop"));
+        assert!(rules.piece('M').is_none());
+    }
+
+    #[test]
+    fn starting_positions_fill_the_board() {
+        for rules in [FairyRules::capablanca(), FairyRules::grand()] {
+            let placement: &str = rules.starting_fen.split_whitespace().next().unwrap();
+            let rows: Vec<&str> = placement.split('/').collect();
+            assert_eq!(rows.len(), rules.geometry.rows(), "{}", rules.name);
+
+            for row in rows {
+                let mut columns: usize = 0;
+                let mut digits: String = String::new();
+                for character in row.chars().chain(std::iter::once('/')) {
+                    if character.is_ascii_digit() {
+                        digits.push(character);
+                        continue;
+                    }
+                    columns += digits.parse::<usize>().unwrap_or(0);
+                    digits.clear();
+                    if character != '/' {
+                        assert!(rules.piece(character).is_some(), "{}", character);
+                        columns += 1;
+                    }
+                }
+                assert_eq!(columns, rules.geometry.columns(), "{}", row);
+            }
+            for letter in rules.promotion_pieces.iter() {
+                assert!(rules.piece(*letter).is_some_and(|piece| !piece.royal && !piece.pawn));
+            }
+        }
+    }
+
+    #[test]
+    fn grand_chess_changes_the_board_and_the_promotions_of_capablanca_chess() {
+        let capablanca: FairyRules = FairyRules::capablanca();
+        let grand: FairyRules = FairyRules::grand();
+
+        assert_eq!((capablanca.geometry.rows(), capablanca.geometry.columns()), (8, 10));
+        assert_eq!((grand.geometry.rows(), grand.geometry.columns()), (10, 10));
+        assert_eq!((capablanca.promotion_rows, grand.promotion_rows), (1, 3));
+        assert!(!capablanca.promote_to_captured_only && grand.promote_to_captured_only);
+        assert!(capablanca.castling && !grand.castling);
+        assert_eq!(capablanca.pieces.len(), grand.pieces.len());
+    }
+}
@@ -0,0 +1,805 @@
+//! Rooms where two players play a game against each other while spectators watch, enabled by the `server` feature.
+//!
+//! The hub only decides what happens in the rooms: it takes the messages of clients, checks every move against the
+//! board of the room, and returns the messages to send to each client. Carrying the messages is left to the transport,
+//! which is WebSockets in the `game_hub` binary, so rooms can also be driven directly, such as from tests.
+
+use crate::board::Board;
+use crate::clock::{Clock, Outcome, SystemTimeSource, TimeControl, TimeSource};
+use crate::moves::Move;
+use crate::pgn::PgnGame;
+use crate::piece::Team;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The most time that a room can give each player, and the largest increment it can add after a move.
+const MAX_CLOCK_TIME: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Identifies a connection of a client, as numbered by the transport.
+pub type ConnectionId = u64;
+
+/// The messages that clients send to the hub.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join(JoinRequest),
+
+    /// Makes a move written in SAN or UCI.
+    Move {
+        #[serde(rename = "move")]
+        chess_move: String,
+    },
+
+    Resign,
+    OfferDraw,
+    AcceptDraw,
+    DeclineDraw,
+
+    /// Asks the opponent to take back the last move of the team asking.
+    RequestTakeback,
+    AcceptTakeback,
+    DeclineTakeback,
+}
+
+/// Joins the room with the name, creating it with the time control when it does not exist yet. Clients take the free
+/// seat (or the team they ask for), join as spectators when both seats are taken, and take back their seat after
+/// reconnecting by sending the token they were given when they first joined.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct JoinRequest {
+    pub room: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub team: Option<Team>,
+    #[serde(default)]
+    pub spectate: bool,
+
+    /// The time on the clock of each player, without a clock when left out.
+    #[serde(default)]
+    pub minutes: Option<f64>,
+
+    /// The time in seconds added to the clock of a player after each of their moves.
+    #[serde(default)]
+    pub increment: Option<f64>,
+}
+
+/// The messages that the hub sends to clients.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Sent to a client which joined a room. Players are given the token which lets them take their seat back after
+    /// reconnecting.
+    Joined {
+        seat: Seat,
+        token: Option<String>,
+        state: RoomState,
+    },
+
+    /// Sent to everyone in a room when a player joins or leaves it.
+    State { state: RoomState },
+
+    /// Sent to everyone in a room when a move is made.
+    Move { san: String, uci: String, state: RoomState },
+
+    /// Sent to everyone in a room with a clock every time the hub ticks.
+    Clock { clock: ClockState },
+
+    DrawOffered { by: Team },
+    DrawDeclined { by: Team },
+    TakebackRequested { by: Team },
+    TakebackDeclined { by: Team },
+
+    /// Sent to everyone in a room when moves are taken back.
+    TakenBack { state: RoomState },
+
+    GameOver { result: String, reason: String, state: RoomState },
+
+    /// Sent to a client whose message could not be carried out.
+    Error { message: String },
+}
+
+/// Where a client sits in a room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Seat {
+    Player(Team),
+    Spectator,
+}
+
+/// Everything that clients are shown about a room.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RoomState {
+    pub room: String,
+    pub fen: String,
+
+    /// The moves made so far in standard algebraic notation.
+    pub moves: Vec<String>,
+    pub turn: Team,
+    pub white: Option<PlayerState>,
+    pub black: Option<PlayerState>,
+    pub spectators: usize,
+    pub clock: Option<ClockState>,
+    pub draw_offer: Option<Team>,
+    pub takeback_request: Option<Team>,
+
+    /// The result in PGN notation once the game is over.
+    pub result: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlayerState {
+    pub name: String,
+    pub connected: bool,
+}
+
+/// The time left for each team in milliseconds, and the team whose time is counting down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ClockState {
+    pub white: u64,
+    pub black: u64,
+    pub running: Option<Team>,
+}
+
+/// A message along with the connection to send it to.
+pub type Outgoing = (ConnectionId, ServerMessage);
+
+struct Player {
+    name: String,
+    token: String,
+    connection: Option<ConnectionId>,
+}
+
+struct Room {
+    name: String,
+    board: Board,
+    clock: Option<Clock>,
+    players: HashMap<Team, Player>,
+    spectators: Vec<ConnectionId>,
+    draw_offer: Option<Team>,
+    takeback_request: Option<Team>,
+
+    /// The result of the game in PGN notation and the reason that the game ended.
+    result: Option<(String, String)>,
+}
+
+/// The rooms of the hub and the clients in them.
+pub struct Hub {
+    rooms: HashMap<String, Room>,
+    seats: HashMap<ConnectionId, (String, Seat)>,
+    time_source: Arc<dyn TimeSource>,
+    random_state: RandomState,
+    tokens_issued: u64,
+}
+
+impl Default for Hub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hub {
+    /// Creates a hub whose clocks measure time with the monotonic clock of the system.
+    pub fn new() -> Self {
+        Self::with_time_source(Arc::new(SystemTimeSource::new()))
+    }
+
+    /// Creates a hub whose clocks measure time with the time source, which lets tests drive the clocks.
+    pub fn with_time_source(time_source: Arc<dyn TimeSource>) -> Self {
+        Self {
+            rooms: HashMap::new(),
+            seats: HashMap::new(),
+            time_source,
+            random_state: RandomState::new(),
+            tokens_issued: 0,
+        }
+    }
+
+    /// Handles a message from the client on the connection.
+    pub fn handle(&mut self, connection: ConnectionId, message: ClientMessage) -> Vec<Outgoing> {
+        let result: Result<Vec<Outgoing>, String> = match message {
+            ClientMessage::Join(request) => self.join(connection, request),
+            message => match self.seats.get(&connection) {
+                Some((room, Seat::Player(team))) => {
+                    let (room, team) = (room.clone(), *team);
+                    self.rooms.get_mut(&room).unwrap().handle(team, message)
+                }
+                Some((_, Seat::Spectator)) => Err("Spectators can not play.".to_string()),
+                None => Err("Join a room first.".to_string()),
+            },
+        };
+        result.unwrap_or_else(|message| vec![(connection, ServerMessage::Error { message })])
+    }
+
+    /// Handles the connection closing. Players keep their seats so that they can reconnect, and a room is closed once
+    /// everyone has left it, unless both players have joined and their game is still being played.
+    pub fn disconnect(&mut self, connection: ConnectionId) -> Vec<Outgoing> {
+        let (room_name, seat) = match self.seats.remove(&connection) {
+            Some(seat) => seat,
+            None => return Vec::new(),
+        };
+        let room: &mut Room = self.rooms.get_mut(&room_name).unwrap();
+        match seat {
+            Seat::Player(team) => room.players.get_mut(&team).unwrap().connection = None,
+            Seat::Spectator => room.spectators.retain(|spectator| *spectator != connection),
+        }
+
+        if room.connections().is_empty() && (room.players.len() < 2 || room.result.is_some()) {
+            self.rooms.remove(&room_name);
+            return Vec::new();
+        }
+        room.broadcast(ServerMessage::State { state: room.state() })
+    }
+
+    /// Checks the clocks of every room, ending the games of players whose time ran out, and sends the time left to
+    /// everyone in the rooms. The transport calls this regularly, such as every second.
+    pub fn tick(&mut self) -> Vec<Outgoing> {
+        let mut outgoing: Vec<Outgoing> = Vec::new();
+        for room in self.rooms.values_mut() {
+            if room.result.is_some() {
+                continue;
+            }
+            outgoing.extend(room.check_outcome());
+            if let Some(clock) = room.clock_state() {
+                outgoing.extend(room.broadcast(ServerMessage::Clock { clock }));
+            }
+        }
+        outgoing
+    }
+
+    fn join(&mut self, connection: ConnectionId, request: JoinRequest) -> Result<Vec<Outgoing>, String> {
+        if self.seats.contains_key(&connection) {
+            return Err("This connection is already in a room.".to_string());
+        }
+        let JoinRequest { room: room_name, name, token, team, spectate, minutes, increment } = request;
+        let time_control: Option<TimeControl> = match minutes {
+            Some(minutes) => Some(
+                fischer_time_control(minutes, increment.unwrap_or(0.0))
+                    .ok_or_else(|| "The time control is not valid.".to_string())?,
+            ),
+            None => None,
+        };
+        let new_token: String = self.new_token();
+        let time_source: Arc<dyn TimeSource> = self.time_source.clone();
+        let room: &mut Room = self.rooms.entry(room_name.clone()).or_insert_with(|| Room {
+            name: room_name.clone(),
+            board: Board::new(),
+            clock: time_control.map(|time_control| Clock::new(time_control, time_source)),
+            players: HashMap::new(),
+            spectators: Vec::new(),
+            draw_offer: None,
+            takeback_request: None,
+            result: None,
+        });
+
+        // A player who reconnects takes back their seat, and a connection that was left open is replaced.
+        let returning: Option<Team> = token.as_ref().and_then(|token| {
+            room.players
+                .iter()
+                .find(|(_, player)| player.token == *token)
+                .map(|(team, _)| *team)
+        });
+        let free_team: Option<Team> = match team {
+            Some(team) => Some(team).filter(|team| !room.players.contains_key(team)),
+            None => [Team::White, Team::Black].into_iter().find(|team| !room.players.contains_key(team)),
+        };
+
+        let (seat, token): (Seat, Option<String>) = match (returning, free_team) {
+            (Some(team), _) => {
+                let player: &mut Player = room.players.get_mut(&team).unwrap();
+                if let Some(previous) = player.connection.replace(connection) {
+                    self.seats.remove(&previous);
+                }
+                (Seat::Player(team), Some(player.token.clone()))
+            }
+            (None, Some(team)) if !spectate && room.result.is_none() => {
+                let name: String = name.unwrap_or_else(|| format!("Player {}", room.players.len() + 1));
+                room.players.insert(team, Player { name, token: new_token.clone(), connection: Some(connection) });
+                if room.players.len() == 2 {
+                    if let Some(clock) = room.clock.as_mut() {
+                        clock.start(room.board.turn_to_play()).ok();
+                    }
+                }
+                (Seat::Player(team), Some(new_token))
+            }
+            (None, _) if token.is_some() && !spectate => {
+                return Err("The token does not belong to a player of the room.".to_string());
+            }
+            (None, _) => {
+                room.spectators.push(connection);
+                (Seat::Spectator, None)
+            }
+        };
+        self.seats.insert(connection, (room_name, seat));
+
+        let state: RoomState = room.state();
+        let mut outgoing: Vec<Outgoing> = room
+            .connections()
+            .into_iter()
+            .filter(|other| *other != connection)
+            .map(|other| (other, ServerMessage::State { state: state.clone() }))
+            .collect();
+        outgoing.push((connection, ServerMessage::Joined { seat, token, state }));
+        Ok(outgoing)
+    }
+
+    /// Creates a token which is hard to guess, for players to take back their seat with.
+    fn new_token(&mut self) -> String {
+        self.tokens_issued += 1;
+        let mut token: String = String::new();
+        for part in 0..2u64 {
+            let mut hasher = self.random_state.build_hasher();
+            hasher.write_u64(self.tokens_issued);
+            hasher.write_u64(part);
+            token.push_str(&format!("{:016x}", hasher.finish()));
+        }
+        token
+    }
+}
+
+impl Room {
+    fn handle(&mut self, team: Team, message: ClientMessage) -> Result<Vec<Outgoing>, String> {
+        if self.result.is_some() {
+            return Err("The game is over.".to_string());
+        }
+        if self.players.len() < 2 {
+            return Err("The game starts when both players have joined.".to_string());
+        }
+
+        match message {
+            ClientMessage::Move { chess_move } => self.make_move(team, &chess_move),
+            ClientMessage::Resign => Ok(self.end(winning_result(team.opponent()), "resignation")),
+            ClientMessage::OfferDraw => match self.draw_offer {
+                Some(offering) if offering != team => Ok(self.end("1/2-1/2", "agreement")),
+                Some(_) => Err("The draw has already been offered.".to_string()),
+                None => {
+                    self.draw_offer = Some(team);
+                    Ok(self.broadcast(ServerMessage::DrawOffered { by: team }))
+                }
+            },
+            ClientMessage::AcceptDraw => match self.draw_offer {
+                Some(offering) if offering != team => Ok(self.end("1/2-1/2", "agreement")),
+                _ => Err("There is no draw offer to accept.".to_string()),
+            },
+            ClientMessage::DeclineDraw => match self.draw_offer {
+                Some(offering) if offering != team => {
+                    self.draw_offer = None;
+                    Ok(self.broadcast(ServerMessage::DrawDeclined { by: team }))
+                }
+                _ => Err("There is no draw offer to decline.".to_string()),
+            },
+            ClientMessage::RequestTakeback => {
+                if self.moves_to_take_back(team) == 0 {
+                    return Err("There is no move to take back.".to_string());
+                }
+                self.takeback_request = Some(team);
+                Ok(self.broadcast(ServerMessage::TakebackRequested { by: team }))
+            }
+            ClientMessage::AcceptTakeback => match self.takeback_request {
+                Some(requesting) if requesting != team => Ok(self.take_back(requesting)),
+                _ => Err("There is no takeback request to accept.".to_string()),
+            },
+            ClientMessage::DeclineTakeback => match self.takeback_request {
+                Some(requesting) if requesting != team => {
+                    self.takeback_request = None;
+                    Ok(self.broadcast(ServerMessage::TakebackDeclined { by: team }))
+                }
+                _ => Err("There is no takeback request to decline.".to_string()),
+            },
+            ClientMessage::Join { .. } => Err("This connection is already in a room.".to_string()),
+        }
+    }
+
+    fn make_move(&mut self, team: Team, text: &str) -> Result<Vec<Outgoing>, String> {
+        if self.board.turn_to_play() != team {
+            return Err("It is not your turn.".to_string());
+        }
+        // A move made after the time ran out does not count.
+        let outgoing: Vec<Outgoing> = self.check_outcome();
+        if !outgoing.is_empty() {
+            return Ok(outgoing);
+        }
+
+        let chess_move: Move = self
+            .board
+            .parse_san(text)
+            .or_else(|error| self.board.parse_uci(text).map_err(|_| error))
+            .map_err(|error| error.to_string())?;
+        let (san, uci) = (self.board.san(&chess_move), self.board.uci(&chess_move));
+        self.board.make_move(&chess_move).map_err(|error| error.to_string())?;
+        if let Some(clock) = self.clock.as_mut() {
+            clock.press().ok();
+        }
+
+        // Moving declines a draw offered by the opponent, and ends any takeback request.
+        self.draw_offer = self.draw_offer.filter(|offering| *offering == team);
+        self.takeback_request = None;
+
+        let mut outgoing: Vec<Outgoing> = self.broadcast(ServerMessage::Move { san, uci, state: self.state() });
+        outgoing.extend(self.check_outcome());
+        Ok(outgoing)
+    }
+
+    /// The number of moves to take back so that the team is to play again before its last move.
+    fn moves_to_take_back(&self, team: Team) -> usize {
+        let moves: usize = self.board.history().len();
+        match self.board.turn_to_play() == team {
+            true if moves >= 2 => 2,
+            false if moves >= 1 => 1,
+            _ => 0,
+        }
+    }
+
+    fn take_back(&mut self, requesting: Team) -> Vec<Outgoing> {
+        for _ in 0..self.moves_to_take_back(requesting) {
+            self.board.undo_move();
+        }
+        self.takeback_request = None;
+        self.draw_offer = None;
+        if let Some(clock) = self.clock.as_mut() {
+            clock.start(self.board.turn_to_play()).ok();
+        }
+        self.broadcast(ServerMessage::TakenBack { state: self.state() })
+    }
+
+    /// Ends the game when it is over on the board or on the clock.
+    fn check_outcome(&mut self) -> Vec<Outgoing> {
+        let outcome: Option<Outcome> = match self.clock.as_mut() {
+            Some(clock) => clock.outcome(&self.board),
+            None => match self.board.winner() {
+                Some(team) => Some(Outcome::Win(team)),
+                None => self.board.is_draw().then_some(Outcome::Draw),
+            },
+        };
+        match outcome {
+            Some(Outcome::Win(team)) if self.board.is_checkmate() => self.end(winning_result(team), "checkmate"),
+            Some(Outcome::Win(team)) => self.end(winning_result(team), "variant"),
+            Some(Outcome::WinOnTime(team)) => self.end(winning_result(team), "timeout"),
+            Some(Outcome::Draw) if self.board.is_stalemate() => self.end("1/2-1/2", "stalemate"),
+            Some(Outcome::Draw) => self.end("1/2-1/2", "draw"),
+            Some(Outcome::DrawOnTime) => self.end("1/2-1/2", "timeout"),
+            None => Vec::new(),
+        }
+    }
+
+    fn end(&mut self, result: &str, reason: &str) -> Vec<Outgoing> {
+        self.result = Some((result.to_string(), reason.to_string()));
+        self.draw_offer = None;
+        self.takeback_request = None;
+        if let Some(clock) = self.clock.as_mut() {
+            clock.pause();
+        }
+        self.broadcast(ServerMessage::GameOver {
+            result: result.to_string(),
+            reason: reason.to_string(),
+            state: self.state(),
+        })
+    }
+
+    fn connections(&self) -> Vec<ConnectionId> {
+        self.players
+            .values()
+            .filter_map(|player| player.connection)
+            .chain(self.spectators.iter().copied())
+            .collect()
+    }
+
+    fn broadcast(&self, message: ServerMessage) -> Vec<Outgoing> {
+        self.connections()
+            .into_iter()
+            .map(|connection| (connection, message.clone()))
+            .collect()
+    }
+
+    fn clock_state(&self) -> Option<ClockState> {
+        self.clock.as_ref().map(|clock| ClockState {
+            white: clock.remaining(Team::White).as_millis() as u64,
+            black: clock.remaining(Team::Black).as_millis() as u64,
+            running: clock.running_team().filter(|_| !clock.is_paused()),
+        })
+    }
+
+    fn state(&self) -> RoomState {
+        let player_state = |team: Team| {
+            self.players.get(&team).map(|player| PlayerState {
+                name: player.name.clone(),
+                connected: player.connection.is_some(),
+            })
+        };
+        RoomState {
+            room: self.name.clone(),
+            fen: self.board.fen().state,
+            moves: PgnGame::from_board(&self.board, "*").moves,
+            turn: self.board.turn_to_play(),
+            white: player_state(Team::White),
+            black: player_state(Team::Black),
+            spectators: self.spectators.len(),
+            clock: self.clock_state(),
+            draw_offer: self.draw_offer,
+            takeback_request: self.takeback_request,
+            result: self.result.as_ref().map(|(result, _)| result.clone()),
+            reason: self.result.as_ref().map(|(_, reason)| reason.clone()),
+        }
+    }
+}
+
+/// Creates the time control that a client asked for with the minutes on the clock and the increment in seconds. Returns
+/// `None` unless there is some time on the clock and neither the time nor the increment is more than
+/// [`MAX_CLOCK_TIME`].
+fn fischer_time_control(minutes: f64, increment: f64) -> Option<TimeControl> {
+    let time: Duration = Duration::try_from_secs_f64(minutes * 60.0).ok()?;
+    let increment: Duration = Duration::try_from_secs_f64(increment).ok()?;
+    if time.is_zero() || time > MAX_CLOCK_TIME || increment > MAX_CLOCK_TIME {
+        return None;
+    }
+    Some(TimeControl::fischer(time, increment))
+}
+
+fn winning_result(team: Team) -> &'static str {
+    match team {
+        Team::White => "1-0",
+        Team::Black => "0-1",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualTimeSource;
+
+    fn join(room: &str) -> ClientMessage {
+        ClientMessage::Join(JoinRequest { room: room.to_string(), ..Default::default() })
+    }
+
+    fn join_with_clock(room: &str, minutes: f64, increment: f64) -> ClientMessage {
+        ClientMessage::Join(JoinRequest {
+            room: room.to_string(),
+            minutes: Some(minutes),
+            increment: Some(increment),
+            ..Default::default()
+        })
+    }
+
+    fn play(chess_move: &str) -> ClientMessage {
+        ClientMessage::Move { chess_move: chess_move.to_string() }
+    }
+
+    /// Gets the messages sent to the connection.
+    fn sent_to(outgoing: &[Outgoing], connection: ConnectionId) -> Vec<ServerMessage> {
+        outgoing
+            .iter()
+            .filter(|(to, _)| *to == connection)
+            .map(|(_, message)| message.clone())
+            .collect()
+    }
+
+    /// Gets the seat and the token that the connection was given when it joined.
+    fn joined(outgoing: &[Outgoing], connection: ConnectionId) -> (Seat, Option<String>) {
+        match sent_to(outgoing, connection).as_slice() {
+            [ServerMessage::Joined { seat, token, .. }] => (*seat, token.clone()),
+            messages => panic!("expected a joined message, got {:?}", messages),
+        }
+    }
+
+    fn is_error(outgoing: &[Outgoing], connection: ConnectionId) -> bool {
+        matches!(sent_to(outgoing, connection).as_slice(), [ServerMessage::Error { .. }])
+    }
+
+    /// Creates a hub with a room where the connections 1 and 2 play white and black.
+    fn hub_with_game(time_control: Option<(f64, f64)>) -> (Hub, ManualTimeSource) {
+        let time_source: ManualTimeSource = ManualTimeSource::new();
+        let mut hub: Hub = Hub::with_time_source(Arc::new(time_source.clone()));
+        for connection in [1, 2] {
+            let message: ClientMessage = match time_control {
+                Some((minutes, increment)) => join_with_clock("room", minutes, increment),
+                None => join("room"),
+            };
+            hub.handle(connection, message);
+        }
+        (hub, time_source)
+    }
+
+    fn game_over(outgoing: &[Outgoing], connection: ConnectionId) -> Option<(String, String)> {
+        sent_to(outgoing, connection).into_iter().find_map(|message| match message {
+            ServerMessage::GameOver { result, reason, .. } => Some((result, reason)),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn invalid_time_controls_are_rejected() {
+        let mut hub: Hub = Hub::new();
+        for (minutes, increment) in [
+            (1e20, 0.0),
+            (f64::NAN, 0.0),
+            (f64::INFINITY, 0.0),
+            (-1.0, 0.0),
+            (0.0, 0.0),
+            (5.0, 1e20),
+            (5.0, -2.0),
+        ] {
+            let outgoing: Vec<Outgoing> = hub.handle(1, join_with_clock("room", minutes, increment));
+            assert!(is_error(&outgoing, 1), "{} minutes and {} seconds", minutes, increment);
+        }
+
+        let outgoing: Vec<Outgoing> = hub.handle(1, join_with_clock("room", 5.0, 3.0));
+        assert_eq!(joined(&outgoing, 1).0, Seat::Player(Team::White));
+    }
+
+    #[test]
+    fn players_are_paired_and_spectators_watch() {
+        let mut hub: Hub = Hub::new();
+        assert_eq!(joined(&hub.handle(1, join("room")), 1).0, Seat::Player(Team::White));
+        assert!(is_error(&hub.handle(1, play("e4")), 1));
+
+        let outgoing: Vec<Outgoing> = hub.handle(2, join("room"));
+        assert_eq!(joined(&outgoing, 2).0, Seat::Player(Team::Black));
+        assert!(matches!(sent_to(&outgoing, 1).as_slice(), [ServerMessage::State { .. }]));
+
+        let outgoing: Vec<Outgoing> = hub.handle(3, join("room"));
+        assert_eq!(joined(&outgoing, 3), (Seat::Spectator, None));
+        assert!(is_error(&hub.handle(3, play("e4")), 3));
+        assert!(is_error(&hub.handle(2, play("e5")), 2));
+
+        let outgoing: Vec<Outgoing> = hub.handle(1, play("e4"));
+        for connection in [1, 2, 3] {
+            match sent_to(&outgoing, connection).as_slice() {
+                [ServerMessage::Move { san, uci, state }] => {
+                    assert_eq!((san.as_str(), uci.as_str()), ("e4", "e2e4"));
+                    assert_eq!(state.turn, Team::Black);
+                    assert_eq!(state.spectators, 1);
+                }
+                messages => panic!("expected a move, got {:?}", messages),
+            }
+        }
+        assert!(is_error(&hub.handle(2, play("e4")), 2));
+    }
+
+    #[test]
+    fn malformed_moves_are_rejected() {
+        let (mut hub, _) = hub_with_game(None);
+        for chess_move in ["é", "N@é", "é@e4", "e2é", "ée2e4", "e2€4", "", "@", "O-O-O-O", "e2e4e5", "Ke9", "xyz"] {
+            assert!(is_error(&hub.handle(1, play(chess_move)), 1), "{:?}", chess_move);
+        }
+
+        let outgoing: Vec<Outgoing> = hub.handle(1, play("e4"));
+        assert!(matches!(sent_to(&outgoing, 2).as_slice(), [ServerMessage::Move { .. }]));
+    }
+
+    #[test]
+    fn players_reconnect_with_their_token() {
+        let mut hub: Hub = Hub::new();
+        let (_, token) = joined(&hub.handle(1, join("room")), 1);
+        hub.handle(2, join("room"));
+
+        let outgoing: Vec<Outgoing> = hub.handle(1, play("e4"));
+        assert!(!is_error(&outgoing, 1));
+        let outgoing: Vec<Outgoing> = hub.disconnect(1);
+        match sent_to(&outgoing, 2).as_slice() {
+            [ServerMessage::State { state }] => assert!(!state.white.as_ref().unwrap().connected),
+            messages => panic!("expected a state, got {:?}", messages),
+        }
+
+        let wrong_token = ClientMessage::Join(JoinRequest {
+            room: "room".to_string(),
+            token: Some("not a token".to_string()),
+            ..Default::default()
+        });
+        assert!(is_error(&hub.handle(3, wrong_token), 3));
+
+        let returning = ClientMessage::Join(JoinRequest { room: "room".to_string(), token, ..Default::default() });
+        let outgoing: Vec<Outgoing> = hub.handle(4, returning);
+        assert_eq!(joined(&outgoing, 4).0, Seat::Player(Team::White));
+        match sent_to(&outgoing, 4).as_slice() {
+            [ServerMessage::Joined { state, .. }] => {
+                assert_eq!(state.moves, vec!["e4".to_string()]);
+                assert!(state.white.as_ref().unwrap().connected);
+            }
+            messages => panic!("expected a joined message, got {:?}", messages),
+        }
+        assert!(!is_error(&hub.handle(2, play("e5")), 2));
+    }
+
+    #[test]
+    fn rooms_are_closed_once_everyone_leaves_before_the_game_starts() {
+        let mut hub: Hub = Hub::new();
+        let (_, token) = joined(&hub.handle(1, join("room")), 1);
+        hub.disconnect(1);
+
+        // The room is created again, so the old token no longer belongs to anyone and a new seat is given out.
+        let returning = ClientMessage::Join(JoinRequest {
+            room: "room".to_string(),
+            token: token.clone(),
+            ..Default::default()
+        });
+        let (seat, new_token) = joined(&hub.handle(2, returning), 2);
+        assert_eq!(seat, Seat::Player(Team::White));
+        assert_ne!(new_token, token);
+    }
+
+    #[test]
+    fn draws_are_offered_declined_and_accepted() {
+        let (mut hub, _) = hub_with_game(None);
+
+        let outgoing: Vec<Outgoing> = hub.handle(1, ClientMessage::OfferDraw);
+        assert_eq!(sent_to(&outgoing, 2), vec![ServerMessage::DrawOffered { by: Team::White }]);
+        assert!(is_error(&hub.handle(1, ClientMessage::AcceptDraw), 1));
+
+        let outgoing: Vec<Outgoing> = hub.handle(2, ClientMessage::DeclineDraw);
+        assert_eq!(sent_to(&outgoing, 1), vec![ServerMessage::DrawDeclined { by: Team::Black }]);
+        assert!(is_error(&hub.handle(2, ClientMessage::AcceptDraw), 2));
+
+        hub.handle(1, ClientMessage::OfferDraw);
+        let outgoing: Vec<Outgoing> = hub.handle(2, ClientMessage::AcceptDraw);
+        assert_eq!(game_over(&outgoing, 1), Some(("1/2-1/2".to_string(), "agreement".to_string())));
+        assert!(is_error(&hub.handle(1, play("e4")), 1));
+    }
+
+    #[test]
+    fn takebacks_undo_the_moves_since_the_team_last_played() {
+        let (mut hub, _) = hub_with_game(None);
+        hub.handle(1, play("e4"));
+        hub.handle(2, play("e5"));
+
+        let outgoing: Vec<Outgoing> = hub.handle(1, ClientMessage::RequestTakeback);
+        assert_eq!(sent_to(&outgoing, 2), vec![ServerMessage::TakebackRequested { by: Team::White }]);
+        let outgoing: Vec<Outgoing> = hub.handle(2, ClientMessage::DeclineTakeback);
+        assert_eq!(sent_to(&outgoing, 1), vec![ServerMessage::TakebackDeclined { by: Team::Black }]);
+        assert!(is_error(&hub.handle(2, ClientMessage::AcceptTakeback), 2));
+
+        hub.handle(1, ClientMessage::RequestTakeback);
+        let outgoing: Vec<Outgoing> = hub.handle(2, ClientMessage::AcceptTakeback);
+        match sent_to(&outgoing, 1).as_slice() {
+            [ServerMessage::TakenBack { state }] => {
+                assert!(state.moves.is_empty());
+                assert_eq!(state.turn, Team::White);
+                assert_eq!(state.fen, Board::new().fen().state);
+            }
+            messages => panic!("expected a takeback, got {:?}", messages),
+        }
+        assert!(is_error(&hub.handle(2, ClientMessage::RequestTakeback), 2));
+    }
+
+    #[test]
+    fn checkmates_end_the_game() {
+        let (mut hub, _) = hub_with_game(None);
+        for (connection, chess_move) in [(1, "f3"), (2, "e5"), (1, "g4")] {
+            assert!(!is_error(&hub.handle(connection, play(chess_move)), connection));
+        }
+
+        let outgoing: Vec<Outgoing> = hub.handle(2, play("Qh4#"));
+        assert_eq!(game_over(&outgoing, 1), Some(("0-1".to_string(), "checkmate".to_string())));
+    }
+
+    #[test]
+    fn clocks_tick_and_flags_fall() {
+        let (mut hub, time_source) = hub_with_game(Some((1.0, 2.0)));
+
+        time_source.advance(Duration::from_secs(10));
+        let outgoing: Vec<Outgoing> = hub.tick();
+        let clock: ClockState = ClockState { white: 50_000, black: 60_000, running: Some(Team::White) };
+        assert_eq!(sent_to(&outgoing, 2), vec![ServerMessage::Clock { clock }]);
+
+        hub.handle(1, play("e4"));
+        time_source.advance(Duration::from_secs(61));
+        let outgoing: Vec<Outgoing> = hub.tick();
+        assert_eq!(game_over(&outgoing, 1), Some(("1-0".to_string(), "timeout".to_string())));
+        assert!(hub.tick().is_empty());
+    }
+
+    #[test]
+    fn moves_made_after_the_time_ran_out_do_not_count() {
+        let (mut hub, time_source) = hub_with_game(Some((1.0, 0.0)));
+
+        time_source.advance(Duration::from_secs(60));
+        let outgoing: Vec<Outgoing> = hub.handle(1, play("e4"));
+        assert_eq!(game_over(&outgoing, 2), Some(("0-1".to_string(), "timeout".to_string())));
+        assert!(!outgoing.iter().any(|(_, message)| matches!(message, ServerMessage::Move { .. })));
+    }
+}
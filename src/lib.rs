@@ -0,0 +1,22 @@
+pub mod bitboard;
+pub mod board;
+pub mod castling;
+pub mod clock;
+pub mod coordinate;
+pub mod dtm;
+pub mod epd;
+pub mod fairy;
+#[cfg(feature = "server")]
+pub mod hub;
+pub mod moves;
+pub mod pgn;
+pub mod piece;
+pub mod pocket;
+pub mod polyglot;
+pub mod render;
+pub mod search;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod svg;
+pub mod syzygy;
+pub mod variant;
@@ -1,34 +1,225 @@
-use crate::coordinate::Coordinate;
-use crate::board::Fen;
+use chess_engine_revive::board::{Board, BoardError, Fen};
+use chess_engine_revive::moves::Move;
+use chess_engine_revive::pgn::PgnGame;
+use chess_engine_revive::piece::Team;
+use chess_engine_revive::render::BoardRenderer;
+use chess_engine_revive::search::{search, Score, SearchLimits, SearchResult};
+use std::io::{BufRead, Write};
+use std::time::Duration;
 
-mod board;
-mod coordinate;
-mod piece;
+const USAGE: &str = "Usage: chess-engine-revive [options]
+
+Plays a game of chess in the terminal between two players, or between a player and the engine.
+
+Options:
+    --engine <white|black>  Team played by the engine
+    --time <milliseconds>   Time the engine thinks about each move for (default 1000)
+    --fen <fen>             Position to start the game from
+    --ascii                 Draw the pieces with letters instead of unicode glyphs
+    --color                 Draw the board with ANSI colors and highlight the last move";
+
+const COMMANDS: &str = "Moves are entered in standard algebraic notation (Nf3), UCI (g1f3), or as coordinates (g1-f3).
+
+Commands:
+    undo    Take back the last move (and the engine's reply)
+    flip    Turn the board around
+    fen     Print the FEN of the position
+    pgn     Print the game so far as PGN
+    resign  Resign the game
+    draw    Offer a draw
+    help    Print this help
+    quit    Leave without finishing the game";
 
 fn main() {
-    let mut board: board::Board = board::Board::new();
-    
-    let from: Coordinate = Coordinate::try_from("E2").unwrap();
-    let to: Coordinate = Coordinate::try_from("E4").unwrap();
-    println!("Response is: {:?}", board.move_piece(&from, &to));
-    
-    let from: Coordinate = Coordinate::try_from("E7").unwrap();
-    let to: Coordinate = Coordinate::try_from("E5").unwrap();
-    println!("Response is: {:?}", board.move_piece(&from, &to));
-    
-    let from: Coordinate = Coordinate::try_from("D2").unwrap();
-    let to: Coordinate = Coordinate::try_from("D4").unwrap();
-    println!("Response is: {:?}", board.move_piece(&from, &to));
-    
-    let from: Coordinate = Coordinate::try_from("D7").unwrap();
-    let to: Coordinate = Coordinate::try_from("D5").unwrap();
-    println!("Response is: {:?}", board.move_piece(&from, &to));
-    
-    let from: Coordinate = Coordinate::try_from("C1").unwrap();
-    let to: Coordinate = Coordinate::try_from("E3").unwrap();
-    println!("Response is: {:?}", board.move_piece(&from, &to));
-    
-    println!("{}", board);
-    let fen: Fen = board.fen();
-    println!("{:?}", fen);
-}
\ No newline at end of file
+    let mut engine_team: Option<Team> = None;
+    let mut time: u64 = 1000;
+    let mut board: Board = Board::new();
+    let mut renderer: BoardRenderer = BoardRenderer::new();
+    let mut colors: bool = false;
+
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--ascii" => {
+                renderer = renderer.with_ascii(true);
+                continue;
+            }
+            "--color" => {
+                colors = true;
+                renderer = renderer.with_colors(true);
+                continue;
+            }
+            _ => {}
+        }
+        let value: String = arguments.next().unwrap_or_else(|| exit_with_usage());
+        match argument.as_str() {
+            "--engine" => {
+                engine_team = match value.as_str() {
+                    "white" => Some(Team::White),
+                    "black" => Some(Team::Black),
+                    _ => exit_with_usage(),
+                }
+            }
+            "--time" => time = parse_or_exit(&value),
+            "--fen" => {
+                board = Board::try_new_with_fen(Fen { state: value }).unwrap_or_else(|error| {
+                    eprintln!("Invalid FEN: {}", error);
+                    std::process::exit(1);
+                })
+            }
+            _ => exit_with_usage(),
+        }
+    }
+    let limits: SearchLimits = SearchLimits::new().with_time(Duration::from_millis(time));
+
+    println!("{}\n", COMMANDS);
+    let mut flipped: bool = engine_team == Some(Team::White);
+    let mut lines = std::io::stdin().lock().lines();
+    let result: String = loop {
+        let mut board_renderer: BoardRenderer = renderer
+            .clone()
+            .with_orientation(if flipped { Team::Black } else { Team::White });
+        if let Some(node) = board.history().last().filter(|_| colors) {
+            board_renderer = board_renderer.with_highlights(&[node.from, node.to]);
+        }
+        println!("{}\n", board_renderer.render(&board));
+        if let Some(result) = game_result(&board) {
+            break result;
+        }
+        let team: Team = board.turn_to_play();
+        if board.is_in_check(team) {
+            println!("{} is in check.", team_name(team));
+        }
+
+        if engine_team == Some(team) {
+            let result: SearchResult = search(&board, &limits);
+            let chess_move: Move = match result.best_move {
+                Some(chess_move) => chess_move,
+                None => break "*".to_string(),
+            };
+            println!("{} plays {} ({})\n", team_name(team), board.san(&chess_move), result.score);
+            board.make_move(&chess_move).expect("the engine only plays legal moves");
+            continue;
+        }
+
+        print!("{} to play> ", team_name(team));
+        std::io::stdout().flush().ok();
+        let line: String = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break "*".to_string(),
+        };
+
+        match line.trim() {
+            "" => {}
+            "help" => println!("{}\n", COMMANDS),
+            "quit" => break "*".to_string(),
+            "flip" => flipped = !flipped,
+            "fen" => println!("{}\n", board.fen().state),
+            "pgn" => println!("{}", PgnGame::from_board(&board, "*")),
+            "undo" => {
+                // Against the engine, the engine's reply is taken back as well so that the player is to move again.
+                let plies: usize = if engine_team.is_some() { 2 } else { 1 };
+                let mut undone: usize = 0;
+                while undone < plies && board.undo_move().is_some() {
+                    undone += 1;
+                }
+                if undone == 0 {
+                    println!("There are no moves to take back.\n");
+                }
+            }
+            "resign" => break winning_result(team.opponent()),
+            "draw" => {
+                if offer_draw(&board, engine_team, &limits, &mut lines) {
+                    break "1/2-1/2".to_string();
+                }
+            }
+            input => match parse_move(&board, input).and_then(|chess_move| board.make_move(&chess_move)) {
+                Ok(()) => println!(),
+                Err(BoardError::InvalidSan | BoardError::InvalidUci) => {
+                    println!("That is neither a move nor a command, enter help for the list of commands.\n")
+                }
+                Err(error @ BoardError::AmbiguousMove) => println!("{} Add the column or row it comes from.\n", error),
+                Err(error) => println!("{}\n", error),
+            },
+        }
+    };
+
+    println!("Game over: {}\n", describe_result(&result));
+    println!("{}", PgnGame::from_board(&board, &result));
+}
+
+/// Parses a move in standard algebraic notation, UCI, or as two coordinates separated by a dash or a space.
+fn parse_move(board: &Board, input: &str) -> Result<Move, BoardError> {
+    board
+        .parse_san(input)
+        .or_else(|_| board.parse_uci(input))
+        .or_else(|error| {
+            let coordinates: String = input.chars().filter(|character| !matches!(character, '-' | ' ')).collect();
+            board.parse_uci(&coordinates.to_lowercase()).map_err(|_| error)
+        })
+}
+
+/// Asks the opponent whether they accept a draw. The engine accepts when it does not think that it is winning.
+fn offer_draw<I: Iterator<Item = std::io::Result<String>>>(
+    board: &Board,
+    engine_team: Option<Team>,
+    limits: &SearchLimits,
+    lines: &mut I,
+) -> bool {
+    let opponent: Team = board.turn_to_play().opponent();
+    if engine_team == Some(opponent) {
+        // The search scores the position for the team to play, which is the team offering the draw.
+        let accepted: bool = match search(board, limits).score {
+            Score::Centipawns(centipawns) => centipawns >= 0,
+            Score::Mate(moves) => moves > 0,
+        };
+        println!("{} {} the draw.\n", team_name(opponent), if accepted { "accepts" } else { "declines" });
+        return accepted;
+    }
+
+    print!("{} offers a draw. {}, do you accept? [y/n]> ", team_name(opponent.opponent()), team_name(opponent));
+    std::io::stdout().flush().ok();
+    let accepted: bool = matches!(lines.next(), Some(Ok(answer)) if answer.trim().eq_ignore_ascii_case("y"));
+    println!();
+    accepted
+}
+
+/// The result of the game in PGN notation, if the game is over.
+fn game_result(board: &Board) -> Option<String> {
+    if let Some(team) = board.winner() {
+        return Some(winning_result(team));
+    }
+    board.is_draw().then(|| "1/2-1/2".to_string())
+}
+
+fn winning_result(team: Team) -> String {
+    match team {
+        Team::White => "1-0".to_string(),
+        Team::Black => "0-1".to_string(),
+    }
+}
+
+fn describe_result(result: &str) -> &'static str {
+    match result {
+        "1-0" => "white wins",
+        "0-1" => "black wins",
+        "1/2-1/2" => "draw",
+        _ => "unfinished",
+    }
+}
+
+fn team_name(team: Team) -> &'static str {
+    match team {
+        Team::White => "White",
+        Team::Black => "Black",
+    }
+}
+
+fn parse_or_exit<T: std::str::FromStr>(value: &str) -> T {
+    value.parse().unwrap_or_else(|_| exit_with_usage())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,47 @@
+use crate::coordinate::Coordinate;
+use crate::piece::PieceClass;
+
+/// Represents a single move of a piece from one coordinate to another. If the move is a pawn promotion then the class
+/// of the piece that the pawn is promoted to is stored alongside it. In variants with pockets a move can instead drop a
+/// piece from the pocket onto an empty coordinate, in which case both `from` and `to` are that coordinate.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Move {
+    pub from: Coordinate,
+    pub to: Coordinate,
+    pub promotion: Option<PieceClass>,
+    pub drop: Option<PieceClass>,
+}
+
+impl Move {
+    pub fn new(from: Coordinate, to: Coordinate) -> Self {
+        Self {
+            from,
+            to,
+            promotion: None,
+            drop: None,
+        }
+    }
+
+    pub fn new_with_promotion(from: Coordinate, to: Coordinate, promotion: PieceClass) -> Self {
+        Self {
+            from,
+            to,
+            promotion: Some(promotion),
+            drop: None,
+        }
+    }
+
+    /// Creates a move which drops a piece of the class from the pocket onto the coordinate.
+    pub fn new_drop(class: PieceClass, to: Coordinate) -> Self {
+        Self {
+            from: to,
+            to,
+            promotion: None,
+            drop: Some(class),
+        }
+    }
+
+    pub fn is_drop(&self) -> bool {
+        self.drop.is_some()
+    }
+}
@@ -0,0 +1,322 @@
+use crate::board::{Board, BoardError, Fen};
+use crate::moves::Move;
+use crate::piece::Team;
+use crate::variant::STANDARD_FEN;
+
+/// The longest line written in the movetext of a PGN.
+const MAX_LINE_LENGTH: usize = 80;
+
+/// The value of the `Variant` tag of Chess960 games, which are played by the standard rules apart from castling.
+const CHESS960_VARIANT: &str = "Chess960";
+
+/// Represents a single game read from a PGN file, with its tag pairs and the moves of its main line written in standard
+/// algebraic notation. Comments, variations, and annotation glyphs are not kept.
+#[derive(Debug, Clone, Default)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    pub result: String,
+}
+
+impl PgnGame {
+    /// Creates a game from the moves made on the board, with the result written as in PGN (`1-0`, `0-1`, `1/2-1/2`,
+    /// or `*`). The tags of the seven tag roster are filled with unknown values, and the `SetUp` and `FEN` tags are
+    /// added when the game did not start from the standard starting position.
+    pub fn from_board(board: &Board, result: &str) -> Self {
+        let mut game: PgnGame = PgnGame {
+            tags: vec![
+                ("Event".to_string(), "?".to_string()),
+                ("Site".to_string(), "?".to_string()),
+                ("Date".to_string(), "????.??.??".to_string()),
+                ("Round".to_string(), "?".to_string()),
+                ("White".to_string(), "?".to_string()),
+                ("Black".to_string(), "?".to_string()),
+                ("Result".to_string(), result.to_string()),
+            ],
+            moves: Vec::new(),
+            result: result.to_string(),
+        };
+
+        if board.variant().name() != "Standard" {
+            game.tags.push(("Variant".to_string(), board.variant().name().to_string()));
+        } else if board.is_chess960() {
+            game.tags.push(("Variant".to_string(), CHESS960_VARIANT.to_string()));
+        }
+        let starting_fen: Fen = board.starting_fen();
+        if starting_fen.state != STANDARD_FEN || board.is_chess960() {
+            game.tags.push(("SetUp".to_string(), "1".to_string()));
+            game.tags.push(("FEN".to_string(), starting_fen.state));
+        }
+
+        let mut replay_board: Board = match game.starting_board() {
+            Ok(board) => board,
+            Err(_) => return game,
+        };
+        replay_board.set_chess960(board.is_chess960());
+        for node in board.history().iter() {
+            let chess_move: Move = Move {
+                from: node.from,
+                to: node.to,
+                promotion: node.promotion,
+                drop: node.drop,
+            };
+            game.moves.push(replay_board.san(&chess_move));
+            if replay_board.make_move(&chess_move).is_err() {
+                break;
+            }
+        }
+        game
+    }
+
+    /// Sets the value of the tag with the given name, adding the tag if the game does not have it yet.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some((_, tag_value)) => *tag_value = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Gets the value of the tag with the given name, if the game has it.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Creates the board that the game starts from, which is the position in the `FEN` tag if the game has one, played
+    /// by the rules of the variant in the `Variant` tag.
+    pub fn starting_board(&self) -> Result<Board, BoardError> {
+        let mut board: Board = match self.tag("FEN") {
+            Some(fen) => Board::try_new_with_fen(Fen { state: fen.to_string() })?,
+            None => Board::new(),
+        };
+        match self.tag("Variant") {
+            None => {}
+            Some(variant) if variant.eq_ignore_ascii_case(CHESS960_VARIANT) => board.set_chess960(true),
+            Some(variant) => board.set_variant_by_name(variant)?,
+        }
+        Ok(board)
+    }
+
+    /// Replays the moves of the game, calling the visitor with the board before each move and the move itself. An error
+    /// is returned at the first move which can not be played, after the visitor has seen all of the moves before it.
+    pub fn replay<F: FnMut(&Board, &Move)>(&self, mut visitor: F) -> Result<Board, BoardError> {
+        let mut board: Board = self.starting_board()?;
+        for san in self.moves.iter() {
+            let chess_move: Move = board.parse_san(san)?;
+            visitor(&board, &chess_move);
+            board.make_move(&chess_move)?;
+        }
+        Ok(board)
+    }
+}
+
+impl std::fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (name, value) in self.tags.iter() {
+            writeln!(f, "[{} \"{}\"]", name, value.replace('\\', "\\\\").replace('"', "\\\""))?;
+        }
+        writeln!(f)?;
+
+        // Games which start from a position with black to play number their first move with an ellipsis.
+        let (mut number, mut team) = match self.starting_board() {
+            Ok(board) => (board.fullmove_number(), board.turn_to_play()),
+            Err(_) => (1, Team::White),
+        };
+        let mut tokens: Vec<String> = Vec::new();
+        for (index, san) in self.moves.iter().enumerate() {
+            match team {
+                Team::White => tokens.push(format!("{}.", number)),
+                Team::Black if index == 0 => tokens.push(format!("{}...", number)),
+                Team::Black => {}
+            }
+            tokens.push(san.clone());
+            if matches!(team, Team::Black) {
+                number += 1;
+            }
+            team = team.opponent();
+        }
+        tokens.push(if self.result.is_empty() { "*".to_string() } else { self.result.clone() });
+
+        let mut line_length: usize = 0;
+        for token in tokens.iter() {
+            if line_length > 0 && line_length + 1 + token.len() > MAX_LINE_LENGTH {
+                writeln!(f)?;
+                line_length = 0;
+            } else if line_length > 0 {
+                write!(f, " ")?;
+                line_length += 1;
+            }
+            write!(f, "{}", token)?;
+            line_length += token.len();
+        }
+        writeln!(f)
+    }
+}
+
+/// Parses all of the games in the contents of a PGN file.
+pub fn parse_pgn(text: &str) -> Vec<PgnGame> {
+    let mut games: Vec<PgnGame> = Vec::new();
+    let mut game: PgnGame = PgnGame::default();
+    let mut in_movetext: bool = false;
+
+    let mut characters = text.chars().peekable();
+    while let Some(character) = characters.next() {
+        match character {
+            // A tag pair after the movetext of a game without a result marks the start of the next game.
+            '[' => {
+                if in_movetext {
+                    games.push(std::mem::take(&mut game));
+                    in_movetext = false;
+                }
+                // The value is quoted, and may hold brackets such as the pockets of a FEN as well as escaped quotes.
+                let mut tag: String = String::new();
+                let mut in_quotes: bool = false;
+                while let Some(character) = characters.next() {
+                    match character {
+                        '"' => in_quotes = !in_quotes,
+                        '\\' if in_quotes => tag.extend(characters.next()),
+                        ']' if !in_quotes => break,
+                        character => tag.push(character),
+                    }
+                }
+                if let Some((name, value)) = tag.trim().split_once(char::is_whitespace) {
+                    game.tags.push((name.to_string(), value.trim().to_string()));
+                }
+            }
+            '{' => {
+                characters.by_ref().take_while(|character| *character != '}').for_each(drop);
+            }
+            ';' => {
+                characters.by_ref().take_while(|character| *character != '\n').for_each(drop);
+            }
+            '(' => {
+                let mut depth: usize = 1;
+                for character in characters.by_ref() {
+                    match character {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            character if character.is_whitespace() => {}
+            character => {
+                let mut token: String = character.to_string();
+                while let Some(next) = characters.peek() {
+                    if next.is_whitespace() || ['{', '(', ';', '['].contains(next) {
+                        break;
+                    }
+                    token.push(characters.next().unwrap());
+                }
+                in_movetext = true;
+
+                match token.as_str() {
+                    "1-0" | "0-1" | "1/2-1/2" | "*" => {
+                        game.result = token;
+                        games.push(std::mem::take(&mut game));
+                        in_movetext = false;
+                    }
+                    _ if token.starts_with('$') => {}
+                    _ => {
+                        // Move numbers may be attached to the move which follows them, as in `1.e4` or `12...Nf6`.
+                        let san: &str = match token.find(|character: char| !character.is_ascii_digit()) {
+                            Some(index) if token[index..].starts_with('.') => token[index..].trim_start_matches('.'),
+                            Some(_) => token.as_str(),
+                            None => "",
+                        };
+                        if !san.is_empty() {
+                            game.moves.push(san.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if in_movetext || !game.tags.is_empty() {
+        games.push(game);
+    }
+    for game in games.iter_mut() {
+        if game.result.is_empty() {
+            game.result = game.tag("Result").unwrap_or("*").to_string();
+        }
+    }
+
+    games
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::STANDARD_CHESS960_INDEX;
+    use crate::variant::{Crazyhouse, Horde, Variant};
+
+    /// Plays the moves on the board, then writes the game as PGN and reads it back.
+    fn write_and_read(mut board: Board, moves: &[&str]) -> (Board, PgnGame) {
+        for uci in moves {
+            board.make_move(&board.parse_uci(uci).unwrap()).unwrap();
+        }
+        let text: String = PgnGame::from_board(&board, "*").to_string();
+        let mut games: Vec<PgnGame> = parse_pgn(&text);
+        assert_eq!(games.len(), 1, "{}", text);
+        (board, games.remove(0))
+    }
+
+    #[test]
+    fn games_are_written_and_read_back() {
+        let (board, game) = write_and_read(Board::new(), &["e2e4", "e7e5", "g1f3"]);
+
+        assert_eq!(game.moves, ["e4", "e5", "Nf3"]);
+        assert_eq!(game.tag("FEN"), None);
+        assert_eq!(game.replay(|_, _| {}).unwrap().fen().state, board.fen().state);
+    }
+
+    #[test]
+    fn variant_games_are_replayed_by_the_rules_of_their_variant() {
+        let moves = ["e2e4", "d7d5", "e4d5", "d8d5", "P@e6", "d5e6"];
+        let (board, game) = write_and_read(Board::new_with_variant(Crazyhouse), &moves);
+        assert_eq!(game.tag("Variant"), Some("Crazyhouse"));
+        assert_eq!(game.tag("FEN"), Some(Crazyhouse.starting_fen().state.as_str()));
+        assert_eq!(game.moves[4], "P@e6");
+
+        let replayed: Board = game.replay(|_, _| {}).unwrap();
+        assert_eq!(replayed.variant().name(), "Crazyhouse");
+        assert_eq!(replayed.fen().state, board.fen().state);
+
+        let (board, game) = write_and_read(Board::new_with_variant(Horde), &["d4d5", "e7e6"]);
+        assert_eq!(game.replay(|_, _| {}).unwrap().fen().state, board.fen().state);
+    }
+
+    #[test]
+    fn chess960_games_keep_their_castling_rules() {
+        let moves = ["e2e4", "e7e5", "g1f3", "g8f6", "f1c4", "f8c5", "e1h1"];
+        let (board, game) = write_and_read(Board::new_chess960(STANDARD_CHESS960_INDEX).unwrap(), &moves);
+        assert_eq!(game.tag("Variant"), Some("Chess960"));
+        assert_eq!(game.moves[6], "O-O");
+
+        let replayed: Board = game.replay(|_, _| {}).unwrap();
+        assert!(replayed.is_chess960());
+        assert_eq!(replayed.fen().state, board.fen().state);
+    }
+
+    #[test]
+    fn unknown_variants_are_errors() {
+        let games: Vec<PgnGame> = parse_pgn("[Variant \"Nonsense\"]\n\n1. e4 *\n");
+        assert!(matches!(games[0].starting_board(), Err(BoardError::UnknownVariant)));
+    }
+
+    #[test]
+    fn tag_values_keep_brackets_and_escaped_quotes() {
+        let mut game: PgnGame = PgnGame::from_board(&Board::new(), "*");
+        game.set_tag("Event", r#"The "Open" [final] \ round"#);
+
+        let games: Vec<PgnGame> = parse_pgn(&game.to_string());
+        assert_eq!(games[0].tag("Event"), Some(r#"The "Open" [final] \ round"#));
+        assert_eq!(games[0].moves.len(), 0);
+    }
+}
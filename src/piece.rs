@@ -10,20 +10,50 @@ pub enum PieceClass {
     Pawn,
 }
 
+impl PieceClass {
+    /// The material value of a piece of this class in centipawns. The king can never be traded off the board, so it is
+    /// valued higher than all of the other pieces combined.
+    pub fn value(&self) -> i32 {
+        match self {
+            PieceClass::King => 20000,
+            PieceClass::Queen => 900,
+            PieceClass::Rook => 500,
+            PieceClass::Bishop => 330,
+            PieceClass::Knight => 320,
+            PieceClass::Pawn => 100,
+        }
+    }
+}
+
 /// Represents the two teams which can exist in a game of chess
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
 #[repr(u8)]
 pub enum Team {
     Black,
     White,
 }
 
+impl Team {
+    /// Returns the team playing against this team.
+    pub fn opponent(&self) -> Team {
+        match self {
+            Team::White => Team::Black,
+            Team::Black => Team::White,
+        }
+    }
+}
+
 /// Represents a chess piece belonging to a specific team
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Piece {
     class: PieceClass,
     team: Team,
     number_of_moves: u8,
+
+    /// Whether the piece was promoted from a pawn, which variants with pockets need to know since a promoted piece goes
+    /// back to being a pawn when it is captured.
+    promoted: bool,
 }
 
 impl Piece {
@@ -32,6 +62,15 @@ impl Piece {
             class: piece_class,
             team: piece_team,
             number_of_moves: 0,
+            promoted: false,
+        }
+    }
+
+    /// Creates a piece which a pawn of the team has been promoted to.
+    pub fn new_promoted(piece_class: PieceClass, piece_team: Team) -> Self {
+        Self {
+            promoted: true,
+            ..Self::new(piece_class, piece_team)
         }
     }
 
@@ -47,8 +86,12 @@ impl Piece {
         self.number_of_moves
     }
 
+    pub fn is_promoted(&self) -> bool {
+        self.promoted
+    }
+
     pub fn is_first_move(&self) -> bool {
-        return self.number_of_moves == 0;
+        self.number_of_moves == 0
     }
 
     pub fn add_move(&mut self) {
@@ -60,36 +103,36 @@ impl std::fmt::Display for Piece {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self.class {
             PieceClass::King => match self.team {
-                Team::Black => write!(f, "{}", "♚"),
-                Team::White => write!(f, "{}", "♔"),
+                Team::Black => write!(f, "♚"),
+                Team::White => write!(f, "♔"),
             },
             PieceClass::Queen => match self.team {
-                Team::Black => write!(f, "{}", "♛"),
-                Team::White => write!(f, "{}", "♕"),
+                Team::Black => write!(f, "♛"),
+                Team::White => write!(f, "♕"),
             },
             PieceClass::Rook => match self.team {
-                Team::Black => write!(f, "{}", "♜"),
-                Team::White => write!(f, "{}", "♖"),
+                Team::Black => write!(f, "♜"),
+                Team::White => write!(f, "♖"),
             },
             PieceClass::Bishop => match self.team {
-                Team::Black => write!(f, "{}", "♝"),
-                Team::White => write!(f, "{}", "♗"),
+                Team::Black => write!(f, "♝"),
+                Team::White => write!(f, "♗"),
             },
             PieceClass::Knight => match self.team {
-                Team::Black => write!(f, "{}", "♞"),
-                Team::White => write!(f, "{}", "♘"),
+                Team::Black => write!(f, "♞"),
+                Team::White => write!(f, "♘"),
             },
             PieceClass::Pawn => match self.team {
-                Team::Black => write!(f, "{}", "♟"),
-                Team::White => write!(f, "{}", "♙"),
+                Team::Black => write!(f, "♟"),
+                Team::White => write!(f, "♙"),
             },
         }
     }
 }
 
-impl Into<char> for Piece {
-    fn into(self) -> char {
-        match (self.class(), self.team()) {
+impl From<Piece> for char {
+    fn from(piece: Piece) -> Self {
+        match (piece.class(), piece.team()) {
             (PieceClass::Pawn, Team::White) => 'P',
             (PieceClass::Pawn, Team::Black) => 'p',
 
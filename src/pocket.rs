@@ -0,0 +1,111 @@
+use crate::piece::{Piece, PieceClass, Team};
+
+/// The classes of the pieces which can be held in a pocket, in the order they are written in a FEN.
+pub const POCKET_CLASSES: [PieceClass; 5] = [
+    PieceClass::Queen,
+    PieceClass::Rook,
+    PieceClass::Bishop,
+    PieceClass::Knight,
+    PieceClass::Pawn,
+];
+
+/// The pieces that a team holds in hand in variants such as Crazyhouse, which can be dropped onto empty coordinates of
+/// the board instead of making a move.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub struct Pocket {
+    /// The number of pieces of each class, indexed by the class.
+    counts: [u8; 6],
+}
+
+impl Pocket {
+    /// Gets the number of pieces of the class in the pocket.
+    pub fn count(&self, class: PieceClass) -> u8 {
+        self.counts[class as usize]
+    }
+
+    /// Puts a piece of the class in the pocket. Returns `false` if the pocket can not hold any more pieces of the class.
+    pub fn add(&mut self, class: PieceClass) -> bool {
+        match self.counts[class as usize].checked_add(1) {
+            Some(count) => {
+                self.counts[class as usize] = count;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes a piece of the class out of the pocket. Returns `false` if the pocket has no piece of the class.
+    pub fn remove(&mut self, class: PieceClass) -> bool {
+        match self.counts[class as usize] {
+            0 => false,
+            _ => {
+                self.counts[class as usize] -= 1;
+                true
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Gets the classes of the pieces in the pocket, with every piece listed once.
+    pub fn pieces(&self) -> impl Iterator<Item = PieceClass> + '_ {
+        POCKET_CLASSES
+            .into_iter()
+            .flat_map(|class| std::iter::repeat_n(class, self.count(class) as usize))
+    }
+}
+
+/// The pockets of both teams, written in a FEN inside brackets after the piece placement, such as `[Qn]` for a white
+/// queen and a black knight.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default)]
+pub struct Pockets {
+    white: Pocket,
+    black: Pocket,
+}
+
+impl Pockets {
+    pub fn get(&self, team: Team) -> &Pocket {
+        match team {
+            Team::White => &self.white,
+            Team::Black => &self.black,
+        }
+    }
+
+    pub fn get_mut(&mut self, team: Team) -> &mut Pocket {
+        match team {
+            Team::White => &mut self.white,
+            Team::Black => &mut self.black,
+        }
+    }
+}
+
+impl std::fmt::Display for Pockets {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for team in [Team::White, Team::Black] {
+            for class in self.get(team).pieces() {
+                write!(f, "{}", char::from(Piece::new(class, team)))?;
+            }
+        }
+        std::fmt::Result::Ok(())
+    }
+}
+
+impl TryFrom<&str> for Pockets {
+    type Error = &'static str;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        let mut pockets: Self = Self::default();
+        for character in string.chars() {
+            let piece: Piece = Piece::try_from(character)?;
+            if matches!(piece.class(), PieceClass::King) {
+                return Err("Kings can not be held in a pocket");
+            }
+            if !pockets.get_mut(piece.team()).add(piece.class()) {
+                return Err("A pocket can not hold that many pieces of a class");
+            }
+        }
+        Ok(pockets)
+    }
+}
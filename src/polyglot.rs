@@ -0,0 +1,375 @@
+use crate::board::Board;
+use crate::castling::{back_row, CastlingSide};
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+use std::path::Path;
+
+mod builder;
+mod keys;
+
+pub use builder::BookBuilder;
+use keys::RANDOM64;
+
+/// The offsets into the random numbers of the different parts of the key of a position.
+const CASTLING_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+/// The size in bytes of a single entry in a polyglot book.
+const ENTRY_SIZE: usize = 16;
+
+impl Board {
+    /// Computes the key of the current position as defined by the polyglot book format.
+    pub fn polyglot_key(&self) -> u64 {
+        let mut key: u64 = 0;
+
+        for (row_index, row) in self.map().iter().enumerate() {
+            for (column_index, item) in row.iter().enumerate() {
+                if let Some(piece) = item {
+                    key ^= RANDOM64[64 * piece_kind(piece) + 8 * (7 - row_index) + column_index];
+                }
+            }
+        }
+
+        let castling_rights = self.castling_rights();
+        for (index, (team, side)) in [
+            (Team::White, CastlingSide::KingSide),
+            (Team::White, CastlingSide::QueenSide),
+            (Team::Black, CastlingSide::KingSide),
+            (Team::Black, CastlingSide::QueenSide),
+        ]
+        .iter()
+        .enumerate()
+        {
+            if castling_rights.has(*team, *side) {
+                key ^= RANDOM64[CASTLING_OFFSET + index];
+            }
+        }
+
+        // The en passant column is only part of the key when a pawn of the team to play is able to capture en passant.
+        if let Some(en_passant) = self.en_passant() {
+            let can_capture: bool = self
+                .attackers_to(&en_passant, self.turn_to_play())
+                .into_iter()
+                .any(|coordinate| matches!(self.get_piece(&coordinate).unwrap().class(), PieceClass::Pawn));
+            if can_capture {
+                key ^= RANDOM64[EN_PASSANT_OFFSET + en_passant.column()];
+            }
+        }
+
+        if matches!(self.turn_to_play(), Team::White) {
+            key ^= RANDOM64[TURN_OFFSET];
+        }
+
+        key
+    }
+}
+
+/// Represents a single entry of a polyglot book which maps the key of a position to a move and its weight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolyglotEntry {
+    pub key: u64,
+    pub raw_move: u16,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+impl PolyglotEntry {
+    /// Reads an entry from its big endian representation in a polyglot book.
+    pub fn from_bytes(bytes: &[u8; ENTRY_SIZE]) -> Self {
+        Self {
+            key: u64::from_be_bytes(bytes[0..8].try_into().unwrap()),
+            raw_move: u16::from_be_bytes(bytes[8..10].try_into().unwrap()),
+            weight: u16::from_be_bytes(bytes[10..12].try_into().unwrap()),
+            learn: u32::from_be_bytes(bytes[12..16].try_into().unwrap()),
+        }
+    }
+
+    /// Writes the entry in the big endian representation used in polyglot books.
+    pub fn to_bytes(&self) -> [u8; ENTRY_SIZE] {
+        let mut bytes: [u8; ENTRY_SIZE] = [0; ENTRY_SIZE];
+        bytes[0..8].copy_from_slice(&self.key.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.raw_move.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.weight.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.learn.to_be_bytes());
+        bytes
+    }
+}
+
+/// A move found in a polyglot book along with the weight that the book gives it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookMove {
+    pub chess_move: Move,
+    pub weight: u16,
+}
+
+/// An opening book in the polyglot format. The entries of the book are sorted by their keys which allows for the moves
+/// of a position to be found through a binary search.
+#[derive(Debug, Clone, Default)]
+pub struct PolyglotBook {
+    entries: Vec<PolyglotEntry>,
+}
+
+impl PolyglotBook {
+    /// Loads a polyglot book from a `.bin` file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PolyglotError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Reads a polyglot book from the contents of a `.bin` file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PolyglotError> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(PolyglotError::InvalidLength);
+        }
+
+        let mut entries: Vec<PolyglotEntry> = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|chunk| PolyglotEntry::from_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        // Books are expected to be sorted already, but sorting keeps the lookups correct for books which are not.
+        entries.sort_by_key(|entry| entry.key);
+
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> &[PolyglotEntry] {
+        &self.entries
+    }
+
+    /// Gets all of the entries of the book for the given key.
+    pub fn entries_for_key(&self, key: u64) -> &[PolyglotEntry] {
+        let start: usize = self.entries.partition_point(|entry| entry.key < key);
+        let end: usize = self.entries.partition_point(|entry| entry.key <= key);
+        &self.entries[start..end]
+    }
+
+    /// Gets the book moves of the current position of the board ordered from the highest weight to the lowest. Entries
+    /// whose moves are not legal on the board are skipped.
+    pub fn moves(&self, board: &Board) -> Vec<BookMove> {
+        let mut book_moves: Vec<BookMove> = self
+            .entries_for_key(board.polyglot_key())
+            .iter()
+            .filter_map(|entry| {
+                Some(BookMove {
+                    chess_move: decode_move(board, entry.raw_move)?,
+                    weight: entry.weight,
+                })
+            })
+            .collect();
+        book_moves.sort_by_key(|book_move| std::cmp::Reverse(book_move.weight));
+        book_moves
+    }
+
+    /// Checks if the move is one of the book moves of the current position of the board.
+    pub fn contains(&self, board: &Board, chess_move: &Move) -> bool {
+        self.moves(board)
+            .iter()
+            .any(|book_move| book_move.chess_move == *chess_move)
+    }
+
+    /// Gets the book move with the highest weight for the current position of the board.
+    pub fn best_move(&self, board: &Board) -> Option<Move> {
+        self.moves(board).first().map(|book_move| book_move.chess_move)
+    }
+
+    /// Picks one of the book moves of the current position with a probability proportional to its weight. The random
+    /// number is provided by the caller so that the choice can be reproduced.
+    pub fn weighted_move(&self, board: &Board, random: u32) -> Option<Move> {
+        let book_moves: Vec<BookMove> = self.moves(board);
+        let total_weight: u32 = book_moves.iter().map(|book_move| book_move.weight as u32).sum();
+        if total_weight == 0 {
+            return self.best_move(board);
+        }
+
+        let mut remaining: u32 = random % total_weight;
+        for book_move in book_moves.iter() {
+            if remaining < book_move.weight as u32 {
+                return Some(book_move.chess_move);
+            }
+            remaining -= book_move.weight as u32;
+        }
+        None
+    }
+}
+
+/// Decodes a move from its polyglot representation into a legal move on the board. Castling is represented in polyglot
+/// books as the king capturing its own rook, so unless the board follows the Chess960 rules, where castling is written
+/// the same way, it gets converted to the king moving to its castling destination.
+pub fn decode_move(board: &Board, raw_move: u16) -> Option<Move> {
+    let coordinate = |column: u16, row: u16| Coordinate::try_from((7 - row as usize, column as usize)).unwrap();
+
+    let to: Coordinate = coordinate(raw_move & 0b111, (raw_move >> 3) & 0b111);
+    let from: Coordinate = coordinate((raw_move >> 6) & 0b111, (raw_move >> 9) & 0b111);
+    let promotion: Option<PieceClass> = match (raw_move >> 12) & 0b111 {
+        0 => None,
+        1 => Some(PieceClass::Knight),
+        2 => Some(PieceClass::Bishop),
+        3 => Some(PieceClass::Rook),
+        4 => Some(PieceClass::Queen),
+        _ => return None,
+    };
+
+    let piece: Piece = board.get_piece(&from)?;
+    let mut chess_move: Move = Move { from, to, promotion, drop: None };
+
+    if !board.is_chess960() {
+        if let Some(side) = castling_side_of_rook(board, &piece, &to) {
+            chess_move.to = Coordinate::try_from((from.row(), side.king_destination_column())).unwrap();
+        }
+    }
+
+    board.legal_moves().contains(&chess_move).then_some(chess_move)
+}
+
+/// Encodes a move into its polyglot representation.
+pub fn encode_move(board: &Board, chess_move: &Move) -> u16 {
+    let mut to: Coordinate = chess_move.to;
+
+    // Castling is stored as the king moving to the coordinate of the rook it castles with.
+    if let Some((rook_from, _)) = board.castling_rook_move(chess_move) {
+        to = rook_from;
+    }
+
+    let promotion: u16 = match chess_move.promotion {
+        None => 0,
+        Some(PieceClass::Knight) => 1,
+        Some(PieceClass::Bishop) => 2,
+        Some(PieceClass::Rook) => 3,
+        Some(PieceClass::Queen) => 4,
+        Some(_) => 0,
+    };
+
+    (to.column() as u16)
+        | ((7 - to.row() as u16) << 3)
+        | ((chess_move.from.column() as u16) << 6)
+        | ((7 - chess_move.from.row() as u16) << 9)
+        | (promotion << 12)
+}
+
+/// Gets the castling side if the piece is a king moving onto the coordinate of a rook that it has the right to castle
+/// with.
+fn castling_side_of_rook(board: &Board, piece: &Piece, to: &Coordinate) -> Option<CastlingSide> {
+    if !matches!(piece.class(), PieceClass::King) || to.row() != back_row(piece.team()) {
+        return None;
+    }
+
+    [CastlingSide::KingSide, CastlingSide::QueenSide]
+        .into_iter()
+        .find(|side| board.castling_rights().rook_column(piece.team(), *side) == Some(to.column()))
+}
+
+/// The index of the piece in the polyglot format, which orders the pieces by their class with black before white.
+fn piece_kind(piece: &Piece) -> usize {
+    let class_index: usize = match piece.class() {
+        PieceClass::Pawn => 0,
+        PieceClass::Knight => 1,
+        PieceClass::Bishop => 2,
+        PieceClass::Rook => 3,
+        PieceClass::Queen => 4,
+        PieceClass::King => 5,
+    };
+    let team_index: usize = match piece.team() {
+        Team::Black => 0,
+        Team::White => 1,
+    };
+
+    2 * class_index + team_index
+}
+
+/// Represents an error encountered when reading a polyglot book.
+#[derive(Debug)]
+pub enum PolyglotError {
+    Io(std::io::Error),
+    InvalidLength,
+}
+
+impl From<std::io::Error> for PolyglotError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Finds the legal move from and to the coordinates of a move written in UCI.
+    fn legal_move(board: &Board, uci: &str) -> Move {
+        let from: Coordinate = Coordinate::try_from(&uci[0..2]).unwrap();
+        let to: Coordinate = Coordinate::try_from(&uci[2..4]).unwrap();
+        board
+            .legal_moves()
+            .into_iter()
+            .find(|chess_move| chess_move.from == from && chess_move.to == to)
+            .unwrap()
+    }
+
+    /// Plays the moves, written in UCI, from the starting position.
+    fn board_after(moves: &[&str]) -> Board {
+        let mut board: Board = Board::new();
+        for uci in moves {
+            let chess_move: Move = legal_move(&board, uci);
+            board.make_move(&chess_move).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    fn keys_match_the_published_test_vectors() {
+        let vectors: [(&[&str], u64); 9] = [
+            (&[], 0x463b96181691fc9c),
+            (&["e2e4"], 0x823c9b50fd114196),
+            (&["e2e4", "d7d5"], 0x0756b94461c50fb0),
+            (&["e2e4", "d7d5", "e4e5"], 0x662fafb965db29d4),
+            (&["e2e4", "d7d5", "e4e5", "f7f5"], 0x22a48b5a8e47ff78),
+            (&["e2e4", "d7d5", "e4e5", "f7f5", "e1e2"], 0x652a607ca3f242c1),
+            (&["e2e4", "d7d5", "e4e5", "f7f5", "e1e2", "e8f7"], 0x00fdd303c946bdd9),
+            (&["a2a4", "b7b5", "h2h4", "b5b4", "c2c4"], 0x3c8123ea7b067637),
+            (&["a2a4", "b7b5", "h2h4", "b5b4", "c2c4", "b4c3", "a1a3"], 0x5c3f9b829b279560),
+        ];
+
+        for (moves, key) in vectors {
+            assert_eq!(board_after(moves).polyglot_key(), key, "after {:?}", moves);
+        }
+    }
+
+    #[test]
+    fn castling_is_encoded_as_the_king_taking_its_rook() {
+        let board: Board = Board::new_with_fen(crate::board::Fen {
+            state: "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1".to_string(),
+        });
+        let castle: Move = legal_move(&board, "e1g1");
+        let raw_move: u16 = encode_move(&board, &castle);
+
+        // e1 is column 4 of row 0 and h1 is column 7 of row 0.
+        assert_eq!(raw_move, (4 << 6) | 7);
+        assert_eq!(decode_move(&board, raw_move), Some(castle));
+    }
+
+    #[test]
+    fn book_moves_are_looked_up_by_key_and_ordered_by_weight() {
+        let board: Board = Board::new();
+        let e4: Move = legal_move(&board, "e2e4");
+        let d4: Move = legal_move(&board, "d2d4");
+        let bytes: Vec<u8> = [
+            PolyglotEntry { key: board.polyglot_key(), raw_move: encode_move(&board, &e4), weight: 2, learn: 0 },
+            PolyglotEntry { key: board.polyglot_key(), raw_move: encode_move(&board, &d4), weight: 5, learn: 0 },
+            PolyglotEntry { key: 1, raw_move: encode_move(&board, &e4), weight: 9, learn: 0 },
+        ]
+        .iter()
+        .flat_map(|entry| entry.to_bytes())
+        .collect();
+        let book: PolyglotBook = PolyglotBook::from_bytes(&bytes).unwrap();
+
+        assert_eq!(
+            book.moves(&board),
+            vec![BookMove { chess_move: d4, weight: 5 }, BookMove { chess_move: e4, weight: 2 }]
+        );
+        assert_eq!(book.best_move(&board), Some(d4));
+        assert_eq!(book.weighted_move(&board, 5), Some(e4));
+        assert!(book.contains(&board, &e4));
+        assert!(matches!(PolyglotBook::from_bytes(&bytes[1..]), Err(PolyglotError::InvalidLength)));
+    }
+}
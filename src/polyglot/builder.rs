@@ -0,0 +1,221 @@
+use super::{encode_move, PolyglotEntry, PolyglotError};
+use crate::board::{Board, BoardError};
+use crate::pgn::PgnGame;
+use crate::piece::Team;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Builds a polyglot book out of the moves played in a collection of games. Every move played within the first plies of
+/// a game is recorded along with the result of the game for the team which played it, and the moves which pass the
+/// filters of the builder are written as entries of the book.
+#[derive(Debug, Clone)]
+pub struct BookBuilder {
+    /// The number of plies from the start of each game which are added to the book.
+    max_ply: usize,
+
+    /// The minimum number of games that a move must have been played in to be added to the book.
+    min_games: u32,
+
+    /// The minimum score, between 0 and 1, that a move must have achieved for the team playing it.
+    min_score: f64,
+
+    /// The minimum rating from the `WhiteElo` and `BlackElo` tags that a player must have for their moves to count.
+    min_elo: u16,
+
+    /// The statistics of every move that has been played, keyed by the polyglot key of the position and the move.
+    statistics: HashMap<(u64, u16), MoveStatistics>,
+}
+
+/// The results of the games in which a move was played, from the point of view of the team which played the move.
+#[derive(Debug, Clone, Copy, Default)]
+struct MoveStatistics {
+    games: u32,
+    wins: u32,
+    draws: u32,
+}
+
+impl MoveStatistics {
+    fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games as f64
+    }
+
+    /// The weight of the move following the convention of polyglot, where a win is worth two points and a draw one.
+    fn weight(&self) -> u32 {
+        2 * self.wins + self.draws
+    }
+}
+
+impl BookBuilder {
+    pub fn new() -> Self {
+        Self {
+            max_ply: 24,
+            min_games: 1,
+            min_score: 0.0,
+            min_elo: 0,
+            statistics: HashMap::new(),
+        }
+    }
+
+    pub fn with_max_ply(mut self, max_ply: usize) -> Self {
+        self.max_ply = max_ply;
+        self
+    }
+
+    pub fn with_min_games(mut self, min_games: u32) -> Self {
+        self.min_games = min_games;
+        self
+    }
+
+    pub fn with_min_score(mut self, min_score: f64) -> Self {
+        self.min_score = min_score;
+        self
+    }
+
+    pub fn with_min_elo(mut self, min_elo: u16) -> Self {
+        self.min_elo = min_elo;
+        self
+    }
+
+    /// Replays the game and records its moves. Games without a decisive or drawn result are skipped, and the moves after
+    /// the first move which can not be played are ignored. Returns whether the game was used, which is not the case when
+    /// not even its first move could be played.
+    pub fn add_game(&mut self, game: &PgnGame) -> bool {
+        let winner: Option<Team> = match game.result.as_str() {
+            "1-0" => Some(Team::White),
+            "0-1" => Some(Team::Black),
+            "1/2-1/2" => None,
+            _ => return false,
+        };
+        let elo = |tag: &str| -> u16 { game.tag(tag).and_then(|elo| elo.parse().ok()).unwrap_or(0) };
+        let white_elo: u16 = elo("WhiteElo");
+        let black_elo: u16 = elo("BlackElo");
+
+        let mut ply: usize = 0;
+        let replay: Result<Board, BoardError> = game.replay(|board, chess_move| {
+            let team: Team = board.turn_to_play();
+            let player_elo: u16 = match team {
+                Team::White => white_elo,
+                Team::Black => black_elo,
+            };
+
+            if ply < self.max_ply && player_elo >= self.min_elo {
+                let statistics: &mut MoveStatistics = self
+                    .statistics
+                    .entry((board.polyglot_key(), encode_move(board, chess_move)))
+                    .or_default();
+                statistics.games += 1;
+                match winner {
+                    Some(winner) if winner == team => statistics.wins += 1,
+                    Some(_) => {}
+                    None => statistics.draws += 1,
+                }
+            }
+            ply += 1;
+        });
+
+        replay.is_ok() || ply > 0
+    }
+
+    /// Gets the entries of the book sorted by their keys, and by descending weight for entries of the same key. The
+    /// weights are scaled down where needed so that the heaviest move of every position fits in the entry. Moves with a
+    /// weight of zero are left out since they would never be picked from the book.
+    pub fn entries(&self) -> Vec<PolyglotEntry> {
+        let mut entries: Vec<(u64, u16, u32)> = self
+            .statistics
+            .iter()
+            .filter(|(_, statistics)| statistics.games >= self.min_games && statistics.score() >= self.min_score)
+            .filter(|(_, statistics)| statistics.weight() > 0)
+            .map(|((key, raw_move), statistics)| (*key, *raw_move, statistics.weight()))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0).then(b.2.cmp(&a.2)).then(a.1.cmp(&b.1)));
+
+        let mut max_weights: HashMap<u64, u32> = HashMap::new();
+        for (key, _, weight) in entries.iter() {
+            let max_weight: &mut u32 = max_weights.entry(*key).or_default();
+            *max_weight = u32::max(*max_weight, *weight);
+        }
+
+        entries
+            .into_iter()
+            .map(|(key, raw_move, weight)| {
+                let max_weight: u32 = max_weights[&key];
+                let weight: u32 = if max_weight > u16::MAX as u32 {
+                    u32::max(1, (weight as u64 * u16::MAX as u64 / max_weight as u64) as u32)
+                } else {
+                    weight
+                };
+
+                PolyglotEntry {
+                    key,
+                    raw_move,
+                    weight: weight as u16,
+                    learn: 0,
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the book to a `.bin` file in the polyglot format.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), PolyglotError> {
+        let bytes: Vec<u8> = self
+            .entries()
+            .iter()
+            .flat_map(|entry| entry.to_bytes())
+            .collect();
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+}
+
+impl Default for BookBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(moves: &[&str], result: &str) -> PgnGame {
+        PgnGame {
+            tags: Vec::new(),
+            moves: moves.iter().map(|san| san.to_string()).collect(),
+            result: result.to_string(),
+        }
+    }
+
+    #[test]
+    fn games_whose_first_move_can_not_be_played_are_not_used() {
+        let mut builder: BookBuilder = BookBuilder::new();
+
+        assert!(!builder.add_game(&game(&["e5", "e4"], "1-0")));
+        assert!(builder.entries().is_empty());
+    }
+
+    #[test]
+    fn moves_before_the_first_illegal_move_are_recorded() {
+        let mut builder: BookBuilder = BookBuilder::new();
+
+        assert!(builder.add_game(&game(&["e4", "e5", "Ke3"], "1-0")));
+        assert!(builder.add_game(&game(&["d4", "d5"], "1/2-1/2")));
+        assert!(!builder.add_game(&game(&["e4"], "*")));
+
+        let board: Board = Board::new();
+        let entries: Vec<PolyglotEntry> = builder.entries();
+        let first_moves: Vec<(u16, u16)> = entries
+            .iter()
+            .filter(|entry| entry.key == board.polyglot_key())
+            .map(|entry| (entry.raw_move, entry.weight))
+            .collect();
+        assert_eq!(
+            first_moves,
+            vec![
+                (encode_move(&board, &board.parse_san("e4").unwrap()), 2),
+                (encode_move(&board, &board.parse_san("d4").unwrap()), 1),
+            ]
+        );
+        // The black moves of the game won by white have no weight and are left out of the book.
+        assert_eq!(entries.len(), 3);
+    }
+}
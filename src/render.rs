@@ -0,0 +1,309 @@
+use crate::board::Board;
+use crate::coordinate::Coordinate;
+use crate::piece::{Piece, Team};
+
+/// The 256 color palette indices used when drawing with ANSI colors.
+const LIGHT_SQUARE: u8 = 180;
+const DARK_SQUARE: u8 = 137;
+const HIGHLIGHTED_SQUARE: u8 = 186;
+const ARROW_SQUARE: u8 = 110;
+const WHITE_PIECE: u8 = 231;
+const BLACK_PIECE: u8 = 16;
+const ARROW: u8 = 25;
+
+/// Draws a board as text, from the side of either team, with unicode glyphs or the letters of a FEN, with or without
+/// ANSI colors. Squares can be highlighted and arrows drawn between squares.
+///
+/// The default renderer draws the board the same way as `impl Display for Board`, and the output of every renderer is
+/// a plain string, so it can be printed to a terminal or written to a log.
+#[derive(Debug, Clone)]
+pub struct BoardRenderer {
+    orientation: Team,
+    ascii: bool,
+    colors: bool,
+    labels: bool,
+    highlights: Vec<Coordinate>,
+    arrows: Vec<(Coordinate, Coordinate)>,
+}
+
+impl Default for BoardRenderer {
+    fn default() -> Self {
+        Self {
+            orientation: Team::White,
+            ascii: false,
+            colors: false,
+            labels: true,
+            highlights: Vec::new(),
+            arrows: Vec::new(),
+        }
+    }
+}
+
+impl BoardRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the team whose side of the board is drawn at the bottom.
+    pub fn with_orientation(mut self, team: Team) -> Self {
+        self.orientation = team;
+        self
+    }
+
+    /// Draws the pieces with the letters used in a FEN instead of unicode glyphs.
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
+    /// Shades the squares and colors the pieces with ANSI escape codes.
+    pub fn with_colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Draws the numbers of the rows and the letters of the columns around the board.
+    pub fn with_labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn with_highlight(mut self, coordinate: Coordinate) -> Self {
+        self.highlights.push(coordinate);
+        self
+    }
+
+    pub fn with_highlights(mut self, coordinates: &[Coordinate]) -> Self {
+        self.highlights.extend_from_slice(coordinates);
+        self
+    }
+
+    /// Draws an arrow from one square to another. Arrows along a row, column, or diagonal mark the empty squares they
+    /// cross with the direction of the arrow, other arrows only mark the square they point to.
+    pub fn with_arrow(mut self, from: Coordinate, to: Coordinate) -> Self {
+        self.arrows.push((from, to));
+        self
+    }
+
+    /// Draws the board.
+    pub fn render(&self, board: &Board) -> String {
+        // Overlays need room on both sides of a piece to be drawn without colors, so squares are drawn three characters
+        // wide when there is anything more to show than the pieces.
+        let wide: bool = self.colors || !self.highlights.is_empty() || !self.arrows.is_empty();
+        let square_width: usize = if wide { 3 } else { 2 };
+        let arrow_marks: Vec<(Coordinate, char)> = self.arrow_marks();
+
+        let indices: Vec<usize> = match self.orientation {
+            Team::White => (0..8).collect(),
+            Team::Black => (0..8).rev().collect(),
+        };
+
+        let mut text: String = String::new();
+        for row in indices.iter() {
+            if self.labels {
+                text.push_str(&format!("{} ┃ ", 8 - row));
+            }
+            for column in indices.iter() {
+                let coordinate: Coordinate = Coordinate::try_from((*row as u8, *column as u8)).unwrap();
+                let mark: Option<char> = arrow_marks
+                    .iter()
+                    .find(|(marked, _)| *marked == coordinate)
+                    .map(|(_, mark)| *mark);
+                text.push_str(&self.square(board, coordinate, mark, wide));
+            }
+            if self.colors {
+                text.push_str("\x1b[0m");
+            }
+            text.push('\n');
+        }
+
+        if self.labels {
+            text.push_str(&format!("  ┗{}\n    ", "━".repeat(8 * square_width)));
+            for column in indices.iter() {
+                let letter: char = (b'A' + *column as u8) as char;
+                text.push_str(&if wide { format!(" {} ", letter) } else { format!("{} ", letter) });
+            }
+        } else {
+            text.pop();
+        }
+        text
+    }
+
+    /// Draws a single square. Without colors, highlighted squares are drawn in brackets and squares that arrows point
+    /// to in parentheses.
+    fn square(&self, board: &Board, coordinate: Coordinate, mark: Option<char>, wide: bool) -> String {
+        let piece: Option<Piece> = board.get_piece(&coordinate);
+        let highlighted: bool = self.highlights.contains(&coordinate);
+        let arrow_target: bool = self.arrows.iter().any(|(_, to)| *to == coordinate);
+
+        let glyph: String = match (piece, mark) {
+            (Some(piece), _) if self.ascii => char::from(piece).to_string(),
+            // Colored pieces are all drawn with the filled glyphs, which are easier to see on a shaded square.
+            (Some(piece), _) if self.colors => Piece::new(piece.class(), Team::Black).to_string(),
+            (Some(piece), _) => piece.to_string(),
+            (None, Some(mark)) => mark.to_string(),
+            (None, None) if self.colors => " ".to_string(),
+            (None, None) => ".".to_string(),
+        };
+
+        if !wide {
+            return format!("{} ", glyph);
+        }
+        if !self.colors {
+            let (left, right) = match () {
+                _ if highlighted => ('[', ']'),
+                _ if arrow_target => ('(', ')'),
+                _ => (' ', ' '),
+            };
+            return format!("{}{}{}", left, glyph, right);
+        }
+
+        let background: u8 = match () {
+            _ if highlighted => HIGHLIGHTED_SQUARE,
+            _ if arrow_target => ARROW_SQUARE,
+            _ if (coordinate.row() + coordinate.column()).is_multiple_of(2) => LIGHT_SQUARE,
+            _ => DARK_SQUARE,
+        };
+        let foreground: u8 = match piece {
+            Some(piece) if piece.team() == Team::White => WHITE_PIECE,
+            Some(_) => BLACK_PIECE,
+            None => ARROW,
+        };
+        format!("\x1b[48;5;{}m\x1b[38;5;{};1m {} ", background, foreground, glyph)
+    }
+
+    /// The squares crossed by the arrows, with the direction of the arrow as it is seen on the screen.
+    fn arrow_marks(&self) -> Vec<(Coordinate, char)> {
+        let mut marks: Vec<(Coordinate, char)> = Vec::new();
+        for (from, to) in self.arrows.iter() {
+            let row_distance: i8 = to.row() as i8 - from.row() as i8;
+            let column_distance: i8 = to.column() as i8 - from.column() as i8;
+            let is_line: bool =
+                row_distance == 0 || column_distance == 0 || row_distance.abs() == column_distance.abs();
+            if (row_distance, column_distance) == (0, 0) || !is_line {
+                continue;
+            }
+
+            let (row_step, column_step) = (row_distance.signum(), column_distance.signum());
+            let (screen_row_step, screen_column_step) = match self.orientation {
+                Team::White => (row_step, column_step),
+                Team::Black => (-row_step, -column_step),
+            };
+            let mark: char = match (screen_row_step, screen_column_step) {
+                (-1, 0) => '↑',
+                (1, 0) => '↓',
+                (0, -1) => '←',
+                (0, 1) => '→',
+                (-1, -1) => '↖',
+                (-1, 1) => '↗',
+                (1, -1) => '↙',
+                _ => '↘',
+            };
+
+            let mut coordinate: Coordinate = *from;
+            while let Ok(next) = coordinate.checked_add_individual(row_step, column_step) {
+                marks.push((next, mark));
+                if next == *to {
+                    break;
+                }
+                coordinate = next;
+            }
+        }
+        marks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinate(name: &str) -> Coordinate {
+        Coordinate::try_from(name).unwrap()
+    }
+
+    /// Gets the line of the rendered board which shows the row with the number, when the board is drawn with labels.
+    fn row_line(text: &str, number: usize) -> &str {
+        text.lines().find(|line| line.starts_with(&format!("{} ┃", number))).unwrap()
+    }
+
+    #[test]
+    fn ascii_boards_are_drawn_with_the_letters_of_a_fen() {
+        let text: String = BoardRenderer::new().with_ascii(true).with_labels(false).render(&Board::new());
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "r n b q k b n r ");
+        assert_eq!(lines[4], ". . . . . . . . ");
+        assert_eq!(lines[7], "R N B Q K B N R ");
+    }
+
+    #[test]
+    fn boards_seen_from_black_are_turned_around() {
+        let text: String = BoardRenderer::new().with_orientation(Team::Black).with_ascii(true).render(&Board::new());
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], "1 ┃ R N B K Q B N R ");
+        assert_eq!(lines[7], "8 ┃ r n b k q b n r ");
+        assert_eq!(lines[9], "    H G F E D C B A ");
+    }
+
+    #[test]
+    fn highlighted_squares_are_drawn_in_brackets() {
+        let text: String = BoardRenderer::new().with_ascii(true).with_highlight(coordinate("e2")).render(&Board::new());
+
+        assert_eq!(row_line(&text, 2), "2 ┃  P  P  P  P [P] P  P  P ");
+        assert_eq!(row_line(&text, 3), "3 ┃  .  .  .  .  .  .  .  . ");
+        assert!(text.ends_with("  A  B  C  D  E  F  G  H "));
+    }
+
+    #[test]
+    fn arrows_mark_the_squares_they_cross_and_point_to() {
+        let renderer: BoardRenderer =
+            BoardRenderer::new().with_ascii(true).with_arrow(coordinate("e2"), coordinate("e4"));
+
+        let text: String = renderer.clone().render(&Board::new());
+        assert_eq!(row_line(&text, 3), "3 ┃  .  .  .  .  ↑  .  .  . ");
+        assert_eq!(row_line(&text, 4), "4 ┃  .  .  .  . (↑) .  .  . ");
+
+        // Highlights take the place of the parentheses, and the direction of the arrow turns with the board.
+        let text: String =
+            renderer.with_orientation(Team::Black).with_highlight(coordinate("e4")).render(&Board::new());
+        assert_eq!(row_line(&text, 3), "3 ┃  .  .  .  ↓  .  .  .  . ");
+        assert_eq!(row_line(&text, 4), "4 ┃  .  .  . [↓] .  .  .  . ");
+    }
+
+    #[test]
+    fn diagonal_arrows_point_along_the_diagonal_on_the_screen() {
+        let board: Board = Board::new();
+        let arrow = |team: Team| {
+            BoardRenderer::new()
+                .with_ascii(true)
+                .with_orientation(team)
+                .with_arrow(coordinate("c3"), coordinate("e5"))
+                .render(&board)
+        };
+
+        assert_eq!(row_line(&arrow(Team::White), 4), "4 ┃  .  .  .  ↗  .  .  .  . ");
+        assert_eq!(row_line(&arrow(Team::Black), 4), "4 ┃  .  .  .  .  ↙  .  .  . ");
+    }
+
+    #[test]
+    fn arrows_which_are_not_lines_only_mark_the_square_they_point_to() {
+        let text: String = BoardRenderer::new()
+            .with_ascii(true)
+            .with_arrow(coordinate("g1"), coordinate("f3"))
+            .render(&Board::new());
+
+        assert_eq!(row_line(&text, 3), "3 ┃  .  .  .  .  . (.) .  . ");
+        assert_eq!(row_line(&text, 2), "2 ┃  P  P  P  P  P  P  P  P ");
+    }
+
+    #[test]
+    fn colored_boards_shade_the_squares_and_reset_at_the_end_of_every_row() {
+        let text: String = BoardRenderer::new().with_colors(true).with_labels(false).render(&Board::new());
+
+        assert_eq!(text.lines().count(), 8);
+        assert!(text.lines().all(|line| line.ends_with("\x1b[0m")));
+        assert!(text.starts_with(&format!("\x1b[48;5;{}m\x1b[38;5;{};1m ♜ ", LIGHT_SQUARE, BLACK_PIECE)));
+    }
+}
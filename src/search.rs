@@ -0,0 +1,351 @@
+use crate::board::Board;
+use crate::moves::Move;
+use crate::variant::has_insufficient_material;
+use std::time::{Duration, Instant};
+
+mod evaluation;
+
+pub use evaluation::evaluate;
+
+/// The score of a position where the team to play checkmates right away. Mates further away score one less for every
+/// half move it takes to reach them.
+const MATE_SCORE: i32 = 30000;
+
+/// The deepest number of half moves from the root that the search reaches, including the quiescence search.
+const MAX_PLY: usize = 64;
+
+/// How many nodes are searched between checks of the time limit.
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+/// The score of a searched position from the point of view of the team to play.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Score {
+    Centipawns(i32),
+
+    /// The number of moves until the team to play checkmates, which is negative when the team to play is the one being
+    /// checkmated.
+    Mate(i32),
+}
+
+impl Score {
+    fn from_internal(score: i32) -> Self {
+        if score.abs() < MATE_SCORE - MAX_PLY as i32 {
+            return Score::Centipawns(score);
+        }
+        let moves: i32 = (MATE_SCORE - score.abs() + 1) / 2;
+        Score::Mate(if score > 0 { moves } else { -moves })
+    }
+}
+
+impl std::fmt::Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Score::Centipawns(centipawns) => write!(f, "cp {}", centipawns),
+            Score::Mate(moves) => write!(f, "mate {}", moves),
+        }
+    }
+}
+
+/// The limits that a search stops at. Searches without any limit go as deep as the search can reach.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchLimits {
+    depth: Option<u8>,
+    time: Option<Duration>,
+    nodes: Option<u64>,
+}
+
+impl SearchLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_depth(mut self, depth: u8) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    pub fn with_time(mut self, time: Duration) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    pub fn with_nodes(mut self, nodes: u64) -> Self {
+        self.nodes = Some(nodes);
+        self
+    }
+}
+
+/// The result of the deepest iteration of a search which was completed.
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    /// The best move found, which is `None` when the team to play has no legal moves.
+    pub best_move: Option<Move>,
+
+    pub score: Score,
+    pub depth: u8,
+    pub nodes: u64,
+    pub principal_variation: Vec<Move>,
+}
+
+/// Searches the position for the best move of the team to play with an alpha-beta search which goes one half move
+/// deeper on every iteration until it reaches a limit. Captures are searched until the position is quiet, and the
+/// moves are ordered by the principal variation of the previous iteration and then by the value of the captured piece.
+pub fn search(board: &Board, limits: &SearchLimits) -> SearchResult {
+    let mut searcher: Searcher = Searcher {
+        limits: *limits,
+        start: Instant::now(),
+        nodes: 0,
+        stopped: false,
+        principal_variations: vec![Vec::new(); MAX_PLY + 1],
+        previous_principal_variation: Vec::new(),
+    };
+
+    let legal_moves: Vec<Move> = board.legal_moves();
+    let mut result: SearchResult = SearchResult {
+        best_move: legal_moves.first().copied(),
+        score: Score::Centipawns(0),
+        depth: 0,
+        nodes: 0,
+        principal_variation: Vec::new(),
+    };
+    if legal_moves.is_empty() {
+        result.score = Score::from_internal(if board.is_in_check(board.turn_to_play()) { -MATE_SCORE } else { 0 });
+        return result;
+    }
+
+    let max_depth: u8 = limits.depth.unwrap_or(MAX_PLY as u8 / 2).min(MAX_PLY as u8 / 2);
+    for depth in 1..=max_depth {
+        let score: i32 = searcher.negamax(board, depth as i32, 0, -MATE_SCORE - 1, MATE_SCORE + 1);
+        if searcher.stopped {
+            break;
+        }
+
+        let principal_variation: Vec<Move> = searcher.principal_variations[0].clone();
+        result = SearchResult {
+            best_move: principal_variation.first().copied().or(result.best_move),
+            score: Score::from_internal(score),
+            depth,
+            nodes: searcher.nodes,
+            principal_variation: principal_variation.clone(),
+        };
+        searcher.previous_principal_variation = principal_variation;
+
+        // A forced mate found within the depth can not be improved on by searching deeper.
+        if matches!(result.score, Score::Mate(moves) if moves.unsigned_abs() * 2 - 1 <= depth as u32) {
+            break;
+        }
+        // The next iteration takes several times as long, so it is not started when it could not finish in time.
+        if limits.time.is_some_and(|time| searcher.start.elapsed() * 2 > time) {
+            break;
+        }
+    }
+
+    result.nodes = searcher.nodes;
+    result
+}
+
+/// The state of a running search.
+struct Searcher {
+    limits: SearchLimits,
+    start: Instant,
+    nodes: u64,
+    stopped: bool,
+
+    /// The best line found from each ply of the current line.
+    principal_variations: Vec<Vec<Move>>,
+
+    /// The best line found by the previous iteration, whose moves are searched first.
+    previous_principal_variation: Vec<Move>,
+}
+
+impl Searcher {
+    fn negamax(&mut self, board: &Board, depth: i32, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.principal_variations[ply].clear();
+        if self.should_stop() {
+            return 0;
+        }
+        if ply > 0 && (board.halfmove_clock() >= 100 || has_insufficient_material(board)) {
+            return 0;
+        }
+
+        let in_check: bool = board.is_in_check(board.turn_to_play());
+        // Positions in check are searched one half move deeper so that the search never stops in the middle of a mate.
+        let depth: i32 = if in_check && ply < MAX_PLY / 2 { depth + 1 } else { depth };
+        if depth <= 0 || ply >= MAX_PLY {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+
+        let mut moves: Vec<Move> = board.legal_moves();
+        if moves.is_empty() {
+            return if in_check { -MATE_SCORE + ply as i32 } else { 0 };
+        }
+        self.order_moves(board, &mut moves, ply);
+
+        for chess_move in moves.iter() {
+            let mut next_board: Board = board.clone();
+            next_board.apply_move(chess_move);
+            let score: i32 = -self.negamax(&next_board, depth - 1, ply + 1, -beta, -alpha);
+            if self.stopped {
+                return 0;
+            }
+
+            if score > alpha {
+                alpha = score;
+                let mut principal_variation: Vec<Move> = vec![*chess_move];
+                principal_variation.extend_from_slice(&self.principal_variations[ply + 1]);
+                self.principal_variations[ply] = principal_variation;
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        alpha
+    }
+
+    /// Searches only the captures and promotions which do not lose material, so that positions are only evaluated once
+    /// no pieces are hanging.
+    fn quiescence(&mut self, board: &Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+
+        let stand_pat: i32 = evaluate(board);
+        if stand_pat >= beta || ply >= MAX_PLY {
+            return stand_pat.min(beta);
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut moves: Vec<Move> = board
+            .legal_moves()
+            .into_iter()
+            .filter(|chess_move| {
+                (board.captured_coordinate(chess_move).is_some() || chess_move.promotion.is_some())
+                    && board.see_ge(chess_move, 0)
+            })
+            .collect();
+        moves.sort_by_key(|chess_move| -capture_order(board, chess_move));
+
+        for chess_move in moves.iter() {
+            let mut next_board: Board = board.clone();
+            next_board.apply_move(chess_move);
+            let score: i32 = -self.quiescence(&next_board, ply + 1, -beta, -alpha);
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+
+    /// Puts the move of the previous principal variation first when the current line follows it, then the captures by
+    /// the value of the captured piece and the attacker, then the remaining moves.
+    fn order_moves(&self, board: &Board, moves: &mut [Move], ply: usize) {
+        let history = board.history();
+        let follows_principal_variation: bool = ply < self.previous_principal_variation.len()
+            && history.len() >= ply
+            && history[history.len() - ply..]
+                .iter()
+                .zip(self.previous_principal_variation.iter())
+                .all(|(node, chess_move)| {
+                    node.from == chess_move.from && node.to == chess_move.to && node.promotion == chess_move.promotion
+                });
+        let principal_move: Option<Move> = follows_principal_variation.then(|| self.previous_principal_variation[ply]);
+
+        moves.sort_by_key(|chess_move| match principal_move == Some(*chess_move) {
+            true => i32::MIN,
+            false => -capture_order(board, chess_move),
+        });
+    }
+
+    fn should_stop(&mut self) -> bool {
+        self.nodes += 1;
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes) {
+            self.stopped = true;
+        }
+        if self.nodes.is_multiple_of(TIME_CHECK_INTERVAL)
+            && self.limits.time.is_some_and(|time| self.start.elapsed() >= time)
+        {
+            self.stopped = true;
+        }
+        self.stopped
+    }
+}
+
+/// Orders captures by the value of the captured piece first and the value of the capturing piece second, and puts
+/// promotions and captures before quiet moves.
+fn capture_order(board: &Board, chess_move: &Move) -> i32 {
+    let promotion: i32 = chess_move.promotion.map_or(0, |class| class.value());
+    let captured: i32 = match board.captured_coordinate(chess_move) {
+        Some(coordinate) => board.get_piece(&coordinate).map_or(0, |piece| piece.class().value()),
+        None if promotion == 0 => return 0,
+        None => 0,
+    };
+    let attacker: i32 = board.get_piece(&chess_move.from).map_or(0, |piece| piece.class().value());
+    10 * (captured + promotion) - attacker / 100 + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    fn board(fen: &str) -> Board {
+        Board::try_new_with_fen(Fen { state: fen.to_string() }).unwrap()
+    }
+
+    #[test]
+    fn mates_in_one_are_found() {
+        let board: Board = board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+        let result: SearchResult = search(&board, &SearchLimits::new().with_depth(4));
+
+        assert_eq!(board.uci(&result.best_move.unwrap()), "a1a8");
+        assert_eq!(result.score, Score::Mate(1));
+        assert_eq!(result.score.to_string(), "mate 1");
+        // The search stops as soon as the mate is found, without going to the depth it was given.
+        assert_eq!(result.depth, 1);
+    }
+
+    #[test]
+    fn mates_in_two_are_found() {
+        // The king takes the opposition so that the rook mates on the eighth row.
+        let board: Board = board("k7/8/2K5/8/8/8/8/6R1 w - - 0 1");
+        let result: SearchResult = search(&board, &SearchLimits::new().with_depth(6));
+
+        assert_eq!(result.score, Score::Mate(2));
+        assert_eq!(result.principal_variation.len(), 3);
+        assert_eq!(result.depth, 3);
+    }
+
+    #[test]
+    fn the_team_which_is_mated_knows_it() {
+        let board: Board = board("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1");
+        let result: SearchResult = search(&board, &SearchLimits::new().with_depth(3));
+
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, Score::Mate(0));
+    }
+
+    #[test]
+    fn searches_stop_at_their_limits() {
+        let board: Board = Board::new();
+
+        let result: SearchResult = search(&board, &SearchLimits::new().with_depth(3));
+        assert_eq!(result.depth, 3);
+        assert_eq!(result.principal_variation.len(), 3);
+
+        let result: SearchResult = search(&board, &SearchLimits::new().with_nodes(500));
+        assert!(result.nodes <= 500);
+        assert!(result.best_move.is_some());
+
+        let start: Instant = Instant::now();
+        let result: SearchResult = search(&board, &SearchLimits::new().with_time(Duration::from_millis(200)));
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(result.depth >= 1);
+        assert!(board.legal_moves().contains(&result.best_move.unwrap()));
+    }
+}
@@ -0,0 +1,261 @@
+//! Serde support, enabled by the `serde` feature.
+//!
+//! Values are written in the notations used elsewhere in the crate so that they stay stable and readable: coordinates
+//! as their names (e.g. `"e4"`), pieces as their FEN letters (e.g. `"N"` and `"n"`), piece classes as the lowercase
+//! letters used for promotions, and boards as the FEN of their starting position followed by the moves made from it in
+//! the UCI notation. Boards are rebuilt by replaying the moves, so a deserialized board has the same history and
+//! follows the same rules as the board that was serialized. The pieces passed to a board from the other board of
+//! Bughouse are written along with the number of moves after which they arrived, and are put back in the pockets at
+//! that point of the replay.
+
+use crate::board::{Board, Fen};
+use crate::coordinate::Coordinate;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+impl Serialize for Coordinate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string().to_lowercase())
+    }
+}
+
+impl<'de> Deserialize<'de> for Coordinate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name: String = String::deserialize(deserializer)?;
+        Coordinate::try_from(name.as_str()).map_err(|_| D::Error::custom(format!("invalid coordinate `{}`", name)))
+    }
+}
+
+impl Serialize for Piece {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(char::from(*self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Piece {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let letter: char = char::deserialize(deserializer)?;
+        Piece::try_from(letter).map_err(|_| D::Error::custom(format!("invalid piece `{}`", letter)))
+    }
+}
+
+impl Serialize for PieceClass {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let letter: char = char::from(Piece::new(*self, Team::Black));
+        serializer.serialize_char(letter)
+    }
+}
+
+impl<'de> Deserialize<'de> for PieceClass {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let letter: char = char::deserialize(deserializer)?;
+        match Piece::try_from(letter) {
+            Ok(piece) if letter.is_ascii_lowercase() => Ok(piece.class()),
+            _ => Err(D::Error::custom(format!("invalid piece class `{}`", letter))),
+        }
+    }
+}
+
+/// The form that a board is written in.
+#[derive(Serialize, Deserialize)]
+struct SerializedBoard {
+    variant: String,
+    chess960: bool,
+    starting_fen: Fen,
+    moves: Vec<String>,
+
+    /// The pieces added to the pockets from outside of the board, as in Bughouse.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    received: Vec<ReceivedPiece>,
+
+    /// The FEN of the current position, which is checked against the position reached by replaying the moves.
+    fen: Fen,
+}
+
+/// A piece added to a pocket after the number of moves given by `ply`.
+#[derive(Serialize, Deserialize)]
+struct ReceivedPiece {
+    ply: usize,
+    team: Team,
+    class: PieceClass,
+}
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedBoard {
+            variant: self.variant().name().to_string(),
+            chess960: self.is_chess960(),
+            starting_fen: self.starting_fen(),
+            moves: self
+                .history()
+                .iter()
+                .map(|node| {
+                    self.uci(&Move {
+                        from: node.from,
+                        to: node.to,
+                        promotion: node.promotion,
+                        drop: node.drop,
+                    })
+                })
+                .collect(),
+            received: self
+                .received_pieces()
+                .iter()
+                .map(|(ply, team, class)| ReceivedPiece { ply: *ply, team: *team, class: *class })
+                .collect(),
+            fen: self.fen(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let serialized: SerializedBoard = SerializedBoard::deserialize(deserializer)?;
+
+        let mut board: Board = Board::try_new_with_fen(serialized.starting_fen)
+            .map_err(|error| D::Error::custom(format!("invalid starting FEN: {:?}", error)))?;
+        board
+            .set_variant_by_name(&serialized.variant)
+            .map_err(|_| D::Error::custom(format!("unknown variant `{}`", serialized.variant)))?;
+        board.set_chess960(serialized.chess960);
+
+        for ply in 0..=serialized.moves.len() {
+            for piece in serialized.received.iter().filter(|piece| piece.ply == ply) {
+                if !board.add_to_pocket(piece.team, piece.class) {
+                    return Err(D::Error::custom("the pocket can not hold the received pieces"));
+                }
+            }
+            if let Some(uci) = serialized.moves.get(ply) {
+                board
+                    .parse_uci(uci)
+                    .and_then(|chess_move| board.make_move(&chess_move))
+                    .map_err(|error| D::Error::custom(format!("invalid move `{}`: {:?}", uci, error)))?;
+            }
+        }
+
+        if board.fen().state != serialized.fen.state {
+            return Err(D::Error::custom("the moves do not lead to the FEN of the board"));
+        }
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::STANDARD_CHESS960_INDEX;
+    use crate::variant::{Bughouse, BughouseGame, Crazyhouse};
+
+    /// Plays the moves, then writes the board to JSON and reads it back.
+    fn round_trip(mut board: Board, moves: &[&str]) -> (Board, Board) {
+        for uci in moves {
+            board.make_move(&board.parse_uci(uci).unwrap()).unwrap();
+        }
+        let json: String = serde_json::to_string(&board).unwrap();
+        let read: Board = serde_json::from_str(&json).unwrap();
+        (board, read)
+    }
+
+    fn assert_same_game(board: &Board, read: &Board) {
+        assert_eq!(read.fen().state, board.fen().state);
+        assert_eq!(read.starting_fen().state, board.starting_fen().state);
+        assert_eq!(read.history().len(), board.history().len());
+        assert_eq!(read.variant().name(), board.variant().name());
+        assert_eq!(read.is_chess960(), board.is_chess960());
+    }
+
+    #[test]
+    fn values_are_written_in_their_notations() {
+        let coordinate: Coordinate = Coordinate::try_from("e4").unwrap();
+        assert_eq!(serde_json::to_string(&coordinate).unwrap(), r#""e4""#);
+        assert_eq!(serde_json::from_str::<Coordinate>(r#""e4""#).unwrap(), coordinate);
+        assert!(serde_json::from_str::<Coordinate>(r#""é""#).is_err());
+
+        let piece: Piece = Piece::new(PieceClass::Knight, Team::Black);
+        assert_eq!(serde_json::to_string(&piece).unwrap(), r#""n""#);
+        assert_eq!(char::from(serde_json::from_str::<Piece>(r#""n""#).unwrap()), 'n');
+        assert_eq!(serde_json::to_string(&PieceClass::Queen).unwrap(), r#""q""#);
+        assert!(serde_json::from_str::<PieceClass>(r#""Q""#).is_err());
+    }
+
+    #[test]
+    fn standard_games_survive_a_round_trip() {
+        let moves = ["e2e4", "d7d5", "e4e5", "f7f5", "e5f6", "g8h6", "f6g7", "e8f7", "g7h8q", "b8c6", "e1e2"];
+        let (board, read) = round_trip(Board::new(), &moves);
+
+        assert_same_game(&board, &read);
+        assert!(read.history()[8].promotion.is_some());
+        assert_eq!(read.fen().state, "r1bq1b1Q/ppp1pk1p/2n4n/3p4/8/8/PPPPKPPP/RNBQ1BNR b - - 2 6");
+    }
+
+    #[test]
+    fn chess960_games_survive_a_round_trip() {
+        // Castling is written as the king capturing its own rook.
+        let moves = ["e2e4", "e7e5", "g1f3", "g8f6", "f1c4", "f8c5", "e1h1"];
+        let (board, read) = round_trip(Board::new_chess960(STANDARD_CHESS960_INDEX).unwrap(), &moves);
+
+        assert_same_game(&board, &read);
+        assert!(read.is_chess960());
+        assert_eq!(read.fen().board_pieces_state(), "rnbqk2r/pppp1ppp/5n2/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1");
+    }
+
+    #[test]
+    fn crazyhouse_games_survive_a_round_trip() {
+        let moves = ["e2e4", "d7d5", "e4d5", "d8d5", "P@e6", "d5e6"];
+        let (board, read) = round_trip(Board::new_with_variant(Crazyhouse), &moves);
+
+        assert_same_game(&board, &read);
+        assert_eq!(read.pocket(Team::Black).count(PieceClass::Pawn), 2);
+        assert!(read.history()[4].drop.is_some());
+    }
+
+    #[test]
+    fn bughouse_boards_keep_the_pieces_passed_to_them() {
+        let mut game: BughouseGame = BughouseGame::new();
+        for (index, uci) in [(1, "e2e4"), (0, "e2e4"), (0, "d7d5"), (0, "e4d5"), (1, "d7d6")] {
+            let board: &Board = game.board(index);
+            let chess_move: Move = board.parse_uci(uci).unwrap();
+            game.make_move(index, &chess_move).unwrap();
+        }
+        let mut board: Board = game.board(1).clone();
+        assert_eq!(board.pocket(Team::Black).count(PieceClass::Pawn), 1);
+        board.make_move(&board.parse_uci("e4e5").unwrap()).unwrap();
+        board.make_move(&board.parse_uci("P@e6").unwrap()).unwrap();
+
+        let (board, read) = round_trip(board, &[]);
+        assert_same_game(&board, &read);
+        assert_eq!(board.received_pieces(), [(1, Team::Black, PieceClass::Pawn)]);
+        assert_eq!(read.received_pieces(), board.received_pieces());
+    }
+
+    #[test]
+    fn pieces_passed_before_moves_which_are_taken_back_are_kept() {
+        let mut board: Board = Board::new_with_variant(Bughouse);
+        board.make_move(&board.parse_uci("e2e4").unwrap()).unwrap();
+        board.add_to_pocket(Team::White, PieceClass::Knight);
+        board.make_move(&board.parse_uci("e7e5").unwrap()).unwrap();
+        board.add_to_pocket(Team::Black, PieceClass::Bishop);
+
+        board.undo_move();
+        let (mut board, read) = round_trip(board, &["B@c5"]);
+        assert_same_game(&board, &read);
+
+        // Once every move is taken back the pieces are part of the position that the game starts from.
+        while board.undo_move().is_some() {}
+        assert_eq!(board.starting_fen().state, board.fen().state);
+        let (_, read) = round_trip(board, &["N@f3"]);
+        assert_eq!(read.fen().board_pieces_state(), "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKBNR[b]");
+    }
+
+    #[test]
+    fn boards_which_do_not_add_up_are_rejected() {
+        let json: String = serde_json::to_string(&round_trip(Board::new(), &["e2e4"]).0).unwrap();
+
+        assert!(serde_json::from_str::<Board>(&json.replace("e2e4", "e2e5")).is_err());
+        assert!(serde_json::from_str::<Board>(&json.replace("\"Standard\"", "\"Nonsense\"")).is_err());
+        assert!(serde_json::from_str::<Board>(&json.replace("4P3", "8")).is_err());
+    }
+}
@@ -0,0 +1,485 @@
+use crate::board::Board;
+use crate::coordinate::Coordinate;
+use crate::piece::{Piece, PieceClass, Team};
+use std::collections::HashMap;
+
+/// The size of the square that the pieces of a custom piece set are drawn in, which is the size used by most freely
+/// available SVG piece sets.
+pub const CUSTOM_PIECE_SIZE: f64 = 45.0;
+
+/// The colors of a board diagram, written as SVG colors such as `#f0d9b5` or `rgba(0, 0, 0, 0.5)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgTheme {
+    pub light_square: String,
+    pub dark_square: String,
+
+    /// The color drawn over the squares of the last move and the highlighted squares.
+    pub highlight: String,
+
+    /// The color of the glow around a king in check.
+    pub check: String,
+
+    pub arrow: String,
+    pub circle: String,
+}
+
+impl SvgTheme {
+    pub fn brown() -> Self {
+        Self::new("#f0d9b5", "#b58863", "rgba(155, 199, 0, 0.41)")
+    }
+
+    pub fn blue() -> Self {
+        Self::new("#dee3e6", "#8ca2ad", "rgba(155, 199, 0, 0.41)")
+    }
+
+    pub fn green() -> Self {
+        Self::new("#ffffdd", "#86a666", "rgba(20, 85, 30, 0.5)")
+    }
+
+    fn new(light_square: &str, dark_square: &str, highlight: &str) -> Self {
+        Self {
+            light_square: light_square.to_string(),
+            dark_square: dark_square.to_string(),
+            highlight: highlight.to_string(),
+            check: "#ff0000".to_string(),
+            arrow: "rgba(21, 120, 27, 0.8)".to_string(),
+            circle: "rgba(21, 120, 27, 0.8)".to_string(),
+        }
+    }
+}
+
+impl Default for SvgTheme {
+    fn default() -> Self {
+        Self::brown()
+    }
+}
+
+/// The way that pieces are drawn in a board diagram.
+#[derive(Debug, Clone, Default)]
+pub enum PieceSet {
+    /// The unicode chess glyphs, drawn as text in the font of the viewer.
+    #[default]
+    Glyphs,
+
+    /// The letters of a FEN in a disc of the color of the team, which need no font with chess glyphs.
+    Letters,
+
+    /// SVG fragments for every piece, drawn in a square of [`CUSTOM_PIECE_SIZE`] and scaled to the squares of the
+    /// board. Pieces without a fragment fall back to glyphs.
+    Custom(HashMap<(Team, PieceClass), String>),
+}
+
+/// Draws a board as an SVG diagram, with no other dependencies than a viewer which supports SVG.
+///
+/// By default, the diagram is 360 pixels wide with coordinates, seen from the side of white, with the last move in the
+/// history of the board highlighted and a glow around a king in check.
+#[derive(Debug, Clone)]
+pub struct SvgRenderer {
+    size: u32,
+    theme: SvgTheme,
+    piece_set: PieceSet,
+    coordinates: bool,
+    orientation: Team,
+    last_move: bool,
+    check: bool,
+    highlights: Vec<Coordinate>,
+    arrows: Vec<(Coordinate, Coordinate)>,
+    circles: Vec<Coordinate>,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        Self {
+            size: 360,
+            theme: SvgTheme::default(),
+            piece_set: PieceSet::default(),
+            coordinates: true,
+            orientation: Team::White,
+            last_move: true,
+            check: true,
+            highlights: Vec::new(),
+            arrows: Vec::new(),
+            circles: Vec::new(),
+        }
+    }
+}
+
+impl SvgRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the width and height of the diagram in pixels.
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: SvgTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_piece_set(mut self, piece_set: PieceSet) -> Self {
+        self.piece_set = piece_set;
+        self
+    }
+
+    /// Draws the numbers of the rows and the letters of the columns inside the squares at the edges of the board.
+    pub fn with_coordinates(mut self, coordinates: bool) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// Sets the team whose side of the board is drawn at the bottom.
+    pub fn with_orientation(mut self, team: Team) -> Self {
+        self.orientation = team;
+        self
+    }
+
+    /// Highlights the squares of the last move in the history of the board.
+    pub fn with_last_move(mut self, last_move: bool) -> Self {
+        self.last_move = last_move;
+        self
+    }
+
+    /// Draws a glow around the king of the team to play when it is in check.
+    pub fn with_check(mut self, check: bool) -> Self {
+        self.check = check;
+        self
+    }
+
+    pub fn with_highlight(mut self, coordinate: Coordinate) -> Self {
+        self.highlights.push(coordinate);
+        self
+    }
+
+    pub fn with_arrow(mut self, from: Coordinate, to: Coordinate) -> Self {
+        self.arrows.push((from, to));
+        self
+    }
+
+    pub fn with_circle(mut self, coordinate: Coordinate) -> Self {
+        self.circles.push(coordinate);
+        self
+    }
+
+    /// Draws the board as a standalone SVG document, which can also be embedded directly in HTML.
+    pub fn render(&self, board: &Board) -> String {
+        let square: f64 = self.size as f64 / 8.0;
+        let mut svg: String = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" width=\"{size}\" height=\"{size}\" \
+             viewBox=\"0 0 {size} {size}\">\n",
+            size = self.size
+        );
+
+        svg.push_str("<defs>\n");
+        svg.push_str(&format!(
+            "<radialGradient id=\"check\"><stop offset=\"0%\" stop-color=\"{color}\" stop-opacity=\"1\"/>\
+             <stop offset=\"50%\" stop-color=\"{color}\" stop-opacity=\"0.6\"/>\
+             <stop offset=\"100%\" stop-color=\"{color}\" stop-opacity=\"0\"/></radialGradient>\n",
+            color = escape(&self.theme.check)
+        ));
+        svg.push_str(&format!(
+            "<marker id=\"arrowhead\" viewBox=\"0 0 4 4\" refX=\"0\" refY=\"2\" markerWidth=\"3\" markerHeight=\"3\" \
+             orient=\"auto\"><path d=\"M0,0 L4,2 L0,4 z\" fill=\"{}\"/></marker>\n",
+            escape(&self.theme.arrow)
+        ));
+        svg.push_str("</defs>\n");
+
+        let mut highlights: Vec<Coordinate> = self.highlights.clone();
+        if let Some(node) = board.history().last().filter(|_| self.last_move) {
+            highlights.extend([node.from, node.to]);
+        }
+        let checked_king: Option<Coordinate> = match self.check && board.is_in_check(board.turn_to_play()) {
+            true => board.king_coordinate(board.turn_to_play()),
+            false => None,
+        };
+
+        for row in 0..8 {
+            for column in 0..8 {
+                let coordinate: Coordinate = Coordinate::try_from((row as u8, column as u8)).unwrap();
+                let (x, y) = self.position(coordinate);
+                let color: &str = match (row + column) % 2 {
+                    0 => &self.theme.light_square,
+                    _ => &self.theme.dark_square,
+                };
+                svg.push_str(&rectangle(x, y, square, color));
+                if highlights.contains(&coordinate) {
+                    svg.push_str(&rectangle(x, y, square, &self.theme.highlight));
+                }
+                if checked_king == Some(coordinate) {
+                    svg.push_str(&format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"url(#check)\"/>\n",
+                        x,
+                        y,
+                        s = square
+                    ));
+                }
+            }
+        }
+
+        if self.coordinates {
+            svg.push_str(&self.coordinate_labels(square));
+        }
+        for row in 0..8 {
+            for column in 0..8 {
+                let coordinate: Coordinate = Coordinate::try_from((row as u8, column as u8)).unwrap();
+                if let Some(piece) = board.get_piece(&coordinate) {
+                    let (x, y) = self.position(coordinate);
+                    svg.push_str(&self.piece(piece, x, y, square));
+                }
+            }
+        }
+
+        for coordinate in self.circles.iter() {
+            let (x, y) = self.position(*coordinate);
+            svg.push_str(&format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>\n",
+                x + square / 2.0,
+                y + square / 2.0,
+                square * 0.45,
+                escape(&self.theme.circle),
+                square / 16.0
+            ));
+        }
+        for (from, to) in self.arrows.iter() {
+            svg.push_str(&self.arrow(*from, *to, square));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// The position of the top left corner of the square on the diagram.
+    fn position(&self, coordinate: Coordinate) -> (f64, f64) {
+        let square: f64 = self.size as f64 / 8.0;
+        let (row, column) = match self.orientation {
+            Team::White => (coordinate.row(), coordinate.column()),
+            Team::Black => (7 - coordinate.row(), 7 - coordinate.column()),
+        };
+        (column as f64 * square, row as f64 * square)
+    }
+
+    /// The letters of the columns in the bottom row and the numbers of the rows in the right column, each written in
+    /// the color of the other kind of square so that it stands out.
+    fn coordinate_labels(&self, square: f64) -> String {
+        let font_size: f64 = square / 5.0;
+        let mut labels: String = String::new();
+        // The row at the bottom and the column on the right, which are the same index from either side.
+        let edge: usize = match self.orientation {
+            Team::White => 7,
+            Team::Black => 0,
+        };
+        for index in 0..8 {
+            let (row, column) = match self.orientation {
+                Team::White => (index, index),
+                Team::Black => (7 - index, 7 - index),
+            };
+            let offset: f64 = index as f64 * square;
+
+            let file_color: &str = self.label_color(edge, column);
+            labels.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" font-weight=\"bold\" \
+                 fill=\"{}\">{}</text>\n",
+                offset + square * 0.06,
+                self.size as f64 - square * 0.06,
+                font_size,
+                escape(file_color),
+                (b'a' + column as u8) as char
+            ));
+
+            let rank_color: &str = self.label_color(row, edge);
+            labels.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" font-weight=\"bold\" \
+                 text-anchor=\"end\" dominant-baseline=\"hanging\" fill=\"{}\">{}</text>\n",
+                self.size as f64 - square * 0.06,
+                offset + square * 0.06,
+                font_size,
+                escape(rank_color),
+                8 - row
+            ));
+        }
+        labels
+    }
+
+    /// The color of the other kind of square than the one at the row and column.
+    fn label_color(&self, row: usize, column: usize) -> &str {
+        match (row + column) % 2 {
+            0 => &self.theme.dark_square,
+            _ => &self.theme.light_square,
+        }
+    }
+
+    fn piece(&self, piece: Piece, x: f64, y: f64, square: f64) -> String {
+        let (fill, stroke) = match piece.team() {
+            Team::White => ("#ffffff", "#000000"),
+            Team::Black => ("#000000", "#ffffff"),
+        };
+        let (center_x, center_y) = (x + square / 2.0, y + square / 2.0);
+
+        match &self.piece_set {
+            PieceSet::Custom(pieces) if pieces.contains_key(&(piece.team(), piece.class())) => format!(
+                "<g transform=\"translate({} {}) scale({})\">{}</g>\n",
+                x,
+                y,
+                square / CUSTOM_PIECE_SIZE,
+                pieces[&(piece.team(), piece.class())]
+            ),
+            PieceSet::Letters => format!(
+                "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"{}\" fill=\"{fill}\" stroke=\"{stroke}\" stroke-width=\"{}\"/>\
+                 <text x=\"{cx}\" y=\"{cy}\" font-family=\"sans-serif\" font-size=\"{}\" font-weight=\"bold\" \
+                 text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{stroke}\">{}</text>\n",
+                square * 0.38,
+                square / 24.0,
+                square * 0.5,
+                char::from(piece).to_ascii_uppercase(),
+                cx = center_x,
+                cy = center_y,
+                fill = fill,
+                stroke = stroke
+            ),
+            // The filled glyphs are drawn for both teams and filled with the color of the team, which looks the same in
+            // every font unlike the outlined glyphs.
+            _ => format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" \
+                 fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\">{}</text>\n",
+                center_x,
+                center_y,
+                square * 0.8,
+                fill,
+                stroke,
+                square / 40.0,
+                Piece::new(piece.class(), Team::Black)
+            ),
+        }
+    }
+
+    /// Draws an arrow between the centers of the squares, stopping short of the center of the square it points to so
+    /// that the arrowhead does not cover the piece there.
+    fn arrow(&self, from: Coordinate, to: Coordinate, square: f64) -> String {
+        let (from_x, from_y) = self.position(from);
+        let (to_x, to_y) = self.position(to);
+        let (from_x, from_y) = (from_x + square / 2.0, from_y + square / 2.0);
+        let (to_x, to_y) = (to_x + square / 2.0, to_y + square / 2.0);
+
+        let length: f64 = ((to_x - from_x).powi(2) + (to_y - from_y).powi(2)).sqrt();
+        if length == 0.0 {
+            return String::new();
+        }
+        let width: f64 = square / 6.0;
+        // The arrowhead is three times as long as the line is wide and starts where the line ends.
+        let shortening: f64 = (square * 0.15 + width * 3.0).min(length / 2.0);
+        let end_x: f64 = to_x - (to_x - from_x) / length * shortening;
+        let end_y: f64 = to_y - (to_y - from_y) / length * shortening;
+
+        format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\" \
+             marker-end=\"url(#arrowhead)\"/>\n",
+            from_x,
+            from_y,
+            end_x,
+            end_y,
+            escape(&self.theme.arrow),
+            width
+        )
+    }
+}
+
+fn rectangle(x: f64, y: f64, size: f64, color: &str) -> String {
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"{}\"/>\n",
+        x,
+        y,
+        escape(color),
+        s = size
+    )
+}
+
+/// Escapes the characters which can not appear in an attribute of an SVG element.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    fn coordinate(name: &str) -> Coordinate {
+        Coordinate::try_from(name).unwrap()
+    }
+
+    /// Plays the fool's mate, which leaves the white king on e1 in check.
+    fn checkmated_board() -> Board {
+        let mut board: Board = Board::new();
+        for uci in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+            board.make_move(&board.parse_uci(uci).unwrap()).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    fn boards_seen_from_black_are_turned_around() {
+        let renderer: SvgRenderer = SvgRenderer::new().with_orientation(Team::Black);
+
+        assert_eq!(renderer.position(coordinate("a1")), (315.0, 0.0));
+        assert_eq!(renderer.position(coordinate("h8")), (0.0, 315.0));
+        assert_eq!(SvgRenderer::new().position(coordinate("a1")), (0.0, 315.0));
+
+        // The letters of the columns are written from h to a, and the numbers of the rows from 1 to 8.
+        let svg: String = renderer.render(&Board::new());
+        assert!(svg.find(">h</text>").unwrap() < svg.find(">a</text>").unwrap());
+        assert!(svg.find(">1</text>").unwrap() < svg.find(">8</text>").unwrap());
+        let svg: String = SvgRenderer::new().render(&Board::new());
+        assert!(svg.find(">a</text>").unwrap() < svg.find(">h</text>").unwrap());
+    }
+
+    #[test]
+    fn kings_in_check_glow() {
+        let glow: &str = "<rect x=\"180\" y=\"315\" width=\"45\" height=\"45\" fill=\"url(#check)\"/>";
+
+        assert!(SvgRenderer::new().render(&checkmated_board()).contains(glow));
+        assert!(!SvgRenderer::new().with_check(false).render(&checkmated_board()).contains("url(#check)"));
+        assert!(!SvgRenderer::new().render(&Board::new()).contains("url(#check)"));
+
+        let flipped: String = SvgRenderer::new().with_orientation(Team::Black).render(&checkmated_board());
+        assert!(flipped.contains("<rect x=\"135\" y=\"0\" width=\"45\" height=\"45\" fill=\"url(#check)\"/>"));
+    }
+
+    #[test]
+    fn the_last_move_is_highlighted() {
+        let mut board: Board = Board::new_with_fen(Fen { state: "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1".to_string() });
+        board.make_move(&board.parse_uci("e2e4").unwrap()).unwrap();
+        let highlight: String = format!("fill=\"{}\"", SvgTheme::brown().highlight);
+
+        assert_eq!(SvgRenderer::new().render(&board).matches(&highlight).count(), 2);
+        assert_eq!(SvgRenderer::new().with_last_move(false).render(&board).matches(&highlight).count(), 0);
+    }
+
+    #[test]
+    fn theme_colors_are_escaped() {
+        let theme: SvgTheme = SvgTheme {
+            light_square: "red\" onload=\"alert(1)".to_string(),
+            arrow: "<script>".to_string(),
+            check: "a&b".to_string(),
+            ..SvgTheme::blue()
+        };
+        let svg: String = SvgRenderer::new()
+            .with_theme(theme)
+            .with_arrow(coordinate("e2"), coordinate("e4"))
+            .render(&checkmated_board());
+
+        assert!(!svg.contains("onload=\""));
+        assert!(svg.contains("fill=\"red&quot; onload=&quot;alert(1)\""));
+        assert!(!svg.contains("<script>"));
+        assert!(svg.contains("stroke=\"&lt;script>\""));
+        assert!(svg.contains("stop-color=\"a&amp;b\""));
+    }
+
+    #[test]
+    fn escaping_replaces_ampersands_quotes_and_angle_brackets() {
+        assert_eq!(escape("rgba(0, 0, 0, 0.5)"), "rgba(0, 0, 0, 0.5)");
+        assert_eq!(escape("a & \"b\" <c>"), "a &amp; &quot;b&quot; &lt;c>");
+    }
+}
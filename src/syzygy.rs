@@ -0,0 +1,554 @@
+use crate::board::Board;
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+mod table;
+
+use table::{PieceCode, ProbePosition, Square, Table, TableKind, MAX_PIECES};
+
+/// The extensions of the win/draw/loss and distance to zeroing tables.
+const WDL_EXTENSION: &str = "rtbw";
+const DTZ_EXTENSION: &str = "rtbz";
+
+/// The result of a position with perfect play, from the point of view of the team to play. Cursed wins and blessed losses
+/// are wins and losses which can not be forced before the fifty move rule allows a draw to be claimed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Wdl {
+    Loss = -2,
+    BlessedLoss = -1,
+    Draw = 0,
+    CursedWin = 1,
+    Win = 2,
+}
+
+impl Wdl {
+    fn from_value(value: i32) -> Self {
+        match value {
+            i32::MIN..=-2 => Wdl::Loss,
+            -1 => Wdl::BlessedLoss,
+            0 => Wdl::Draw,
+            1 => Wdl::CursedWin,
+            _ => Wdl::Win,
+        }
+    }
+
+    /// The sign of the result, which is positive for wins and negative for losses.
+    fn signum(&self) -> i32 {
+        (*self as i32).signum()
+    }
+}
+
+impl std::ops::Neg for Wdl {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_value(-(self as i32))
+    }
+}
+
+/// A legal move of a position along with the result and the distance to zeroing that it leads to, from the point of view
+/// of the team making the move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TablebaseMove {
+    pub chess_move: Move,
+    pub wdl: Wdl,
+    pub dtz: i32,
+}
+
+/// Whether the best move of a searched position is known to reset the fifty move counter, in which case distance to
+/// zeroing tables hold no useful value for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProbeState {
+    Stored,
+    ZeroingBestMove,
+}
+
+/// A set of Syzygy endgame tablebases found in a local directory. The tables are loaded in memory the first time that a
+/// position with their material is probed.
+#[derive(Debug)]
+pub struct Tablebase {
+    directory: PathBuf,
+
+    /// The names of the tables found in the directory, such as `KRvK.rtbw`.
+    files: HashSet<String>,
+
+    /// The largest number of pieces of the tables in the directory.
+    max_pieces: usize,
+
+    tables: Mutex<HashMap<String, Arc<Table>>>,
+}
+
+impl Tablebase {
+    /// Finds the `.rtbw` and `.rtbz` tables in the directory.
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self, SyzygyError> {
+        let mut files: HashSet<String> = HashSet::new();
+        let mut max_pieces: usize = 0;
+
+        for entry in std::fs::read_dir(directory.as_ref())? {
+            let path: PathBuf = entry?.path();
+            let (stem, extension) = match (path.file_stem(), path.extension()) {
+                (Some(stem), Some(extension)) => (stem.to_string_lossy(), extension.to_string_lossy()),
+                _ => continue,
+            };
+            if extension != WDL_EXTENSION && extension != DTZ_EXTENSION {
+                continue;
+            }
+            if stem.len() <= MAX_PIECES + 1 && stem.split_once('v').is_some() {
+                max_pieces = max_pieces.max(stem.len() - 1);
+                files.insert(format!("{}.{}", stem, extension));
+            }
+        }
+
+        Ok(Self {
+            directory: directory.as_ref().to_path_buf(),
+            files,
+            max_pieces,
+            tables: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The largest number of pieces, kings included, that positions can have to be probed.
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    /// Gets the result of the position with perfect play for the team to play.
+    pub fn probe_wdl(&self, board: &Board) -> Result<Wdl, SyzygyError> {
+        self.check_board(board)?;
+        Ok(self.search(board, false)?.0)
+    }
+
+    /// Gets the distance to zeroing of the position in plies, which is the number of plies until the fifty move counter
+    /// is reset by a capture or a pawn move with perfect play. The distance is positive when the team to play wins,
+    /// negative when it loses, and zero for draws. It can be off by one ply, but is never too small for wins, so that
+    /// playing the moves which keep the lowest distance always wins.
+    pub fn probe_dtz(&self, board: &Board) -> Result<i32, SyzygyError> {
+        self.check_board(board)?;
+        self.dtz(board)
+    }
+
+    /// Gets all of the legal moves of the position with the result and the distance to zeroing that they lead to.
+    pub fn moves(&self, board: &Board) -> Result<Vec<TablebaseMove>, SyzygyError> {
+        self.check_board(board)?;
+
+        let mut tablebase_moves: Vec<TablebaseMove> = Vec::new();
+        for chess_move in board.legal_moves() {
+            let mut next_board: Board = board.clone();
+            next_board.apply_move(&chess_move);
+
+            let wdl: Wdl = -self.search(&next_board, false)?.0;
+            let mut dtz: i32 = if next_board.halfmove_clock() == 0 {
+                dtz_before_zeroing(wdl)
+            } else {
+                let dtz: i32 = -self.dtz(&next_board)?;
+                dtz + dtz.signum()
+            };
+
+            // A mating move resets nothing but ends the game right away.
+            if dtz == 2 && next_board.is_checkmate() {
+                dtz = 1;
+            }
+
+            tablebase_moves.push(TablebaseMove { chess_move, wdl, dtz });
+        }
+
+        Ok(tablebase_moves)
+    }
+
+    /// Gets the move which keeps the best result for the team to play. Wins which can be completed before the fifty move
+    /// rule are preferred, playing towards the lowest distance to zeroing, and losing positions are defended with the
+    /// move which delays the loss the longest. Returns `None` when the position has no legal moves.
+    pub fn best_move(&self, board: &Board) -> Result<Option<TablebaseMove>, SyzygyError> {
+        let halfmove_clock: i32 = board.halfmove_clock() as i32;
+
+        let rank = |tablebase_move: &TablebaseMove| -> i32 {
+            let dtz: i32 = tablebase_move.dtz;
+            if dtz > 0 {
+                if dtz + halfmove_clock <= 99 {
+                    1000
+                } else {
+                    1000 - (dtz + halfmove_clock)
+                }
+            } else if dtz < 0 {
+                if -dtz * 2 + halfmove_clock < 100 {
+                    -1000
+                } else {
+                    -1000 + (-dtz + halfmove_clock)
+                }
+            } else {
+                0
+            }
+        };
+
+        Ok(self
+            .moves(board)?
+            .into_iter()
+            .max_by_key(|tablebase_move| (rank(tablebase_move), -tablebase_move.dtz)))
+    }
+
+    /// Checks that the position can be found in the tables.
+    fn check_board(&self, board: &Board) -> Result<(), SyzygyError> {
+        if !board.castling_rights().is_empty() {
+            return Err(SyzygyError::CastlingRights);
+        }
+        if board.occupancy().count() as usize > self.max_pieces.max(2) {
+            return Err(SyzygyError::TooManyPieces);
+        }
+        Ok(())
+    }
+
+    /// Gets the result of the position by looking at the captures, and at the pawn moves too when `zeroing_moves` is set,
+    /// as well as the table. Tables store arbitrary values for positions whose best move is a capture, to improve their
+    /// compression, so the result of the position is the best of the captures and the stored value. Positions with an
+    /// en passant capture are not stored at all, which is also covered since en passant is a capture.
+    fn search(&self, board: &Board, zeroing_moves: bool) -> Result<(Wdl, ProbeState), SyzygyError> {
+        let legal_moves: Vec<Move> = board.legal_moves();
+        let mut best: Wdl = Wdl::Loss;
+        let mut searched: usize = 0;
+
+        for chess_move in legal_moves.iter() {
+            let is_capture: bool = board.captured_coordinate(chess_move).is_some();
+            let is_pawn_move: bool = matches!(
+                board.get_piece(&chess_move.from).map(|piece| piece.class()),
+                Some(PieceClass::Pawn)
+            );
+            if !is_capture && (!zeroing_moves || !is_pawn_move) {
+                continue;
+            }
+            searched += 1;
+
+            let mut next_board: Board = board.clone();
+            next_board.apply_move(chess_move);
+            let value: Wdl = -self.search(&next_board, false)?.0;
+
+            if value > best {
+                best = value;
+                if value >= Wdl::Win {
+                    return Ok((value, ProbeState::ZeroingBestMove));
+                }
+            }
+        }
+
+        // When every legal move has been searched the stored value is not needed, and may not even be right.
+        let searched_all: bool = searched > 0 && searched == legal_moves.len();
+        let value: Wdl = if searched_all {
+            best
+        } else {
+            self.probe_wdl_table(board)?
+        };
+
+        if best >= value {
+            let state: ProbeState = if best > Wdl::Draw || searched_all {
+                ProbeState::ZeroingBestMove
+            } else {
+                ProbeState::Stored
+            };
+            return Ok((best, state));
+        }
+
+        Ok((value, ProbeState::Stored))
+    }
+
+    /// Gets the distance to zeroing of the position, without checking the board first.
+    fn dtz(&self, board: &Board) -> Result<i32, SyzygyError> {
+        let (wdl, state) = self.search(board, true)?;
+
+        // Distance to zeroing tables do not store draws, nor positions where the best move resets the counter.
+        if wdl == Wdl::Draw {
+            return Ok(0);
+        }
+        if state == ProbeState::ZeroingBestMove {
+            return Ok(dtz_before_zeroing(wdl));
+        }
+
+        if let Some(dtz) = self.probe_dtz_table(board, wdl)? {
+            let cursed: bool = matches!(wdl, Wdl::CursedWin | Wdl::BlessedLoss);
+            return Ok((dtz + if cursed { 100 } else { 0 }) * wdl.signum());
+        }
+
+        // The table only stores the positions with the other team to play, so the distance is found from the moves.
+        let mut min_dtz: i32 = i32::MAX;
+        for chess_move in board.legal_moves() {
+            let is_zeroing: bool = board.captured_coordinate(&chess_move).is_some()
+                || matches!(
+                    board.get_piece(&chess_move.from).map(|piece| piece.class()),
+                    Some(PieceClass::Pawn)
+                );
+
+            let mut next_board: Board = board.clone();
+            next_board.apply_move(&chess_move);
+
+            let mut dtz: i32 = if is_zeroing {
+                -dtz_before_zeroing(self.search(&next_board, false)?.0)
+            } else {
+                -self.dtz(&next_board)?
+            };
+
+            if dtz == 1 && next_board.is_checkmate() {
+                min_dtz = 1;
+            }
+            if !is_zeroing {
+                dtz += dtz.signum();
+            }
+            if dtz < min_dtz && dtz.signum() == wdl.signum() {
+                min_dtz = dtz;
+            }
+        }
+
+        // Without legal moves the position is mate.
+        Ok(if min_dtz == i32::MAX { -1 } else { min_dtz })
+    }
+
+    /// Probes the win/draw/loss table of the material of the position.
+    fn probe_wdl_table(&self, board: &Board) -> Result<Wdl, SyzygyError> {
+        if board.occupancy().count() == 2 {
+            return Ok(Wdl::Draw);
+        }
+
+        let (table, position) = self.table(board, TableKind::Wdl)?;
+        match table.probe(&position, Wdl::Draw) {
+            Some(value) => Ok(Wdl::from_value(value)),
+            None => Err(SyzygyError::CorruptedTable(table_name(board))),
+        }
+    }
+
+    /// Probes the distance to zeroing table of the material of the position, which returns `None` when the table only
+    /// stores the positions with the other team to play.
+    fn probe_dtz_table(&self, board: &Board, wdl: Wdl) -> Result<Option<i32>, SyzygyError> {
+        if board.occupancy().count() == 2 {
+            return Ok(Some(0));
+        }
+
+        let (table, position) = self.table(board, TableKind::Dtz)?;
+        Ok(table.probe(&position, wdl))
+    }
+
+    /// Gets the table for the material of the position, loading it if needed, along with the position as seen by it.
+    /// Tables are only stored with the stronger team as white, so the teams are swapped for positions where black is
+    /// the stronger team.
+    fn table(&self, board: &Board, kind: TableKind) -> Result<(Arc<Table>, ProbePosition), SyzygyError> {
+        let extension: &str = match kind {
+            TableKind::Wdl => WDL_EXTENSION,
+            TableKind::Dtz => DTZ_EXTENSION,
+        };
+        let white: String = material(board, Team::White);
+        let black: String = material(board, Team::Black);
+
+        let (name, flipped) = if self.files.contains(&format!("{}v{}.{}", white, black, extension)) {
+            (format!("{}v{}", white, black), false)
+        } else if self.files.contains(&format!("{}v{}.{}", black, white, extension)) {
+            (format!("{}v{}", black, white), true)
+        } else {
+            return Err(SyzygyError::MissingTable(format!("{}v{}.{}", white, black, extension)));
+        };
+        let file_name: String = format!("{}.{}", name, extension);
+
+        let mut tables = self.tables.lock().unwrap();
+        let table: Arc<Table> = match tables.get(&file_name) {
+            Some(table) => Arc::clone(table),
+            None => {
+                let table: Arc<Table> = Arc::new(Table::open(&self.directory.join(&file_name), &name, kind)?);
+                tables.insert(file_name, Arc::clone(&table));
+                table
+            }
+        };
+
+        let mut pieces: Vec<(Square, PieceCode)> = Vec::new();
+        for (row_index, row) in board.map().iter().enumerate() {
+            for (column_index, item) in row.iter().enumerate() {
+                if let Some(piece) = item {
+                    pieces.push((8 * (7 - row_index) + column_index, piece_code(piece)));
+                }
+            }
+        }
+
+        let position = ProbePosition {
+            pieces,
+            white_to_play: matches!(board.turn_to_play(), Team::White),
+            flipped,
+        };
+        Ok((table, position))
+    }
+}
+
+/// The distance to zeroing of a position whose best move resets the fifty move counter.
+fn dtz_before_zeroing(wdl: Wdl) -> i32 {
+    match wdl {
+        Wdl::Win => 1,
+        Wdl::CursedWin => 101,
+        Wdl::Draw => 0,
+        Wdl::BlessedLoss => -101,
+        Wdl::Loss => -1,
+    }
+}
+
+/// Writes the pieces of a team the way they are written in the names of the tables, such as `KRP`.
+fn material(board: &Board, team: Team) -> String {
+    let mut material: String = String::new();
+    for (class, letter) in [
+        (PieceClass::King, 'K'),
+        (PieceClass::Queen, 'Q'),
+        (PieceClass::Rook, 'R'),
+        (PieceClass::Bishop, 'B'),
+        (PieceClass::Knight, 'N'),
+        (PieceClass::Pawn, 'P'),
+    ] {
+        let count: usize = board
+            .map()
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|piece| piece.team() == team && piece.class() == class)
+            .count();
+        material.extend(std::iter::repeat_n(letter, count));
+    }
+    material
+}
+
+/// The name of the table for the material of the position with white first.
+fn table_name(board: &Board) -> String {
+    format!("{}v{}", material(board, Team::White), material(board, Team::Black))
+}
+
+/// The code of the piece in the tables.
+fn piece_code(piece: &Piece) -> PieceCode {
+    let class_code: PieceCode = match piece.class() {
+        PieceClass::Pawn => 1,
+        PieceClass::Knight => 2,
+        PieceClass::Bishop => 3,
+        PieceClass::Rook => 4,
+        PieceClass::Queen => 5,
+        PieceClass::King => 6,
+    };
+    match piece.team() {
+        Team::White => class_code,
+        Team::Black => class_code + 8,
+    }
+}
+
+/// Represents an error encountered when probing the tablebases.
+#[derive(Debug)]
+pub enum SyzygyError {
+    Io(std::io::Error),
+    MissingTable(String),
+    CorruptedTable(String),
+    CastlingRights,
+    TooManyPieces,
+}
+
+impl From<std::io::Error> for SyzygyError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    fn board(fen: &str) -> Board {
+        Board::try_new_with_fen(Fen { state: fen.to_string() }).unwrap()
+    }
+
+    /// A tablebase in a directory with only an empty KRvK table, which lets positions of up to three pieces be probed
+    /// but can only resolve the ones which do not need to look at a table. Tables are only read when they are probed,
+    /// so the directory is removed as soon as the tablebase is opened.
+    fn empty_tablebase(name: &str) -> Tablebase {
+        let directory: PathBuf = std::env::temp_dir().join(format!("syzygy-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("KRvK.rtbw"), []).unwrap();
+        let tablebase: Tablebase = Tablebase::open(&directory).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+        tablebase
+    }
+
+    /// The tablebase in the directory of the `SYZYGY_PATH` environment variable, which must hold the tables of KQvK.
+    /// The tests which need the tables are ignored unless they are asked for with `cargo test -- --ignored`.
+    fn local_tablebase() -> Tablebase {
+        let path = std::env::var_os("SYZYGY_PATH").expect("SYZYGY_PATH must be set to the directory of the tables");
+        Tablebase::open(path).unwrap()
+    }
+
+    #[test]
+    fn negating_a_result_swaps_the_teams() {
+        assert_eq!(-Wdl::Win, Wdl::Loss);
+        assert_eq!(-Wdl::CursedWin, Wdl::BlessedLoss);
+        assert_eq!(-Wdl::Draw, Wdl::Draw);
+    }
+
+    #[test]
+    fn tables_are_named_after_the_material_of_both_teams() {
+        assert_eq!(table_name(&board("8/8/8/8/8/2k5/1p6/KR3N2 w - - 0 1")), "KRNvKP");
+    }
+
+    #[test]
+    fn bare_kings_are_drawn_without_a_table() {
+        let tablebase: Tablebase = empty_tablebase("bare-kings");
+        let board: Board = board("8/8/3k4/8/8/4K3/8/8 w - - 0 1");
+
+        assert_eq!(tablebase.probe_wdl(&board).unwrap(), Wdl::Draw);
+        assert_eq!(tablebase.probe_dtz(&board).unwrap(), 0);
+    }
+
+    #[test]
+    fn positions_with_only_captures_are_resolved_by_searching_them() {
+        // The only legal move of the black king is to capture the undefended queen, which leaves bare kings.
+        let tablebase: Tablebase = empty_tablebase("only-captures");
+        let board: Board = board("k7/1Q6/8/8/8/8/8/7K b - - 0 1");
+
+        assert_eq!(tablebase.probe_wdl(&board).unwrap(), Wdl::Draw);
+        assert_eq!(tablebase.probe_dtz(&board).unwrap(), 0);
+    }
+
+    #[test]
+    fn positions_which_can_not_be_probed_are_errors() {
+        let tablebase: Tablebase = empty_tablebase("errors");
+
+        assert!(matches!(
+            tablebase.probe_wdl(&board("4k3/8/8/8/8/8/8/3QK3 w - - 0 1")),
+            Err(SyzygyError::MissingTable(name)) if name == "KQvK.rtbw"
+        ));
+        assert!(matches!(
+            tablebase.probe_wdl(&board("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1")),
+            Err(SyzygyError::CastlingRights)
+        ));
+        assert!(matches!(
+            tablebase.probe_wdl(&board("4k3/8/8/8/8/8/8/R3K2R w - - 0 1")),
+            Err(SyzygyError::TooManyPieces)
+        ));
+    }
+
+    #[test]
+    #[ignore = "needs SYZYGY_PATH"]
+    fn king_and_queen_win_against_a_lone_king() {
+        let tablebase: Tablebase = local_tablebase();
+
+        assert_eq!(tablebase.probe_wdl(&board("4k3/8/8/8/8/8/8/3QK3 w - - 0 1")).unwrap(), Wdl::Win);
+        assert_eq!(tablebase.probe_wdl(&board("4k3/8/8/8/8/8/8/3QK3 b - - 0 1")).unwrap(), Wdl::Loss);
+        assert!(tablebase.probe_dtz(&board("4k3/8/8/8/8/8/8/3QK3 w - - 0 1")).unwrap() > 0);
+        assert!(tablebase.probe_dtz(&board("4k3/8/8/8/8/8/8/3QK3 b - - 0 1")).unwrap() < 0);
+
+        // The black king can capture the queen, which is not defended by the white king.
+        assert_eq!(tablebase.probe_wdl(&board("3Qk3/8/8/8/8/8/8/7K b - - 0 1")).unwrap(), Wdl::Draw);
+    }
+
+    #[test]
+    #[ignore = "needs SYZYGY_PATH"]
+    fn the_best_move_of_a_mate_in_one_is_the_mate() {
+        let tablebase: Tablebase = local_tablebase();
+        let board: Board = board("6k1/8/6K1/8/8/8/8/Q7 w - - 0 1");
+
+        let best_move: TablebaseMove = tablebase.best_move(&board).unwrap().unwrap();
+        assert_eq!(best_move.wdl, Wdl::Win);
+        assert_eq!(best_move.dtz, 1);
+        assert_eq!(tablebase.probe_dtz(&board).unwrap(), 1);
+
+        let mut next_board: Board = board.clone();
+        next_board.make_move(&best_move.chess_move).unwrap();
+        assert!(next_board.is_checkmate());
+    }
+}
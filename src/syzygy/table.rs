@@ -0,0 +1,844 @@
+use super::{SyzygyError, Wdl};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// The magic numbers at the start of the win/draw/loss and distance to zeroing tables.
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+/// The maximum number of pieces on the board that tables exist for.
+pub(super) const MAX_PIECES: usize = 7;
+
+/// The flags of the pairs data of a table.
+const FLAG_STM: u8 = 1;
+const FLAG_MAPPED: u8 = 2;
+const FLAG_WIN_PLIES: u8 = 4;
+const FLAG_LOSS_PLIES: u8 = 8;
+const FLAG_WIDE: u8 = 16;
+const FLAG_SINGLE_VALUE: u8 = 128;
+
+/// Squares are numbered the way the tables number them, from 0 for a1 to 63 for h8, and pieces are coded with 1 to 6
+/// for the white pawn to the white king and the same plus 8 for the black pieces.
+pub(super) type Square = usize;
+pub(super) type PieceCode = u8;
+
+fn rank(square: Square) -> usize {
+    square >> 3
+}
+
+fn file(square: Square) -> usize {
+    square & 7
+}
+
+/// The signed distance of the square from the a1-h8 diagonal, negative below it.
+fn off_diagonal(square: Square) -> i32 {
+    rank(square) as i32 - file(square) as i32
+}
+
+/// The lookup tables used to turn the squares of the pieces into the index of the position in a table.
+struct Encoding {
+    /// Maps the squares a2 to h7 to the number of squares left to the other pawns when the leading pawn is there.
+    map_pawns: [usize; 64],
+
+    /// Maps the squares below the a1-h8 diagonal to 0 to 27.
+    map_b1h1h7: [usize; 64],
+
+    /// Maps the squares of the a1-d1-d4 triangle to 0 to 9, with the squares on the diagonal last.
+    map_a1d1d4: [usize; 64],
+
+    /// Maps the legal placements of the two kings, with the first one in the a1-d1-d4 triangle, to 0 to 461.
+    map_kk: [[usize; 64]; 10],
+
+    /// The binomial coefficients, which are the number of ways to choose `k` out of `n` squares.
+    binomial: [[u64; 64]; 6],
+
+    /// The index of the leading pawns when the first of them is on a given square.
+    lead_pawn_index: [[u64; 64]; 6],
+
+    /// The number of placements of the leading pawns when the first of them is on a given file.
+    lead_pawns_size: [[u64; 4]; 6],
+}
+
+impl Encoding {
+    // The loops go over square numbers which are used in the arithmetic of the encoding, not only as indices.
+    #[allow(clippy::needless_range_loop)]
+    fn new() -> Self {
+        let mut map_b1h1h7: [usize; 64] = [0; 64];
+        let mut code: usize = 0;
+        for square in 0..64 {
+            if off_diagonal(square) < 0 {
+                map_b1h1h7[square] = code;
+                code += 1;
+            }
+        }
+
+        let mut map_a1d1d4: [usize; 64] = [0; 64];
+        let mut diagonal: Vec<Square> = Vec::new();
+        code = 0;
+        for square in 0..=27 {
+            if off_diagonal(square) < 0 && file(square) <= 3 {
+                map_a1d1d4[square] = code;
+                code += 1;
+            } else if off_diagonal(square) == 0 && file(square) <= 3 {
+                diagonal.push(square);
+            }
+        }
+        for square in diagonal {
+            map_a1d1d4[square] = code;
+            code += 1;
+        }
+
+        // Placements with both kings on the diagonal are numbered last, and when the first king is on the diagonal the
+        // second one is never above it.
+        let mut map_kk: [[usize; 64]; 10] = [[0; 64]; 10];
+        let mut both_on_diagonal: Vec<(usize, Square)> = Vec::new();
+        code = 0;
+        for index in 0..10 {
+            for first in 0..=27 {
+                // The b1 square is mapped to zero, as are the squares outside of the triangle.
+                if map_a1d1d4[first] != index || (index == 0 && first != 1) {
+                    continue;
+                }
+                for second in 0..64 {
+                    let adjacent: bool =
+                        rank(first).abs_diff(rank(second)) <= 1 && file(first).abs_diff(file(second)) <= 1;
+                    if adjacent || (off_diagonal(first) == 0 && off_diagonal(second) > 0) {
+                        continue;
+                    } else if off_diagonal(first) == 0 && off_diagonal(second) == 0 {
+                        both_on_diagonal.push((index, second));
+                    } else {
+                        map_kk[index][second] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+        for (index, second) in both_on_diagonal {
+            map_kk[index][second] = code;
+            code += 1;
+        }
+
+        let mut binomial: [[u64; 64]; 6] = [[0; 64]; 6];
+        binomial[0][0] = 1;
+        for n in 1..64 {
+            for k in 0..6.min(n + 1) {
+                binomial[k][n] = if k > 0 { binomial[k - 1][n - 1] } else { 0 } + if k < n { binomial[k][n - 1] } else { 0 };
+            }
+        }
+
+        // The squares of the pawns are numbered from the edge files inwards and from the second rank upwards, so that
+        // the leading pawn is the one with the highest number.
+        let mut map_pawns: [usize; 64] = [0; 64];
+        let mut lead_pawn_index: [[u64; 64]; 6] = [[0; 64]; 6];
+        let mut lead_pawns_size: [[u64; 4]; 6] = [[0; 4]; 6];
+        let mut available_squares: i32 = 47;
+        for lead_pawns_count in 1..=5 {
+            for file in 0..4 {
+                let mut index: u64 = 0;
+                for rank in 1..=6 {
+                    let square: Square = 8 * rank + file;
+                    if lead_pawns_count == 1 {
+                        map_pawns[square] = available_squares as usize;
+                        map_pawns[square ^ 7] = (available_squares - 1) as usize;
+                        available_squares -= 2;
+                    }
+                    lead_pawn_index[lead_pawns_count][square] = index;
+                    index += binomial[lead_pawns_count - 1][map_pawns[square]];
+                }
+                lead_pawns_size[lead_pawns_count][file] = index;
+            }
+        }
+
+        Self {
+            map_pawns,
+            map_b1h1h7,
+            map_a1d1d4,
+            map_kk,
+            binomial,
+            lead_pawn_index,
+            lead_pawns_size,
+        }
+    }
+}
+
+/// Gets the encoding lookup tables, which are computed the first time that they are needed.
+fn encoding() -> &'static Encoding {
+    static ENCODING: OnceLock<Encoding> = OnceLock::new();
+    ENCODING.get_or_init(Encoding::new)
+}
+
+/// The kind of information stored in a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TableKind {
+    /// A `.rtbw` file with the win/draw/loss value of the positions.
+    Wdl,
+
+    /// A `.rtbz` file with the distance to zeroing the fifty move counter of the positions.
+    Dtz,
+}
+
+/// The decompression data of one of the sub-tables of a table. Tables with pawns have a sub-table for every file that
+/// the leading pawn can be on, and win/draw/loss tables of unequal material have one for each team to play.
+#[derive(Debug, Clone, Default)]
+struct PairsData {
+    flags: u8,
+    min_symbol_length: usize,
+    block_size: usize,
+    span: u64,
+    blocks: usize,
+
+    /// The offsets into the file of the lowest symbol of every length, the pairs of every symbol, the number of values in
+    /// every block, the sparse index into the blocks, and the compressed data.
+    lowest_symbol: usize,
+    btree: usize,
+    block_length: usize,
+    block_length_size: usize,
+    sparse_index: usize,
+    sparse_index_size: usize,
+    data: usize,
+
+    /// The lowest symbol of every length, left aligned to 64 bits.
+    base64: Vec<u64>,
+
+    /// The number of values, minus one, that every symbol expands to.
+    symbol_length: Vec<u32>,
+
+    /// The pieces of the table in the order that they are encoded.
+    pieces: [PieceCode; MAX_PIECES],
+
+    /// The pieces are encoded in groups of pieces of the same kind, and each group is multiplied by its factor.
+    group_index: [u64; MAX_PIECES + 1],
+    group_length: [usize; MAX_PIECES + 1],
+
+    /// The offsets into the value map of distance to zeroing tables for each of the win/draw/loss values.
+    map_index: [usize; 4],
+}
+
+/// A tablebase file for a single material configuration, such as `KRvK`, loaded in memory.
+#[derive(Debug)]
+pub(super) struct Table {
+    kind: TableKind,
+    bytes: Vec<u8>,
+
+    /// Whether both teams have the same pieces, in which case only the positions with white to play are stored.
+    symmetric: bool,
+    piece_count: usize,
+    has_pawns: bool,
+    has_unique_pieces: bool,
+
+    /// The number of pawns of the leading team and of the other team.
+    pawn_count: [usize; 2],
+
+    /// The sub-tables indexed by the team to play and then by the file of the leading pawn.
+    items: Vec<Vec<PairsData>>,
+
+    /// The offset of the value map of distance to zeroing tables.
+    map: usize,
+}
+
+/// A position prepared for probing, with the squares and pieces as seen from the stronger team of the table.
+pub(super) struct ProbePosition {
+    /// The squares and codes of the pieces of the position.
+    pub pieces: Vec<(Square, PieceCode)>,
+
+    /// Whether white is to play.
+    pub white_to_play: bool,
+
+    /// Whether the teams have to be swapped so that the position matches the material of the table.
+    pub flipped: bool,
+}
+
+impl Table {
+    /// Loads a table from a file. The material of the table is given by its name, such as `KRvK`.
+    pub(super) fn open(path: &Path, name: &str, kind: TableKind) -> Result<Self, SyzygyError> {
+        let bytes: Vec<u8> = std::fs::read(path)?;
+        let corrupted = || SyzygyError::CorruptedTable(name.to_string());
+
+        let magic: [u8; 4] = match kind {
+            TableKind::Wdl => WDL_MAGIC,
+            TableKind::Dtz => DTZ_MAGIC,
+        };
+        if bytes.len() % 64 != 16 || bytes[0..4] != magic {
+            return Err(corrupted());
+        }
+
+        let (white, black) = name.split_once('v').ok_or_else(corrupted)?;
+        let count = |side: &str, letter: char| side.chars().filter(|character| *character == letter).count();
+        let has_unique_pieces: bool = [white, black]
+            .iter()
+            .any(|side| "QRBNP".chars().any(|letter| count(side, letter) == 1));
+
+        // The leading team is the one with fewer pawns, as long as it has any.
+        let white_pawns: usize = count(white, 'P');
+        let black_pawns: usize = count(black, 'P');
+        let white_leads: bool = black_pawns == 0 || (white_pawns > 0 && black_pawns >= white_pawns);
+        let pawn_count: [usize; 2] = if white_leads {
+            [white_pawns, black_pawns]
+        } else {
+            [black_pawns, white_pawns]
+        };
+
+        let mut table = Self {
+            kind,
+            bytes,
+            symmetric: white == black,
+            piece_count: white.len() + black.len(),
+            has_pawns: white_pawns + black_pawns > 0,
+            has_unique_pieces,
+            pawn_count,
+            items: Vec::new(),
+            map: 0,
+        };
+        table.read_header(encoding()).ok_or_else(corrupted)?;
+
+        Ok(table)
+    }
+
+    /// Reads the piece order, grouping, and decompression data of every sub-table. Returns `None` when the file ends
+    /// before the data does.
+    fn read_header(&mut self, encoding: &Encoding) -> Option<()> {
+        const SPLIT: u8 = 1;
+        const HAS_PAWNS: u8 = 2;
+
+        let mut offset: usize = 4;
+        let flags: u8 = *self.bytes.get(offset)?;
+        if (flags & HAS_PAWNS != 0) != self.has_pawns || (flags & SPLIT != 0) == self.symmetric {
+            return None;
+        }
+        offset += 1;
+
+        let sides: usize = if self.kind == TableKind::Wdl && !self.symmetric { 2 } else { 1 };
+        let files: usize = if self.has_pawns { 4 } else { 1 };
+        let both_pawns: bool = self.has_pawns && self.pawn_count[1] > 0;
+        self.items = vec![vec![PairsData::default(); files]; sides];
+
+        for file in 0..files {
+            let first: u8 = *self.bytes.get(offset)?;
+            let second: u8 = if both_pawns { *self.bytes.get(offset + 1)? } else { 0xFF };
+            let orders: [[usize; 2]; 2] = [
+                [(first & 0xF) as usize, (second & 0xF) as usize],
+                [(first >> 4) as usize, (second >> 4) as usize],
+            ];
+            offset += 1 + both_pawns as usize;
+
+            for index in 0..self.piece_count {
+                let byte: u8 = *self.bytes.get(offset)?;
+                for side in 0..sides {
+                    self.items[side][file].pieces[index] = if side == 0 { byte & 0xF } else { byte >> 4 };
+                }
+                offset += 1;
+            }
+
+            for (side, order) in orders.iter().enumerate().take(sides) {
+                self.set_groups(encoding, side, file, order);
+            }
+        }
+        offset += offset & 1;
+
+        for file in 0..files {
+            for side in 0..sides {
+                offset = self.set_sizes(side, file, offset)?;
+            }
+        }
+
+        if self.kind == TableKind::Dtz {
+            offset = self.set_dtz_map(files, offset)?;
+        }
+
+        for file in 0..files {
+            for side in 0..sides {
+                let item: &mut PairsData = &mut self.items[side][file];
+                item.sparse_index = offset;
+                offset += item.sparse_index_size * 6;
+            }
+        }
+        for file in 0..files {
+            for side in 0..sides {
+                let item: &mut PairsData = &mut self.items[side][file];
+                item.block_length = offset;
+                offset += item.block_length_size * 2;
+            }
+        }
+        for file in 0..files {
+            for side in 0..sides {
+                offset = (offset + 0x3F) & !0x3F;
+                let item: &mut PairsData = &mut self.items[side][file];
+                item.data = offset;
+                offset += item.blocks * item.block_size;
+
+                // Sub-tables with a single value have no blocks, and their data may start after the end of the file.
+                if item.blocks > 0 && offset > self.bytes.len() {
+                    return None;
+                }
+            }
+        }
+
+        Some(())
+    }
+
+    /// Splits the pieces of a sub-table into the groups which are encoded together, and computes the factor that each
+    /// group is multiplied by in the order given by the table. The leading group is made of the leading pawns, of three
+    /// unique pieces, or of the two kings, and the other groups are made of pieces of the same kind.
+    fn set_groups(&mut self, encoding: &Encoding, side: usize, file: usize, order: &[usize; 2]) {
+        let has_pawns: bool = self.has_pawns;
+        let has_unique_pieces: bool = self.has_unique_pieces;
+        let piece_count: usize = self.piece_count;
+        let both_pawns: bool = has_pawns && self.pawn_count[1] > 0;
+        let item: &mut PairsData = &mut self.items[side][file];
+
+        let mut first_length: i32 = if has_pawns {
+            0
+        } else if has_unique_pieces {
+            3
+        } else {
+            2
+        };
+        let mut groups: usize = 0;
+        item.group_length[0] = 1;
+        for index in 1..piece_count {
+            first_length -= 1;
+            if first_length > 0 || item.pieces[index] == item.pieces[index - 1] {
+                item.group_length[groups] += 1;
+            } else {
+                groups += 1;
+                item.group_length[groups] = 1;
+            }
+        }
+        groups += 1;
+        item.group_length[groups] = 0;
+
+        let mut next: usize = if both_pawns { 2 } else { 1 };
+        let mut free_squares: usize = 64 - item.group_length[0] - if both_pawns { item.group_length[1] } else { 0 };
+        let mut index: u64 = 1;
+        let mut k: usize = 0;
+        while next < groups || k == order[0] || k == order[1] {
+            if k == order[0] {
+                item.group_index[0] = index;
+                index *= if has_pawns {
+                    encoding.lead_pawns_size[item.group_length[0]][file]
+                } else if has_unique_pieces {
+                    31332
+                } else {
+                    462
+                };
+            } else if k == order[1] {
+                item.group_index[1] = index;
+                index *= encoding.binomial[item.group_length[1]][48 - item.group_length[0]];
+            } else {
+                item.group_index[next] = index;
+                index *= encoding.binomial[item.group_length[next]][free_squares];
+                free_squares -= item.group_length[next];
+                next += 1;
+            }
+            k += 1;
+        }
+        item.group_index[groups] = index;
+    }
+
+    /// Reads the sizes and the Huffman code of a sub-table, returning the offset after them.
+    fn set_sizes(&mut self, side: usize, file: usize, mut offset: usize) -> Option<usize> {
+        let bytes: &[u8] = &self.bytes;
+        let item: &mut PairsData = &mut self.items[side][file];
+
+        item.flags = *bytes.get(offset)?;
+        offset += 1;
+        if item.flags & FLAG_SINGLE_VALUE != 0 {
+            // The single value of the sub-table is stored in place of the minimum symbol length.
+            item.min_symbol_length = *bytes.get(offset)? as usize;
+            return Some(offset + 1);
+        }
+
+        let groups: usize = item.group_length.iter().position(|length| *length == 0)?;
+        let size: u64 = item.group_index[groups];
+
+        item.block_size = 1 << *bytes.get(offset)?;
+        item.span = 1 << *bytes.get(offset + 1)?;
+        item.sparse_index_size = size.div_ceil(item.span) as usize;
+        let padding: usize = *bytes.get(offset + 2)? as usize;
+        item.blocks = read_u32_le(bytes, offset + 3)? as usize;
+        item.block_length_size = item.blocks + padding;
+        let max_symbol_length: usize = *bytes.get(offset + 7)? as usize;
+        item.min_symbol_length = *bytes.get(offset + 8)? as usize;
+        offset += 9;
+        item.lowest_symbol = offset;
+
+        // Longer codes have lower values in the canonical Huffman code, so the lowest code of every length padded to 64
+        // bits decreases as the length grows, which lets the length of a code be found by comparing it with them.
+        let lengths: usize = (max_symbol_length + 1).checked_sub(item.min_symbol_length)?;
+        item.base64 = vec![0; lengths];
+        for index in (0..lengths.saturating_sub(1)).rev() {
+            let lowest: u64 = read_u16_le(bytes, item.lowest_symbol + 2 * index)? as u64;
+            let next_lowest: u64 = read_u16_le(bytes, item.lowest_symbol + 2 * (index + 1))? as u64;
+            item.base64[index] = (item.base64[index + 1] + lowest).checked_sub(next_lowest)? / 2;
+        }
+        for (index, base) in item.base64.iter_mut().enumerate() {
+            *base = base.checked_shl((64 - index - item.min_symbol_length) as u32).unwrap_or(0);
+        }
+        offset += 2 * lengths;
+
+        let symbols: usize = read_u16_le(bytes, offset)? as usize;
+        offset += 2;
+        item.btree = offset;
+        if bytes.len() < item.btree + 3 * symbols {
+            return None;
+        }
+
+        // Every symbol stands for a pair of symbols, down to the symbols which stand for a single value.
+        item.symbol_length = vec![0; symbols];
+        let mut visited: Vec<bool> = vec![false; symbols];
+        for symbol in 0..symbols {
+            if !visited[symbol] {
+                set_symbol_length(bytes, item, symbol, &mut visited)?;
+            }
+        }
+
+        Some(offset + 3 * symbols + (symbols & 1))
+    }
+
+    /// Reads the maps from the stored values of distance to zeroing tables to the real values, returning the offset after
+    /// them.
+    fn set_dtz_map(&mut self, files: usize, mut offset: usize) -> Option<usize> {
+        self.map = offset;
+
+        for file in 0..files {
+            let flags: u8 = self.items[0][file].flags;
+            if flags & FLAG_MAPPED == 0 {
+                continue;
+            }
+
+            for index in 0..4 {
+                if flags & FLAG_WIDE != 0 {
+                    offset += offset & 1;
+                    self.items[0][file].map_index[index] = (offset - self.map) / 2 + 1;
+                    offset += 2 * read_u16_le(&self.bytes, offset)? as usize + 2;
+                } else {
+                    self.items[0][file].map_index[index] = offset - self.map + 1;
+                    offset += *self.bytes.get(offset)? as usize + 1;
+                }
+            }
+        }
+
+        Some(offset + (offset & 1))
+    }
+
+    /// Gets the sub-table for the team to play and the file of the leading pawn.
+    fn item(&self, side: usize, file: usize) -> &PairsData {
+        &self.items[side % self.items.len()][if self.has_pawns { file } else { 0 }]
+    }
+
+    /// Gets the code of the first piece in the order of the table, which is a pawn of the leading team in tables with
+    /// pawns.
+    pub(super) fn leading_piece(&self) -> PieceCode {
+        self.items[0][0].pieces[0]
+    }
+
+    /// Probes the table for the position. For distance to zeroing tables, `wdl` is the win/draw/loss value of the
+    /// position, and `None` is returned when the table only stores the positions with the other team to play.
+    pub(super) fn probe(&self, position: &ProbePosition, wdl: Wdl) -> Option<i32> {
+        let encoding: &Encoding = encoding();
+
+        let symmetric_black: bool = !position.white_to_play && self.symmetric;
+        let flip: bool = symmetric_black || position.flipped;
+        let color_flip: u8 = if flip { 8 } else { 0 };
+        let square_flip: usize = if flip { 56 } else { 0 };
+        let side: usize = (flip as usize) ^ (!position.white_to_play as usize);
+
+        let mut squares: Vec<Square> = Vec::with_capacity(self.piece_count);
+        let mut pieces: Vec<PieceCode> = Vec::with_capacity(self.piece_count);
+        let mut lead_pawns_count: usize = 0;
+        let mut table_file: usize = 0;
+
+        let mut sorted: Vec<(Square, PieceCode)> = position.pieces.clone();
+        sorted.sort_unstable();
+
+        // The pawns of the leading team come first, with the one nearest to the edge and the first rank in front.
+        if self.has_pawns {
+            let lead_pawn: PieceCode = self.leading_piece() ^ color_flip;
+            for (square, piece) in sorted.iter() {
+                if *piece == lead_pawn {
+                    squares.push(square ^ square_flip);
+                    pieces.push(piece ^ color_flip);
+                }
+            }
+            lead_pawns_count = squares.len();
+
+            let leading: usize = (0..lead_pawns_count).max_by_key(|index| encoding.map_pawns[squares[*index]])?;
+            squares.swap(0, leading);
+
+            table_file = file(squares[0]);
+            if table_file > 3 {
+                table_file = file(squares[0] ^ 7);
+            }
+        }
+
+        // Distance to zeroing tables only store one team to play.
+        if self.kind == TableKind::Dtz {
+            let flags: u8 = self.item(side, table_file).flags;
+            if (flags & FLAG_STM) as usize != side && (self.has_pawns || !self.symmetric) {
+                return None;
+            }
+        }
+
+        for (square, piece) in sorted.iter() {
+            if self.has_pawns && *piece == self.leading_piece() ^ color_flip {
+                continue;
+            }
+            squares.push(square ^ square_flip);
+            pieces.push(piece ^ color_flip);
+        }
+        if squares.len() != self.piece_count {
+            return None;
+        }
+
+        let item: &PairsData = self.item(side, table_file);
+
+        // The pieces are put in the same order as the pieces of the table.
+        for index in lead_pawns_count..squares.len().saturating_sub(1) {
+            if let Some(found) = (index + 1..squares.len()).find(|other| item.pieces[index] == pieces[*other]) {
+                pieces.swap(index, found);
+                squares.swap(index, found);
+            }
+        }
+
+        // The leading piece is mirrored onto the files a to d.
+        if file(squares[0]) > 3 {
+            for square in squares.iter_mut() {
+                *square ^= 7;
+            }
+        }
+
+        let mut index: u64;
+        if self.has_pawns {
+            index = encoding.lead_pawn_index[lead_pawns_count][squares[0]];
+            squares[1..lead_pawns_count].sort_by_key(|square| encoding.map_pawns[*square]);
+            for (pawn, square) in squares.iter().enumerate().take(lead_pawns_count).skip(1) {
+                index += encoding.binomial[pawn][encoding.map_pawns[*square]];
+            }
+        } else {
+            // Without pawns, the leading piece is also mirrored onto the ranks 1 to 4 and below the a1-h8 diagonal.
+            if rank(squares[0]) > 3 {
+                for square in squares.iter_mut() {
+                    *square ^= 56;
+                }
+            }
+            for piece in 0..item.group_length[0] {
+                if off_diagonal(squares[piece]) == 0 {
+                    continue;
+                }
+                if off_diagonal(squares[piece]) > 0 {
+                    for square in squares[piece..].iter_mut() {
+                        *square = ((*square >> 3) | (*square << 3)) & 63;
+                    }
+                }
+                break;
+            }
+
+            index = if self.has_unique_pieces {
+                let adjust1: usize = (squares[1] > squares[0]) as usize;
+                let adjust2: usize = (squares[2] > squares[0]) as usize + (squares[2] > squares[1]) as usize;
+
+                if off_diagonal(squares[0]) != 0 {
+                    ((encoding.map_a1d1d4[squares[0]] * 63 + (squares[1] - adjust1)) * 62 + squares[2] - adjust2) as u64
+                } else if off_diagonal(squares[1]) != 0 {
+                    ((6 * 63 + rank(squares[0]) * 28 + encoding.map_b1h1h7[squares[1]]) * 62 + squares[2] - adjust2) as u64
+                } else if off_diagonal(squares[2]) != 0 {
+                    (6 * 63 * 62
+                        + 4 * 28 * 62
+                        + rank(squares[0]) * 7 * 28
+                        + (rank(squares[1]) - adjust1) * 28
+                        + encoding.map_b1h1h7[squares[2]]) as u64
+                } else {
+                    (6 * 63 * 62
+                        + 4 * 28 * 62
+                        + 4 * 7 * 28
+                        + rank(squares[0]) * 7 * 6
+                        + (rank(squares[1]) - adjust1) * 6
+                        + (rank(squares[2]) - adjust2)) as u64
+                }
+            } else {
+                encoding.map_kk[encoding.map_a1d1d4[squares[0]]][squares[1]] as u64
+            };
+        }
+        index *= item.group_index[0];
+
+        // The remaining groups are encoded by the squares of their pieces, skipping the squares taken by earlier groups.
+        let mut remaining_pawns: bool = self.has_pawns && self.pawn_count[1] > 0;
+        let mut start: usize = item.group_length[0];
+        let mut group: usize = 1;
+        while item.group_length[group] != 0 {
+            let length: usize = item.group_length[group];
+            squares[start..start + length].sort_unstable();
+
+            let mut n: u64 = 0;
+            for piece in 0..length {
+                let square: Square = squares[start + piece];
+                let adjust: usize = squares[..start].iter().filter(|other| square > **other).count();
+                n += encoding.binomial[piece + 1][square - adjust - if remaining_pawns { 8 } else { 0 }];
+            }
+
+            remaining_pawns = false;
+            index += n * item.group_index[group];
+            start += length;
+            group += 1;
+        }
+
+        let value: i32 = self.decompress(item, index)? as i32;
+        Some(self.map_value(table_file, value, wdl))
+    }
+
+    /// Converts a value stored in the table into a win/draw/loss value or a distance to zeroing in plies.
+    fn map_value(&self, table_file: usize, mut value: i32, wdl: Wdl) -> i32 {
+        if self.kind == TableKind::Wdl {
+            return value - 2;
+        }
+
+        let item: &PairsData = self.item(0, table_file);
+        let flags: u8 = item.flags;
+        if flags & FLAG_MAPPED != 0 {
+            let map_index: usize = item.map_index[match wdl {
+                Wdl::Loss => 1,
+                Wdl::BlessedLoss => 3,
+                Wdl::Draw => 0,
+                Wdl::CursedWin => 2,
+                Wdl::Win => 0,
+            }];
+            value = if flags & FLAG_WIDE != 0 {
+                read_u16_le(&self.bytes, self.map + 2 * (map_index + value as usize)).unwrap_or(0) as i32
+            } else {
+                self.bytes.get(self.map + map_index + value as usize).copied().unwrap_or(0) as i32
+            };
+        }
+
+        // Distances are stored in moves rather than plies unless the flags of the table say otherwise.
+        if (wdl == Wdl::Win && flags & FLAG_WIN_PLIES == 0)
+            || (wdl == Wdl::Loss && flags & FLAG_LOSS_PLIES == 0)
+            || wdl == Wdl::CursedWin
+            || wdl == Wdl::BlessedLoss
+        {
+            value *= 2;
+        }
+
+        value + 1
+    }
+
+    /// Decompresses the value stored at the index of a sub-table.
+    fn decompress(&self, item: &PairsData, index: u64) -> Option<u16> {
+        let bytes: &[u8] = &self.bytes;
+
+        if item.flags & FLAG_SINGLE_VALUE != 0 {
+            return Some(item.min_symbol_length as u16);
+        }
+
+        // The sparse index points to the block and the offset within it of the value in the middle of every span.
+        let k: usize = (index / item.span) as usize;
+        let mut block: usize = read_u32_le(bytes, item.sparse_index + 6 * k)? as usize;
+        let mut offset: i64 = read_u16_le(bytes, item.sparse_index + 6 * k + 4)? as i64;
+        offset += (index % item.span) as i64 - (item.span / 2) as i64;
+
+        let block_length = |block: usize| -> Option<i64> {
+            Some(read_u16_le(bytes, item.block_length + 2 * block)? as i64)
+        };
+        while offset < 0 {
+            block = block.checked_sub(1)?;
+            offset += block_length(block)? + 1;
+        }
+        while offset > block_length(block)? {
+            offset -= block_length(block)? + 1;
+            block += 1;
+        }
+
+        // The block is a sequence of canonical Huffman codes, which are read until the one holding the value is found.
+        let mut pointer: usize = item.data + block * item.block_size;
+        let mut buffer: u64 = read_u64_be(bytes, pointer)?;
+        pointer += 8;
+        let mut buffer_size: usize = 64;
+        let mut symbol: usize;
+
+        loop {
+            let mut length: usize = 0;
+            while buffer < *item.base64.get(length)? {
+                length += 1;
+            }
+
+            symbol = (buffer - item.base64[length])
+                .checked_shr((64 - length - item.min_symbol_length) as u32)
+                .unwrap_or(0) as usize;
+            symbol += read_u16_le(bytes, item.lowest_symbol + 2 * length)? as usize;
+
+            let symbol_length: i64 = *item.symbol_length.get(symbol)? as i64;
+            if offset < symbol_length + 1 {
+                break;
+            }
+
+            offset -= symbol_length + 1;
+            length += item.min_symbol_length;
+            buffer = buffer.checked_shl(length as u32).unwrap_or(0);
+            buffer_size -= length;
+
+            if buffer_size <= 32 {
+                buffer_size += 32;
+                buffer |= (read_u32_be(bytes, pointer)? as u64) << (64 - buffer_size);
+                pointer += 4;
+            }
+        }
+
+        // The symbol stands for a sequence of values made of its pair of symbols, which is walked down to the value.
+        while item.symbol_length[symbol] != 0 {
+            let (left, right) = symbol_pair(bytes, item, symbol)?;
+            let left_length: i64 = item.symbol_length[left] as i64;
+            if offset < left_length + 1 {
+                symbol = left;
+            } else {
+                offset -= left_length + 1;
+                symbol = right;
+            }
+        }
+
+        Some(symbol_pair(bytes, item, symbol)?.0 as u16)
+    }
+}
+
+/// Computes the number of values, minus one, that the symbol stands for, along with the symbols that it is made of.
+fn set_symbol_length(bytes: &[u8], item: &mut PairsData, symbol: usize, visited: &mut [bool]) -> Option<()> {
+    visited[symbol] = true;
+
+    let (left, right) = symbol_pair(bytes, item, symbol)?;
+    if right == 0xFFF {
+        item.symbol_length[symbol] = 0;
+        return Some(());
+    }
+
+    for child in [left, right] {
+        if !*visited.get(child)? {
+            set_symbol_length(bytes, item, child, visited)?;
+        }
+    }
+    item.symbol_length[symbol] = item.symbol_length[left] + item.symbol_length[right] + 1;
+
+    Some(())
+}
+
+/// Reads the two 12 bit symbols that the symbol stands for. Symbols which stand for a single value store it as the left
+/// symbol.
+fn symbol_pair(bytes: &[u8], item: &PairsData, symbol: usize) -> Option<(usize, usize)> {
+    let entry: &[u8] = bytes.get(item.btree + 3 * symbol..item.btree + 3 * symbol + 3)?;
+    let left: usize = (((entry[1] & 0xF) as usize) << 8) | entry[0] as usize;
+    let right: usize = ((entry[2] as usize) << 4) | (entry[1] >> 4) as usize;
+    Some((left, right))
+}
+
+fn read_u16_le(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().unwrap()))
+}
+
+fn read_u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().unwrap()))
+}
+
+fn read_u32_be(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(offset..offset + 4)?.try_into().unwrap()))
+}
+
+fn read_u64_be(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(bytes.get(offset..offset + 8)?.try_into().unwrap()))
+}
@@ -0,0 +1,296 @@
+use crate::board::{Board, Fen};
+use crate::moves::Move;
+use crate::piece::{PieceClass, Team};
+use std::sync::Arc;
+
+mod antichess;
+mod atomic;
+mod crazyhouse;
+mod horde;
+mod king_of_the_hill;
+mod racing_kings;
+mod three_check;
+
+pub use antichess::Antichess;
+pub use atomic::Atomic;
+pub use crazyhouse::{Bughouse, BughouseGame, Crazyhouse};
+pub use horde::Horde;
+pub use king_of_the_hill::KingOfTheHill;
+pub use racing_kings::RacingKings;
+pub use three_check::ThreeCheck;
+
+/// The FEN of the standard starting position.
+pub const STANDARD_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// The classes that a pawn can be promoted to when it reaches the last row in standard chess.
+pub const PROMOTION_CLASSES: [PieceClass; 4] = [
+    PieceClass::Queen,
+    PieceClass::Rook,
+    PieceClass::Bishop,
+    PieceClass::Knight,
+];
+
+/// The rules of a variant of chess. Every method has the standard rules as its default implementation, so a variant
+/// only overrides the rules that it changes.
+///
+/// A board holds its variant and asks it for the starting position, for the moves to remove from the moves that follow
+/// the standard movement rules, and for the end of the game.
+pub trait Variant: std::fmt::Debug + Send + Sync {
+    /// The name of the variant, as written in the `Variant` tag of PGN games.
+    fn name(&self) -> &'static str;
+
+    /// The FEN of the position that games of the variant start from.
+    fn starting_fen(&self) -> Fen {
+        Fen { state: STANDARD_FEN.to_string() }
+    }
+
+    /// Removes the moves that the variant does not allow from the moves of the team. The moves given already follow the
+    /// standard movement rules, including castling, and do not leave the king of the team in check unless the variant
+    /// does not require it.
+    fn filter_moves(&self, _board: &Board, _team: Team, _moves: &mut Vec<Move>) {}
+
+    /// The classes that a pawn can be promoted to when it reaches the last row.
+    fn promotion_classes(&self) -> &'static [PieceClass] {
+        &PROMOTION_CLASSES
+    }
+
+    /// Whether a pawn of the team standing on the row can move two squares forward. In standard chess this is only
+    /// allowed from the row that the pawns start the game on.
+    fn allows_double_step(&self, team: Team, row: usize) -> bool {
+        match team {
+            Team::White => row == 6,
+            Team::Black => row == 1,
+        }
+    }
+
+    /// Whether moves which leave the king of the moving team in check are removed before the moves are filtered by the
+    /// variant. Variants which answer `false` decide which moves are safe for the king in [`Variant::filter_moves`].
+    fn requires_king_safety(&self) -> bool {
+        true
+    }
+
+    /// Whether the teams can drop the pieces in their pockets onto the board instead of moving.
+    fn allows_drops(&self) -> bool {
+        false
+    }
+
+    /// Whether captured pieces change team and go into the pocket of the team which captured them.
+    fn pockets_captures(&self) -> bool {
+        false
+    }
+
+    /// The number of checks that a team wins the game by giving, in variants where checks are counted.
+    fn winning_checks(&self) -> Option<u8> {
+        None
+    }
+
+    /// Whether captures cause an explosion which removes the capturing piece along with every piece other than a pawn
+    /// around the coordinate of the capture.
+    fn explodes_captures(&self) -> bool {
+        false
+    }
+
+    /// Gets the team which won the game, if the game has been won. In standard chess this is the opponent of a team
+    /// which is checkmated.
+    fn winner(&self, board: &Board) -> Option<Team> {
+        board.is_checkmate().then(|| board.turn_to_play().opponent())
+    }
+
+    /// Checks if the game is drawn. In standard chess this is the case on a stalemate, once fifty moves have been made by
+    /// each team without a capture or a pawn move, or when neither team has the material to checkmate.
+    fn is_draw(&self, board: &Board) -> bool {
+        board.is_stalemate() || board.halfmove_clock() >= 100 || has_insufficient_material(board)
+    }
+}
+
+/// The rules of standard chess.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Standard;
+
+impl Variant for Standard {
+    fn name(&self) -> &'static str {
+        "Standard"
+    }
+}
+
+/// Gets the rules of the variant with the name, as given by [`Variant::name`].
+pub fn variant_by_name(name: &str) -> Option<Arc<dyn Variant>> {
+    let variants: [Arc<dyn Variant>; 9] = [
+        Arc::new(Standard),
+        Arc::new(Crazyhouse),
+        Arc::new(Bughouse),
+        Arc::new(Atomic),
+        Arc::new(ThreeCheck),
+        Arc::new(KingOfTheHill),
+        Arc::new(RacingKings),
+        Arc::new(Antichess),
+        Arc::new(Horde),
+    ];
+    variants.into_iter().find(|variant| variant.name() == name)
+}
+
+/// Checks if neither team can ever checkmate, which is when there are no pawns, rooks, or queens left and the only
+/// pieces besides the kings are either a single knight or any number of bishops which all stand on squares of one color.
+pub fn has_insufficient_material(board: &Board) -> bool {
+    let mut knights: usize = 0;
+    let mut bishop_square_colors: [bool; 2] = [false; 2];
+
+    for (row_index, row) in board.map().iter().enumerate() {
+        for (column_index, piece) in row.iter().enumerate() {
+            match piece.map(|piece| piece.class()) {
+                None | Some(PieceClass::King) => {}
+                Some(PieceClass::Bishop) => bishop_square_colors[(row_index + column_index) % 2] = true,
+                Some(PieceClass::Knight) => knights += 1,
+                Some(_) => return false,
+            }
+        }
+    }
+
+    match knights {
+        0 => !(bishop_square_colors[0] && bishop_square_colors[1]),
+        1 => !bishop_square_colors[0] && !bishop_square_colors[1],
+        _ => false,
+    }
+}
+
+/// Checks if the team has the material to checkmate the opponent by some series of legal moves, which decides whether
+/// running out of time loses the game for the opponent or draws it. A lone king can never checkmate, and a single
+/// knight or bishops which all stand on squares of one color can only checkmate when the opponent has a piece which
+/// can block the escape of its own king.
+pub fn has_mating_material(board: &Board, team: Team) -> bool {
+    let mut knights: usize = 0;
+    let mut bishop_square_colors: [bool; 2] = [false; 2];
+    let mut opponent_blockers: [bool; 2] = [false; 2];
+    let mut opponent_has_pieces: bool = false;
+
+    for (row_index, row) in board.map().iter().enumerate() {
+        for (column_index, piece) in row.iter().enumerate() {
+            let piece = match piece {
+                Some(piece) if piece.class() != PieceClass::King => piece,
+                _ => continue,
+            };
+            let square_color: usize = (row_index + column_index) % 2;
+            if piece.team() != team {
+                opponent_has_pieces = true;
+                match piece.class() {
+                    // A bishop can only block on the squares of its own color.
+                    PieceClass::Bishop => opponent_blockers[square_color] = true,
+                    _ => opponent_blockers = [true; 2],
+                }
+                continue;
+            }
+            match piece.class() {
+                PieceClass::Bishop => bishop_square_colors[square_color] = true,
+                PieceClass::Knight => knights += 1,
+                _ => return true,
+            }
+        }
+    }
+
+    match (knights, bishop_square_colors) {
+        (0, [false, false]) => false,
+        (1, [false, false]) => opponent_has_pieces,
+        (0, [true, false]) => opponent_blockers[1],
+        (0, [false, true]) => opponent_blockers[0],
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinate::Coordinate;
+
+    /// A variant where knights can not move, and black wins as soon as white has made a move.
+    #[derive(Debug)]
+    struct StillKnights;
+
+    impl Variant for StillKnights {
+        fn name(&self) -> &'static str {
+            "Still knights"
+        }
+
+        fn starting_fen(&self) -> Fen {
+            Fen { state: "4k3/8/8/8/8/8/4P3/1N2K3 w - - 0 1".to_string() }
+        }
+
+        fn filter_moves(&self, board: &Board, _team: Team, moves: &mut Vec<Move>) {
+            moves.retain(|chess_move| {
+                !matches!(board.get_piece(&chess_move.from), Some(piece) if matches!(piece.class(), PieceClass::Knight))
+            });
+        }
+
+        fn winner(&self, board: &Board) -> Option<Team> {
+            (board.turn_to_play() == Team::Black).then_some(Team::Black)
+        }
+    }
+
+    fn board_from_fen(fen: &str) -> Board {
+        Board::new_with_fen(Fen { state: fen.to_string() })
+    }
+
+    fn play(board: &mut Board, moves: &[&str]) {
+        for uci in moves {
+            board.make_move(&board.parse_uci(uci).unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn boards_ask_their_variant_for_the_rules() {
+        let mut board: Board = Board::new_with_variant(StillKnights);
+        assert_eq!(board.variant().name(), "Still knights");
+        assert_eq!(board.fen().state, StillKnights.starting_fen().state);
+
+        // The king has four moves and the pawn has two, while the knight is not allowed to move at all.
+        let moves: Vec<Move> = board.legal_moves();
+        assert_eq!(moves.len(), 6);
+        let knight_move: Move = Move::new(Coordinate::try_from("b1").unwrap(), Coordinate::try_from("c3").unwrap());
+        assert!(!moves.contains(&knight_move));
+        assert!(board.make_move(&knight_move).is_err());
+
+        assert_eq!(board.winner(), None);
+        play(&mut board, &["e2e4"]);
+        assert_eq!(board.winner(), Some(Team::Black));
+        assert!(!board.is_draw());
+    }
+
+    #[test]
+    fn standard_games_are_won_by_checkmate() {
+        let mut board: Board = Board::new();
+        assert_eq!(board.variant().name(), "Standard");
+        assert_eq!(board.fen().state, STANDARD_FEN);
+
+        play(&mut board, &["f2f3", "e7e5", "g2g4"]);
+        assert_eq!(board.winner(), None);
+        play(&mut board, &["d8h4"]);
+        assert_eq!(board.winner(), Some(Team::Black));
+        assert!(!board.is_draw());
+    }
+
+    #[test]
+    fn standard_games_are_drawn_by_stalemate_and_the_fifty_move_rule() {
+        let board: Board = board_from_fen("k7/8/1Q6/8/8/8/8/7K b - - 0 1");
+        assert!(board.is_stalemate());
+        assert_eq!(board.winner(), None);
+        assert!(board.is_draw());
+
+        assert!(board_from_fen("k7/8/8/8/8/8/8/R6K w - - 100 80").is_draw());
+        assert!(!board_from_fen("k7/8/8/8/8/8/8/R6K w - - 99 80").is_draw());
+    }
+
+    #[test]
+    fn only_lone_minor_pieces_are_insufficient_material() {
+        // The bishops on c1 and e3 stand on squares of the same color, while the one on f1 does not.
+        let insufficient: [&str; 4] =
+            ["4k3/8/8/8/8/8/8/4K3", "4k3/8/8/8/8/8/8/2B1K3", "4k3/8/8/8/8/4B3/8/2B1K3", "4k3/8/8/8/8/8/8/1N2K3"];
+        for fen in insufficient {
+            assert!(has_insufficient_material(&board_from_fen(fen)), "{}", fen);
+        }
+        let sufficient: [&str; 4] =
+            ["4k3/8/8/8/8/8/8/2B1KB2", "4k3/8/8/8/8/8/8/1NN1K3", "4k3/8/8/8/8/8/8/1N2KB2", "4k3/8/8/8/8/8/P7/4K3"];
+        for fen in sufficient {
+            assert!(!has_insufficient_material(&board_from_fen(fen)), "{}", fen);
+        }
+        assert!(board_from_fen("4k3/8/8/8/8/8/8/4K3").is_draw());
+    }
+}
@@ -0,0 +1,120 @@
+use super::{Variant, PROMOTION_CLASSES};
+use crate::board::{Board, Fen};
+use crate::moves::Move;
+use crate::piece::{PieceClass, Team};
+
+/// The FEN of the starting position of Antichess, which is the standard one without castling rights.
+const ANTICHESS_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1";
+
+/// The classes that a pawn can be promoted to in Antichess, where the king is an ordinary piece.
+const ANTICHESS_PROMOTION_CLASSES: [PieceClass; 5] = [
+    PROMOTION_CLASSES[0],
+    PROMOTION_CLASSES[1],
+    PROMOTION_CLASSES[2],
+    PROMOTION_CLASSES[3],
+    PieceClass::King,
+];
+
+/// Antichess, also known as losing chess, where the team which loses all of its pieces or has no legal moves wins.
+/// Capturing is compulsory, and the king is an ordinary piece which can be captured, left in check, and promoted to.
+/// There is no castling.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Antichess;
+
+impl Variant for Antichess {
+    fn name(&self) -> &'static str {
+        "Antichess"
+    }
+
+    fn starting_fen(&self) -> Fen {
+        Fen { state: ANTICHESS_FEN.to_string() }
+    }
+
+    fn promotion_classes(&self) -> &'static [PieceClass] {
+        &ANTICHESS_PROMOTION_CLASSES
+    }
+
+    fn requires_king_safety(&self) -> bool {
+        false
+    }
+
+    /// Removes castling, and removes every move which does not capture when the team has a capture.
+    fn filter_moves(&self, board: &Board, _team: Team, moves: &mut Vec<Move>) {
+        moves.retain(|chess_move| board.castling_side(chess_move).is_none());
+        if moves.iter().any(|chess_move| board.captured_coordinate(chess_move).is_some()) {
+            moves.retain(|chess_move| board.captured_coordinate(chess_move).is_some());
+        }
+    }
+
+    /// The team to play wins when it has no legal moves, which includes having no pieces left.
+    fn winner(&self, board: &Board) -> Option<Team> {
+        board.legal_moves().is_empty().then(|| board.turn_to_play())
+    }
+
+    fn is_draw(&self, board: &Board) -> bool {
+        self.winner(board).is_none() && board.halfmove_clock() >= 100
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinate::Coordinate;
+
+    fn antichess_board(fen: &str) -> Board {
+        let mut board: Board = Board::new_with_fen(Fen { state: fen.to_string() });
+        board.set_variant(Antichess);
+        board
+    }
+
+    fn chess_move(from: &str, to: &str) -> Move {
+        Move::new(Coordinate::try_from(from).unwrap(), Coordinate::try_from(to).unwrap())
+    }
+
+    #[test]
+    fn captures_are_compulsory() {
+        let board: Board = antichess_board("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1");
+
+        assert_eq!(board.legal_moves(), vec![chess_move("e4", "d5")]);
+    }
+
+    #[test]
+    fn kings_can_be_left_in_check_and_captured() {
+        let board: Board = antichess_board("8/8/8/8/8/8/3q4/4K3 w - - 0 1");
+        assert_eq!(board.legal_moves(), vec![chess_move("e1", "d2")]);
+
+        let board: Board = antichess_board("8/8/8/8/8/8/8/r3K3 w - - 0 1");
+        assert!(board.legal_moves().contains(&chess_move("e1", "d1")));
+    }
+
+    #[test]
+    fn there_is_no_castling() {
+        let board: Board = antichess_board("r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1");
+
+        assert!(!board.legal_moves().contains(&chess_move("e1", "g1")));
+    }
+
+    #[test]
+    fn pawns_can_be_promoted_to_kings() {
+        let board: Board = antichess_board("8/P7/8/8/8/8/8/7k w - - 0 1");
+
+        assert!(board.legal_moves().contains(&Move::new_with_promotion(
+            Coordinate::try_from("a7").unwrap(),
+            Coordinate::try_from("a8").unwrap(),
+            PieceClass::King,
+        )));
+    }
+
+    #[test]
+    fn teams_without_moves_win() {
+        // White is stalemated, which wins instead of drawing.
+        let board: Board = antichess_board("8/8/8/8/8/p7/P7/8 w - - 0 1");
+        assert_eq!(board.winner(), Some(Team::White));
+        assert!(!board.is_draw());
+
+        let board: Board = antichess_board("8/8/8/8/8/8/8/k7 w - - 0 1");
+        assert_eq!(board.winner(), Some(Team::White));
+
+        assert_eq!(antichess_board("8/8/8/8/8/8/P7/k7 w - - 0 1").winner(), None);
+    }
+}
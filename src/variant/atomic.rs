@@ -0,0 +1,142 @@
+use super::Variant;
+use crate::board::Board;
+use crate::moves::Move;
+use crate::piece::{PieceClass, Team};
+
+/// Atomic chess, where every capture explodes the capturing piece along with every piece other than a pawn around the
+/// coordinate of the capture. Kings can not capture, and a team whose king is exploded loses the game.
+///
+/// Kings standing next to each other can not be checked, since capturing one of them would explode the other one too.
+/// A move is therefore legal when it keeps the king of the moving team on the board, and either explodes the king of
+/// the opponent, leaves the two kings next to each other, or leaves the king of the moving team out of check.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Atomic;
+
+impl Variant for Atomic {
+    fn name(&self) -> &'static str {
+        "Atomic"
+    }
+
+    fn requires_king_safety(&self) -> bool {
+        false
+    }
+
+    fn explodes_captures(&self) -> bool {
+        true
+    }
+
+    fn filter_moves(&self, board: &Board, team: Team, moves: &mut Vec<Move>) {
+        moves.retain(|chess_move| {
+            let is_capture: bool = board.captured_coordinate(chess_move).is_some();
+            if is_capture && matches!(board.get_piece(&chess_move.from), Some(piece) if matches!(piece.class(), PieceClass::King))
+            {
+                return false;
+            }
+
+            let mut next_board: Board = board.clone();
+            next_board.apply_move(chess_move);
+            next_board.king_coordinate(team).is_some()
+                && (next_board.king_coordinate(team.opponent()).is_none() || !is_in_check(&next_board, team))
+        });
+    }
+
+    /// A team loses when its king has been exploded or when it is checkmated.
+    fn winner(&self, board: &Board) -> Option<Team> {
+        for team in [Team::White, Team::Black] {
+            if board.king_coordinate(team).is_none() {
+                return Some(team.opponent());
+            }
+        }
+
+        let team: Team = board.turn_to_play();
+        (board.legal_moves().is_empty() && is_in_check(board, team)).then_some(team.opponent())
+    }
+
+    /// Besides the fifty move rule, the game is only drawn when the team to play has no legal moves without being in
+    /// check, since even a lone minor piece can win by exploding the king.
+    fn is_draw(&self, board: &Board) -> bool {
+        if self.winner(board).is_some() {
+            return false;
+        }
+        board.halfmove_clock() >= 100
+            || (board.legal_moves().is_empty() && !is_in_check(board, board.turn_to_play()))
+    }
+}
+
+/// Checks if the king of the team is attacked while not standing next to the king of the opponent.
+fn is_in_check(board: &Board, team: Team) -> bool {
+    match (board.king_coordinate(team), board.king_coordinate(team.opponent())) {
+        (Some(king), Some(other_king)) => {
+            let adjacent: bool = king.row().abs_diff(other_king.row()) <= 1 && king.column().abs_diff(other_king.column()) <= 1;
+            !adjacent && board.is_in_check(team)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+    use crate::coordinate::Coordinate;
+
+    fn atomic_board(fen: &str) -> Board {
+        let mut board: Board = Board::new_with_fen(Fen { state: fen.to_string() });
+        board.set_variant(Atomic);
+        board
+    }
+
+    fn chess_move(from: &str, to: &str) -> Move {
+        Move::new(Coordinate::try_from(from).unwrap(), Coordinate::try_from(to).unwrap())
+    }
+
+    #[test]
+    fn captures_explode_every_piece_around_them_except_pawns() {
+        // The capturing pawn, the captured knight, and the pieces of both teams next to d5 are removed, but not the
+        // pawn on e5 or the bishop on f5 which is two columns away.
+        let mut board: Board = atomic_board("4k3/8/2b5/3npb2/2N1P3/8/8/4K3 w - - 0 1");
+        board.make_move(&chess_move("e4", "d5")).unwrap();
+
+        assert_eq!(board.fen().board_pieces_state(), "4k3/8/8/4pb2/8/8/8/4K3");
+    }
+
+    #[test]
+    fn kings_can_not_capture() {
+        let board: Board = atomic_board("4k3/8/8/8/8/8/4p3/4K3 w - - 0 1");
+        let moves: Vec<Move> = board.legal_moves();
+
+        assert!(!moves.contains(&chess_move("e1", "e2")));
+        assert!(moves.contains(&chess_move("e1", "d2")));
+    }
+
+    #[test]
+    fn exploding_the_king_of_the_opponent_wins() {
+        let mut board: Board = atomic_board("4k3/4p3/8/8/8/8/8/4QK2 w - - 0 1");
+        board.make_move(&chess_move("e1", "e7")).unwrap();
+
+        assert_eq!(board.king_coordinate(Team::Black), None);
+        assert_eq!(board.winner(), Some(Team::White));
+        assert!(!board.is_draw());
+    }
+
+    #[test]
+    fn moves_which_explode_the_own_king_are_illegal() {
+        // Capturing the knight on e2 would explode the white king on e1 along with it.
+        let board: Board = atomic_board("4k3/8/8/8/8/3B4/4n3/4K3 w - - 0 1");
+        let moves: Vec<Move> = board.legal_moves();
+
+        assert!(!moves.contains(&chess_move("d3", "e2")));
+        assert!(moves.contains(&chess_move("d3", "c2")));
+    }
+
+    #[test]
+    fn kings_next_to_each_other_are_not_in_check() {
+        // The rook on h3 attacks the white king, but capturing it would explode the black king as well.
+        let board: Board = atomic_board("8/8/8/8/8/3kK2r/8/R7 w - - 0 1");
+        let moves: Vec<Move> = board.legal_moves();
+
+        assert!(moves.contains(&chess_move("a1", "a2")));
+        assert!(!moves.contains(&chess_move("e3", "f3")));
+        assert_eq!(board.winner(), None);
+    }
+}
@@ -0,0 +1,111 @@
+use super::Variant;
+use crate::board::{Board, BoardError, Fen};
+use crate::moves::Move;
+use crate::piece::{Piece, PieceClass, Team};
+
+/// The FEN of the starting position of Crazyhouse and Bughouse, which is the standard one with empty pockets.
+const POCKET_STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1";
+
+/// Crazyhouse, where captured pieces change team and go into the pocket of the team which captured them, and can be
+/// dropped back onto the board instead of moving.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Crazyhouse;
+
+impl Variant for Crazyhouse {
+    fn name(&self) -> &'static str {
+        "Crazyhouse"
+    }
+
+    fn starting_fen(&self) -> Fen {
+        Fen { state: POCKET_STARTING_FEN.to_string() }
+    }
+
+    fn allows_drops(&self) -> bool {
+        true
+    }
+
+    fn pockets_captures(&self) -> bool {
+        true
+    }
+
+    /// Captured pieces can always come back onto the board, so there is no draw by insufficient material.
+    fn is_draw(&self, board: &Board) -> bool {
+        board.is_stalemate() || board.halfmove_clock() >= 100
+    }
+}
+
+/// The rules of one of the two boards of Bughouse. Pieces are dropped from the pockets as in Crazyhouse, but captured
+/// pieces go to the partner of the team which captured them on the other board, which is handled by [`BughouseGame`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bughouse;
+
+impl Variant for Bughouse {
+    fn name(&self) -> &'static str {
+        "Bughouse"
+    }
+
+    fn starting_fen(&self) -> Fen {
+        Fen { state: POCKET_STARTING_FEN.to_string() }
+    }
+
+    fn allows_drops(&self) -> bool {
+        true
+    }
+
+    fn is_draw(&self, board: &Board) -> bool {
+        board.is_stalemate() || board.halfmove_clock() >= 100
+    }
+}
+
+/// A game of Bughouse, played by two teams of two on two boards. The player of white on the first board is the partner
+/// of the player of black on the second board, so a piece captured on one board goes into the pocket of the team it
+/// belonged to on the other board, where it is dropped by the partner of the player who captured it.
+#[derive(Debug, Clone)]
+pub struct BughouseGame {
+    boards: [Board; 2],
+}
+
+impl BughouseGame {
+    pub fn new() -> Self {
+        Self {
+            boards: [Board::new_with_variant(Bughouse), Board::new_with_variant(Bughouse)],
+        }
+    }
+
+    /// Gets the board with the index, which is either 0 or 1.
+    pub fn board(&self, index: usize) -> &Board {
+        &self.boards[index]
+    }
+
+    /// Performs a move on the board with the index, and passes the piece that it captures to the other board.
+    pub fn make_move(&mut self, index: usize, chess_move: &Move) -> Result<(), BoardError> {
+        let board: &Board = &self.boards[index];
+        let captured_piece: Option<Piece> = board
+            .captured_coordinate(chess_move)
+            .and_then(|coordinate| board.get_piece(&coordinate));
+
+        self.boards[index].make_move(chess_move)?;
+
+        if let Some(captured_piece) = captured_piece {
+            let class: PieceClass = if captured_piece.is_promoted() {
+                PieceClass::Pawn
+            } else {
+                captured_piece.class()
+            };
+            self.boards[1 - index].add_to_pocket(captured_piece.team(), class);
+        }
+        Ok(())
+    }
+
+    /// Gets the index of the board on which the game has been won along with the team which won on it. The partner of
+    /// that team on the other board wins as well.
+    pub fn winner(&self) -> Option<(usize, Team)> {
+        (0..2).find_map(|index| self.boards[index].winner().map(|team| (index, team)))
+    }
+}
+
+impl Default for BughouseGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
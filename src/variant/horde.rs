@@ -0,0 +1,98 @@
+use super::Variant;
+use crate::board::{Board, Fen};
+use crate::piece::Team;
+
+/// The FEN of the starting position of Horde, where white has 36 pawns and no king.
+const HORDE_FEN: &str = "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1";
+
+/// Horde, where white plays a horde of pawns without a king against the standard pieces of black. White wins by
+/// checkmating black, and black wins by capturing all of the pieces of white. White pawns on the first row can move
+/// two squares forward just like the ones on the second row.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Horde;
+
+impl Variant for Horde {
+    fn name(&self) -> &'static str {
+        "Horde"
+    }
+
+    fn starting_fen(&self) -> Fen {
+        Fen { state: HORDE_FEN.to_string() }
+    }
+
+    fn allows_double_step(&self, team: Team, row: usize) -> bool {
+        match team {
+            Team::White => row == 6 || row == 7,
+            Team::Black => row == 1,
+        }
+    }
+
+    fn winner(&self, board: &Board) -> Option<Team> {
+        if board.team_occupancy(Team::White).is_empty() {
+            return Some(Team::Black);
+        }
+        board.is_checkmate().then(|| board.turn_to_play().opponent())
+    }
+
+    /// There is no draw by insufficient material, only stalemates and the fifty move rule.
+    fn is_draw(&self, board: &Board) -> bool {
+        self.winner(board).is_none() && (board.legal_moves().is_empty() || board.halfmove_clock() >= 100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinate::Coordinate;
+    use crate::moves::Move;
+
+    fn horde_board(fen: &str) -> Board {
+        let mut board: Board = Board::new_with_fen(Fen { state: fen.to_string() });
+        board.set_variant(Horde);
+        board
+    }
+
+    fn double_step(board: &mut Board, from: &str, to: &str) {
+        let chess_move: Move = Move::new(Coordinate::try_from(from).unwrap(), Coordinate::try_from(to).unwrap());
+        board.make_move(&chess_move).unwrap();
+    }
+
+    #[test]
+    fn pawns_on_the_first_row_move_two_squares_without_allowing_en_passant() {
+        let mut board: Board = horde_board("4k3/8/8/8/8/1p6/8/P7 w - - 0 1");
+        double_step(&mut board, "a1", "a3");
+
+        assert_eq!(board.en_passant(), None);
+    }
+
+    #[test]
+    fn pawns_on_the_second_row_allow_en_passant() {
+        let mut board: Board = horde_board("4k3/8/8/8/1p6/8/P7/8 w - - 0 1");
+        double_step(&mut board, "a2", "a4");
+
+        assert_eq!(board.en_passant(), Some(Coordinate::try_from("a3").unwrap()));
+    }
+
+    #[test]
+    fn black_wins_by_capturing_every_white_pawn() {
+        let mut board: Board = horde_board("4k3/8/8/8/8/8/1p6/P7 b - - 0 1");
+        assert_eq!(board.winner(), None);
+
+        board.make_move(&board.parse_uci("b2a1q").unwrap()).unwrap();
+        assert_eq!(board.winner(), Some(Team::Black));
+    }
+
+    #[test]
+    fn white_wins_by_checkmate_without_a_king() {
+        let board: Board = horde_board("k7/8/8/8/8/8/8/RR6 b - - 0 1");
+        assert_eq!(board.winner(), Some(Team::White));
+
+        let board: Board = horde_board("k7/8/8/8/8/8/8/R1R5 b - - 0 1");
+        assert_eq!(board.winner(), None);
+        assert!(!board.is_draw());
+
+        let board: Board = horde_board("k7/P7/1PP5/8/8/8/8/8 b - - 0 1");
+        assert_eq!(board.winner(), None);
+        assert!(board.is_draw());
+    }
+}
@@ -0,0 +1,74 @@
+use super::Variant;
+use crate::board::Board;
+use crate::coordinate::Coordinate;
+use crate::piece::Team;
+
+/// The four coordinates in the middle of the board, which are d4, e4, d5, and e5.
+const HILL: [(usize, usize); 4] = [(4, 3), (4, 4), (3, 3), (3, 4)];
+
+/// King of the Hill, where a team also wins by bringing its king to one of the four coordinates in the middle of the
+/// board.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KingOfTheHill;
+
+impl Variant for KingOfTheHill {
+    fn name(&self) -> &'static str {
+        "King of the Hill"
+    }
+
+    fn winner(&self, board: &Board) -> Option<Team> {
+        [Team::White, Team::Black]
+            .into_iter()
+            .find(|team| {
+                board.king_coordinate(*team).is_some_and(|king_coordinate| {
+                    HILL.into_iter().any(|hill| Coordinate::try_from(hill).unwrap() == king_coordinate)
+                })
+            })
+            .or_else(|| board.is_checkmate().then(|| board.turn_to_play().opponent()))
+    }
+
+    /// A lone king can still win by reaching the middle of the board, so there is no draw by insufficient material.
+    fn is_draw(&self, board: &Board) -> bool {
+        self.winner(board).is_none() && (board.is_stalemate() || board.halfmove_clock() >= 100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    fn king_of_the_hill_board(fen: &str) -> Board {
+        let mut board: Board = Board::new_with_fen(Fen { state: fen.to_string() });
+        board.set_variant(KingOfTheHill);
+        board
+    }
+
+    #[test]
+    fn kings_on_the_four_center_squares_win() {
+        for fen in ["k7/8/8/8/3K4/8/8/8 b", "k7/8/8/8/4K3/8/8/8 b", "k7/8/8/3K4/8/8/8/8 b", "k7/8/8/4K3/8/8/8/8 b"] {
+            assert_eq!(king_of_the_hill_board(fen).winner(), Some(Team::White), "{}", fen);
+        }
+        assert_eq!(king_of_the_hill_board("K7/8/8/8/4k3/8/8/8 w").winner(), Some(Team::Black));
+    }
+
+    #[test]
+    fn kings_next_to_the_center_have_not_won() {
+        for fen in ["k7/8/8/8/2K5/8/8/8 b", "k7/8/8/8/8/4K3/8/8 b", "k7/8/5K2/8/8/8/8/8 b", "k7/8/8/5K2/8/8/8/8 b"] {
+            assert_eq!(king_of_the_hill_board(fen).winner(), None, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn reaching_the_center_wins_the_game() {
+        let mut board: Board = king_of_the_hill_board("k7/8/8/8/2K5/8/8/8 w - - 0 1");
+        board.make_move(&board.parse_uci("c4d5").unwrap()).unwrap();
+
+        assert_eq!(board.winner(), Some(Team::White));
+    }
+
+    #[test]
+    fn lone_kings_are_not_drawn_by_insufficient_material() {
+        assert!(!king_of_the_hill_board("k7/8/8/8/8/8/8/7K w - - 0 1").is_draw());
+    }
+}
@@ -0,0 +1,123 @@
+use super::Variant;
+use crate::board::{Board, Fen};
+use crate::moves::Move;
+use crate::piece::{PieceClass, Team};
+
+/// The FEN of the starting position of Racing Kings, where both teams start on the first two rows next to each other.
+const RACING_KINGS_FEN: &str = "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1";
+
+/// The row that both kings race towards, which is the eighth row.
+const GOAL_ROW: usize = 0;
+
+/// Racing Kings, where the first team to bring its king to the eighth row wins and giving check is not allowed. Black
+/// moves second, so when the white king reaches the eighth row black still gets a move, and the game is drawn if the
+/// black king reaches it as well.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RacingKings;
+
+impl Variant for RacingKings {
+    fn name(&self) -> &'static str {
+        "Racing Kings"
+    }
+
+    fn starting_fen(&self) -> Fen {
+        Fen { state: RACING_KINGS_FEN.to_string() }
+    }
+
+    /// Removes the moves which give check.
+    fn filter_moves(&self, board: &Board, team: Team, moves: &mut Vec<Move>) {
+        moves.retain(|chess_move| {
+            let mut next_board: Board = board.clone();
+            next_board.apply_move(chess_move);
+            !next_board.is_in_check(team.opponent())
+        });
+    }
+
+    fn winner(&self, board: &Board) -> Option<Team> {
+        match (has_arrived(board, Team::White), has_arrived(board, Team::Black)) {
+            (true, true) | (false, false) => None,
+            (false, true) => Some(Team::Black),
+            (true, false) => {
+                // Black can still draw by bringing its king to the eighth row on the move right after white did.
+                let black_can_arrive: bool = board.turn_to_play() == Team::Black
+                    && board.legal_moves().iter().any(|chess_move| {
+                        chess_move.to.row() == GOAL_ROW
+                            && matches!(board.get_piece(&chess_move.from), Some(piece) if matches!(piece.class(), PieceClass::King))
+                    });
+                (!black_can_arrive).then_some(Team::White)
+            }
+        }
+    }
+
+    fn is_draw(&self, board: &Board) -> bool {
+        if has_arrived(board, Team::White) && has_arrived(board, Team::Black) {
+            return true;
+        }
+        self.winner(board).is_none()
+            && !has_arrived(board, Team::White)
+            && (board.legal_moves().is_empty() || board.halfmove_clock() >= 100)
+    }
+}
+
+/// Checks if the king of the team has reached the eighth row.
+fn has_arrived(board: &Board, team: Team) -> bool {
+    board.king_coordinate(team).is_some_and(|king_coordinate| king_coordinate.row() == GOAL_ROW)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinate::Coordinate;
+
+    fn racing_kings_board(fen: &str) -> Board {
+        let mut board: Board = Board::new_with_fen(Fen { state: fen.to_string() });
+        board.set_variant(RacingKings);
+        board
+    }
+
+    fn chess_move(from: &str, to: &str) -> Move {
+        Move::new(Coordinate::try_from(from).unwrap(), Coordinate::try_from(to).unwrap())
+    }
+
+    fn play(board: &mut Board, uci: &str) {
+        board.make_move(&board.parse_uci(uci).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn games_start_with_twenty_one_moves() {
+        assert_eq!(Board::new_with_variant(RacingKings).legal_moves().len(), 21);
+    }
+
+    #[test]
+    fn moves_which_give_check_are_illegal() {
+        let board: Board = racing_kings_board("8/8/8/8/8/k7/8/1R5K w - - 0 1");
+        let moves: Vec<Move> = board.legal_moves();
+
+        assert!(!moves.contains(&chess_move("b1", "a1")));
+        assert!(!moves.contains(&chess_move("b1", "b3")));
+        assert!(moves.contains(&chess_move("b1", "b2")));
+    }
+
+    #[test]
+    fn the_first_king_on_the_eighth_row_wins() {
+        let mut board: Board = racing_kings_board("8/7K/8/8/8/8/k7/8 w - - 0 1");
+        play(&mut board, "h7h8");
+        assert_eq!(board.winner(), Some(Team::White));
+
+        let mut board: Board = racing_kings_board("8/K7/8/8/8/8/8/7k b - - 0 1");
+        play(&mut board, "h1h2");
+        assert_eq!(board.winner(), None);
+    }
+
+    #[test]
+    fn black_draws_by_reaching_the_eighth_row_right_after_white() {
+        let mut board: Board = racing_kings_board("8/k6K/8/8/8/8/8/8 w - - 0 1");
+        play(&mut board, "h7h8");
+        assert_eq!(board.winner(), None);
+        assert!(!board.is_draw());
+
+        play(&mut board, "a7a8");
+        assert_eq!(board.winner(), None);
+        assert!(board.is_draw());
+    }
+}
@@ -0,0 +1,87 @@
+use super::Variant;
+use crate::board::Board;
+use crate::piece::Team;
+
+/// Three-check, where a team also wins by giving check three times. The checks that both teams still have to give are
+/// written in the FEN before the clocks, such as `3+3` at the start of the game.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreeCheck;
+
+impl Variant for ThreeCheck {
+    fn name(&self) -> &'static str {
+        "Three-check"
+    }
+
+    fn winning_checks(&self) -> Option<u8> {
+        Some(3)
+    }
+
+    fn winner(&self, board: &Board) -> Option<Team> {
+        [Team::White, Team::Black]
+            .into_iter()
+            .find(|team| board.checks_given(*team) >= 3)
+            .or_else(|| board.is_checkmate().then(|| board.turn_to_play().opponent()))
+    }
+
+    /// Any piece can give check, so there is no draw by insufficient material.
+    fn is_draw(&self, board: &Board) -> bool {
+        self.winner(board).is_none() && (board.is_stalemate() || board.halfmove_clock() >= 100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Fen;
+
+    fn three_check_board(fen: &str) -> Board {
+        let mut board: Board = Board::new_with_fen(Fen { state: fen.to_string() });
+        board.set_variant(ThreeCheck);
+        board
+    }
+
+    fn play(board: &mut Board, uci: &str) {
+        board.make_move(&board.parse_uci(uci).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn remaining_checks_are_written_before_the_clocks() {
+        let mut board: Board = Board::new_with_variant(ThreeCheck);
+        assert_eq!(board.fen().state, "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1");
+
+        for uci in ["e2e4", "f7f6", "d1h5"] {
+            play(&mut board, uci);
+        }
+        assert_eq!(board.checks_given(Team::White), 1);
+        assert_eq!(board.checks_given(Team::Black), 0);
+        assert!(board.fen().state.ends_with(" KQkq - 2+3 1 2"), "{}", board.fen().state);
+    }
+
+    #[test]
+    fn checks_given_are_read_in_both_notations() {
+        let remaining: Board = three_check_board("4k3/8/8/8/8/8/8/4K2R w K - 1+2 0 1");
+        let given: Board = three_check_board("4k3/8/8/8/8/8/8/4K2R w K - 0 1 +2+1");
+
+        for board in [remaining, given] {
+            assert_eq!(board.checks_given(Team::White), 2);
+            assert_eq!(board.checks_given(Team::Black), 1);
+            assert_eq!(board.fen().state, "4k3/8/8/8/8/8/8/4K2R w K - 1+2 0 1");
+        }
+    }
+
+    #[test]
+    fn the_third_check_wins() {
+        let mut board: Board = three_check_board("4k3/8/8/8/8/8/8/4K2R w - - 1+3 0 1");
+        assert_eq!(board.winner(), None);
+
+        play(&mut board, "h1h8");
+        assert_eq!(board.checks_given(Team::White), 3);
+        assert_eq!(board.winner(), Some(Team::White));
+        assert!(!board.is_draw());
+    }
+
+    #[test]
+    fn lone_kings_are_not_drawn_by_insufficient_material() {
+        assert!(!three_check_board("4k3/8/8/8/8/8/8/2B1K3 w - - 3+3 0 1").is_draw());
+    }
+}